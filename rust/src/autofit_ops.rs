@@ -0,0 +1,315 @@
+// Auto-fit of column widths and row heights.
+//
+// Excel fits columns by rendering each cell with its real font. Here the text a
+// cell displays (its value through its number format) is measured with the
+// bundled metrics in `font_metrics`, which lands within a pixel or two of
+// Excel for the common fonts.
+
+use std::collections::{BTreeMap, HashSet};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use umya_spreadsheet::structs::{Cell, CellRawValue};
+use umya_spreadsheet::{Spreadsheet, Worksheet};
+
+use crate::font_metrics::FontMetrics;
+use crate::number_format::{format_general, format_value};
+use crate::utils::{a1_range_to_bounds, col_letter_to_u32};
+
+/// Pixels of the widest digit in Excel's default font (Calibri 11 and Arial 10
+/// both draw digits 7 px wide), the unit column widths are counted in.
+const DIGIT_PX: f64 = 7.0;
+
+/// Margin Excel keeps around cell text: 2 px each side and the gridline.
+const PADDING_PX: f64 = 5.0;
+
+/// Pixels of one indent level.
+const INDENT_PX: f64 = 9.0;
+
+const PX_PER_PT: f64 = 96.0 / 72.0;
+
+/// Width of a column without one of its own: 8.43 characters plus padding.
+const DEFAULT_WIDTH: f64 = 9.140625;
+
+/// Height of a row of Calibri 11, the least a fitted row gets.
+const DEFAULT_HEIGHT: f64 = 15.0;
+
+/// Widest column Excel allows, in characters.
+const MAX_WIDTH: f64 = 255.0;
+
+/// Tallest row Excel allows, in points.
+const MAX_HEIGHT: f64 = 409.0;
+
+/// Text a cell displays: its value rendered through its number format.
+fn display_text(cell: &Cell, date1904: bool) -> String {
+    if let Some(value) = cell.get_value_number() {
+        return match cell.get_style().get_number_format() {
+            Some(nf) if !matches!(nf.get_format_code(), "" | "General") => {
+                format_value(value, nf.get_format_code(), date1904)
+            }
+            _ => format_general(value),
+        };
+    }
+    match cell.get_raw_value() {
+        CellRawValue::Bool(true) => "TRUE".to_string(),
+        CellRawValue::Bool(false) => "FALSE".to_string(),
+        _ => cell
+            .get_value()
+            .into_owned()
+            .replace("\r\n", "\n")
+            .replace('\r', "\n"),
+    }
+}
+
+/// A cell's displayed text with what decides how large it renders.
+struct CellText {
+    lines: Vec<String>,
+    metrics: &'static FontMetrics,
+    size: f64,
+    bold: bool,
+    wrap: bool,
+    indent_px: f64,
+    rotation: u32,
+}
+
+impl CellText {
+    fn new(cell: &Cell, date1904: bool) -> Option<Self> {
+        let text = display_text(cell, date1904);
+        if text.is_empty() {
+            return None;
+        }
+        let style = cell.get_style();
+        let (name, size, bold) = match style.get_font() {
+            Some(font) => (font.get_name(), *font.get_size(), *font.get_bold()),
+            None => ("Calibri", 11.0, false),
+        };
+        let (wrap, indent, rotation) = style.get_alignment().map_or((false, 0, 0), |a| {
+            (*a.get_wrap_text(), *a.get_indent(), *a.get_text_rotation())
+        });
+        Some(Self {
+            lines: text.split('\n').map(str::to_string).collect(),
+            metrics: FontMetrics::for_font(name),
+            size: if size > 0.0 { size } else { 11.0 },
+            bold,
+            wrap,
+            indent_px: f64::from(indent) * INDENT_PX,
+            rotation,
+        })
+    }
+
+    /// Width of `text` in this cell's font, in pixels.
+    fn px(&self, text: &str) -> f64 {
+        self.metrics.text_width(text, self.size, self.bold) * PX_PER_PT
+    }
+
+    fn line_px(&self) -> f64 {
+        self.metrics.line_height(self.size) * PX_PER_PT
+    }
+
+    /// Number of lines `line` takes when wrapped into `avail` pixels.
+    fn wrapped_lines(&self, line: &str, avail: f64) -> usize {
+        let avail = avail.max(1.0);
+        let space = self.px(" ");
+        let mut lines = 1;
+        let mut used = 0.0;
+        for word in line.split(' ') {
+            let width = self.px(word);
+            if used > 0.0 && used + space + width > avail {
+                lines += 1;
+                used = 0.0;
+            } else if used > 0.0 {
+                used += space;
+            }
+            // A word longer than the line breaks between characters.
+            let extra = ((width / avail).ceil() as usize).saturating_sub(1);
+            lines += extra;
+            used += width - avail * extra as f64;
+        }
+        lines
+    }
+
+    /// Rendered `(width, height)` in pixels, wrapping into `avail` pixels if
+    /// given and the cell wraps.
+    fn size_px(&self, avail: Option<f64>) -> (f64, f64) {
+        let line_count = match avail {
+            Some(avail) if self.wrap => self
+                .lines
+                .iter()
+                .map(|line| self.wrapped_lines(line, avail - self.indent_px))
+                .sum(),
+            _ => self.lines.len(),
+        };
+        let width = self
+            .lines
+            .iter()
+            .map(|line| self.px(line))
+            .fold(0.0, f64::max);
+        let height = line_count as f64 * self.line_px();
+        let (width, height) = match self.rotation {
+            0 => (width, height),
+            // Stacked vertically: one character per line.
+            255 => {
+                let chars = self.lines.iter().map(|l| l.chars().count()).max();
+                (self.px("W"), chars.unwrap_or(0) as f64 * self.line_px())
+            }
+            rotation => {
+                // 1-90 turns up, 91-180 turns down by `rotation - 90`.
+                let degrees = if rotation > 90 {
+                    rotation - 90
+                } else {
+                    rotation
+                };
+                let (sin, cos) = f64::from(degrees).to_radians().sin_cos();
+                (width * cos + height * sin, width * sin + height * cos)
+            }
+        };
+        (width + self.indent_px, height)
+    }
+}
+
+/// Merged ranges of a sheet as 1-based `(r1, c1, r2, c2)`.
+fn merged_ranges(ws: &Worksheet) -> Vec<(u32, u32, u32, u32)> {
+    ws.get_merge_cells()
+        .iter()
+        .filter_map(|range| a1_range_to_bounds(&range.get_range()).ok())
+        .map(|(r1, c1, r2, c2)| (r1 + 1, c1 + 1, r2 + 1, c2 + 1))
+        .collect()
+}
+
+/// The span a displayed cell covers: its merged range if it anchors one, or
+/// itself. `None` for cells hidden under a merged range.
+fn cell_span(
+    merges: &[(u32, u32, u32, u32)],
+    (row, col): (u32, u32),
+) -> Option<(u32, u32, u32, u32)> {
+    match merges
+        .iter()
+        .find(|&&(r1, c1, r2, c2)| (r1..=r2).contains(&row) && (c1..=c2).contains(&col))
+    {
+        Some(&(r1, c1, r2, c2)) => (r1 == row && c1 == col).then_some((r1, c1, r2, c2)),
+        None => Some((row, col, row, col)),
+    }
+}
+
+fn check_limits((min, max): (f64, f64), limit: f64) -> PyResult<()> {
+    if !(0.0..=limit).contains(&min) || !(0.0..=limit).contains(&max) || min > max {
+        return Err(PyErr::new::<PyValueError, _>(format!(
+            "min and max must satisfy 0 <= min <= max <= {limit}"
+        )));
+    }
+    Ok(())
+}
+
+/// Pixels of a column's width.
+fn column_px(ws: &Worksheet, col: u32) -> f64 {
+    let width = ws
+        .get_column_dimension_by_number(&col)
+        .map(|cd| *cd.get_width())
+        .filter(|&w| w > 0.0)
+        .unwrap_or(DEFAULT_WIDTH);
+    (width * DIGIT_PX + 0.5).trunc()
+}
+
+/// Fit the width of `columns` (letters; every column with content if `None`)
+/// to the text their cells display, clamped to `limits` in characters.
+/// Cells merged across several columns don't widen them, as in Excel.
+pub(crate) fn autofit_columns(
+    book: &mut Spreadsheet,
+    date1904: bool,
+    sheet: &str,
+    columns: Option<Vec<String>>,
+    limits: (f64, f64),
+) -> PyResult<()> {
+    let ws = book
+        .get_sheet_by_name_mut(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+    check_limits(limits, MAX_WIDTH)?;
+    let wanted = columns
+        .map(|letters| {
+            letters
+                .iter()
+                .map(|l| col_letter_to_u32(l.trim()))
+                .collect::<Result<HashSet<u32>, String>>()
+        })
+        .transpose()
+        .map_err(|e| PyErr::new::<PyValueError, _>(e))?;
+
+    let merges = merged_ranges(ws);
+    let mut widths: BTreeMap<u32, f64> = BTreeMap::new();
+    for cell in ws.get_cell_collection() {
+        let coord = cell.get_coordinate();
+        let (row, col) = (*coord.get_row_num(), *coord.get_col_num());
+        if wanted.as_ref().is_some_and(|w| !w.contains(&col)) {
+            continue;
+        }
+        let Some((_, c1, _, c2)) = cell_span(&merges, (row, col)) else {
+            continue;
+        };
+        if c1 != c2 {
+            continue;
+        }
+        let Some(text) = CellText::new(cell, date1904) else {
+            continue;
+        };
+        let (px, _) = text.size_px(None);
+        let widest = widths.entry(col).or_insert(0.0);
+        *widest = widest.max(px);
+    }
+
+    for (col, px) in widths {
+        let chars = ((px.ceil() + PADDING_PX) / DIGIT_PX * 256.0).trunc() / 256.0;
+        ws.get_column_dimension_by_number_mut(&col)
+            .set_width(chars.clamp(limits.0, limits.1));
+    }
+    Ok(())
+}
+
+/// Fit the height of `rows` (every row with content if `None`) to the lines
+/// their cells display, wrapping text in wrapping cells to their column
+/// widths, clamped to `limits` in points. Cells merged across several rows
+/// don't heighten them, as in Excel.
+pub(crate) fn autofit_rows(
+    book: &mut Spreadsheet,
+    date1904: bool,
+    sheet: &str,
+    rows: Option<Vec<u32>>,
+    limits: (f64, f64),
+) -> PyResult<()> {
+    let ws = book
+        .get_sheet_by_name_mut(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+    check_limits(limits, MAX_HEIGHT)?;
+    let wanted: Option<HashSet<u32>> = rows.map(|rows| rows.into_iter().collect());
+
+    let merges = merged_ranges(ws);
+    let mut heights: BTreeMap<u32, f64> = BTreeMap::new();
+    for cell in ws.get_cell_collection() {
+        let coord = cell.get_coordinate();
+        let (row, col) = (*coord.get_row_num(), *coord.get_col_num());
+        if wanted.as_ref().is_some_and(|w| !w.contains(&row)) {
+            continue;
+        }
+        let Some((r1, c1, r2, c2)) = cell_span(&merges, (row, col)) else {
+            continue;
+        };
+        if r1 != r2 {
+            continue;
+        }
+        let Some(text) = CellText::new(cell, date1904) else {
+            continue;
+        };
+        let avail = (c1..=c2).map(|c| column_px(ws, c)).sum::<f64>() - PADDING_PX;
+        let (_, px) = text.size_px(Some(avail));
+        let tallest = heights.entry(row).or_insert(DEFAULT_HEIGHT);
+        *tallest = tallest.max(px / PX_PER_PT);
+    }
+
+    for (row, points) in heights {
+        // Excel row heights step in quarter points.
+        let points = (points * 4.0).ceil() / 4.0;
+        ws.get_row_dimension_mut(&row)
+            .set_height(points.clamp(limits.0, limits.1));
+    }
+    Ok(())
+}
@@ -0,0 +1,1365 @@
+// Formula evaluation.
+//
+// `calculate` parses every formula in the workbook, orders the formula cells so
+// that precedents are evaluated before their dependents, and writes each result
+// back as the cell's cached value while keeping the formula itself. Shared
+// formula dependents are evaluated with their translated formula, and array
+// formulas spill their result over their whole `ref`. Cells in (or downstream
+// of) a circular reference are left untouched and reported.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use chrono::{Datelike, Months, NaiveDate, NaiveTime, TimeDelta};
+use umya_spreadsheet::structs::{Cell, CellFormulaValues, CellRawValue};
+use umya_spreadsheet::{Spreadsheet, Worksheet};
+
+use crate::cell_ops::SheetFormulas;
+use crate::formula_parser::{parse_formula, quote_sheet_name, BinOp, Expr, Reference};
+use crate::number_format::{format_general, format_value, round_half_away};
+use crate::utils::{
+    a1_range_to_bounds, excel_serial_to_naive_datetime, naive_datetime_to_excel_serial,
+    u32_to_col_letter,
+};
+
+/// An evaluated formula value.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Value {
+    Blank,
+    Number(f64),
+    Text(String),
+    Bool(bool),
+    Error(String),
+    /// The values of a range or array, row-major.
+    Array(Vec<Vec<Value>>),
+}
+
+/// `(sheet index, row, col)`, with 1-based row and column.
+type CellKey = (usize, u32, u32);
+
+struct FormulaCell {
+    key: CellKey,
+    /// Bottom-right `(row, col)` of the cells the formula fills: the cell
+    /// itself, or the `ref` of an array formula.
+    end: (u32, u32),
+    expr: Result<Expr, String>,
+}
+
+/// The formula cells of one sheet, indexed so a reference finds the ones it
+/// covers without scanning every formula.
+#[derive(Default)]
+struct FormulaIndex {
+    /// Single-cell formulas by column, then row.
+    cells: BTreeMap<u32, BTreeMap<u32, usize>>,
+    /// Array formulas filling more than one cell, as
+    /// `(top, left, bottom, right, index)`. These are rare, so a list will do.
+    areas: Vec<(u32, u32, u32, u32, usize)>,
+}
+
+impl FormulaIndex {
+    fn insert(&mut self, fc: &FormulaCell, i: usize) {
+        let (_, row, col) = fc.key;
+        if fc.end == (row, col) {
+            self.cells.entry(col).or_default().insert(row, i);
+        } else {
+            self.areas.push((row, col, fc.end.0, fc.end.1, i));
+        }
+    }
+
+    /// Push the formulas overlapping the 1-based inclusive bounds
+    /// `(min_row, min_col, max_row, max_col)` onto `out`.
+    fn overlapping(&self, (r1, c1, r2, c2): (u32, u32, u32, u32), out: &mut Vec<usize>) {
+        for rows in self.cells.range(c1..=c2).map(|(_, rows)| rows) {
+            out.extend(rows.range(r1..=r2).map(|(_, &i)| i));
+        }
+        out.extend(
+            self.areas
+                .iter()
+                .filter(|&&(top, left, bottom, right, _)| {
+                    top <= r2 && r1 <= bottom && left <= c2 && c1 <= right
+                })
+                .map(|&(.., i)| i),
+        );
+    }
+}
+
+/// Evaluate every formula in the workbook and store the results as cached values.
+///
+/// Returns the cells that could not be evaluated because they are part of, or
+/// depend on, a circular reference (e.g. `"Sheet1!A1"`).
+pub(crate) fn calculate(book: &mut Spreadsheet, date1904: bool) -> Vec<String> {
+    let sheet_names: Vec<String> = book
+        .get_sheet_collection()
+        .iter()
+        .map(|ws| ws.get_name().to_string())
+        .collect();
+
+    // Workbook-level defined names are inlined into each formula, so they take
+    // part in the dependency graph like any other reference.
+    let names: HashMap<String, Expr> = book
+        .get_defined_names()
+        .iter()
+        .filter_map(|dn| {
+            let expr = parse_formula(&dn.get_address()).ok()?;
+            Some((dn.get_name().to_uppercase(), expr))
+        })
+        .collect();
+
+    let mut formulas: Vec<FormulaCell> = Vec::new();
+    for (si, ws) in book.get_sheet_collection().iter().enumerate() {
+        let sheet_formulas = SheetFormulas::new(ws, None);
+        for cell in ws.get_cell_collection_sorted() {
+            let Some(formula) = sheet_formulas.formula_text(cell) else {
+                continue;
+            };
+            let coord = cell.get_coordinate();
+            let (row, col) = (*coord.get_row_num(), *coord.get_col_num());
+            let end = cell
+                .get_formula_obj()
+                .filter(|f| matches!(f.get_formula_type(), CellFormulaValues::Array))
+                .and_then(|f| a1_range_to_bounds(f.get_reference()).ok())
+                .map(|(_, _, r2, c2)| (r2 + 1, c2 + 1))
+                .filter(|&(r2, c2)| r2 >= row && c2 >= col)
+                .unwrap_or((row, col));
+            formulas.push(FormulaCell {
+                key: (si, row, col),
+                end,
+                expr: parse_formula(&formula).map(|e| inline_names(e, &names)),
+            });
+        }
+    }
+
+    // Dependency graph: an edge from each formula cell to the formula cells
+    // that reference it (or any cell it spills into).
+    let mut by_sheet: Vec<FormulaIndex> = Vec::new();
+    by_sheet.resize_with(sheet_names.len(), FormulaIndex::default);
+    for (i, fc) in formulas.iter().enumerate() {
+        by_sheet[fc.key.0].insert(fc, i);
+    }
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); formulas.len()];
+    let mut indegree = vec![0usize; formulas.len()];
+    for (i, fc) in formulas.iter().enumerate() {
+        let Ok(expr) = &fc.expr else {
+            continue;
+        };
+        let mut precedents = Vec::new();
+        expr.for_each_ref(&mut |r: &Reference| {
+            let Some(si) = resolve_sheet(&sheet_names, r, fc.key.0) else {
+                return;
+            };
+            by_sheet[si].overlapping(r.bounds(), &mut precedents);
+        });
+        precedents.sort_unstable();
+        precedents.dedup();
+        for j in precedents {
+            dependents[j].push(i);
+            indegree[i] += 1;
+        }
+    }
+
+    let mut queue: VecDeque<usize> = (0..formulas.len()).filter(|&i| indegree[i] == 0).collect();
+    let mut order = Vec::with_capacity(formulas.len());
+    while let Some(i) = queue.pop_front() {
+        order.push(i);
+        for &d in &dependents[i] {
+            indegree[d] -= 1;
+            if indegree[d] == 0 {
+                queue.push_back(d);
+            }
+        }
+    }
+
+    let mut ev = Evaluator {
+        book: &*book,
+        sheet_names: &sheet_names,
+        results: HashMap::new(),
+        date1904,
+        sheet: 0,
+    };
+    for &i in &order {
+        let fc = &formulas[i];
+        ev.sheet = fc.key.0;
+        let value = match &fc.expr {
+            Ok(expr) => ev.eval(expr),
+            Err(_) => Value::Error("#NAME?".to_string()),
+        };
+        let (si, row, col) = fc.key;
+        if fc.end == (row, col) {
+            ev.results.insert(fc.key, value.top_left());
+            continue;
+        }
+        for r in row..=fc.end.0 {
+            for c in col..=fc.end.1 {
+                let v = value.spill_at((r - row) as usize, (c - col) as usize);
+                ev.results.insert((si, r, c), v);
+            }
+        }
+    }
+    let results = ev.results;
+
+    for (&(si, row, col), value) in &results {
+        if let Some(ws) = book.get_sheet_by_name_mut(&sheet_names[si]) {
+            set_cached_value(ws.get_cell_mut((col, row)), value);
+        }
+    }
+
+    formulas
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| indegree[*i] > 0)
+        .map(|(_, fc)| {
+            let (si, row, col) = fc.key;
+            format!(
+                "{}!{}{}",
+                quote_sheet_name(&sheet_names[si]),
+                u32_to_col_letter(col),
+                row
+            )
+        })
+        .collect()
+}
+
+fn inline_names(expr: Expr, names: &HashMap<String, Expr>) -> Expr {
+    let inline = |e: Box<Expr>| Box::new(inline_names(*e, names));
+    match expr {
+        Expr::Name(n) => names
+            .get(&n.to_uppercase())
+            .cloned()
+            .unwrap_or(Expr::Name(n)),
+        Expr::Neg(e) => Expr::Neg(inline(e)),
+        Expr::Percent(e) => Expr::Percent(inline(e)),
+        Expr::Binary(op, a, b) => Expr::Binary(op, inline(a), inline(b)),
+        Expr::Call(f, args) => Expr::Call(
+            f,
+            args.into_iter().map(|a| inline_names(a, names)).collect(),
+        ),
+        e => e,
+    }
+}
+
+fn resolve_sheet(sheet_names: &[String], r: &Reference, current: usize) -> Option<usize> {
+    match &r.sheet {
+        None => Some(current),
+        Some(name) => sheet_names
+            .iter()
+            .position(|n| n.eq_ignore_ascii_case(name)),
+    }
+}
+
+/// Store `value` as the cached result of a formula cell.
+fn set_cached_value(cell: &mut Cell, value: &Value) {
+    // umya's value setters drop the formula; put it back afterwards.
+    let formula = cell.get_formula_obj().cloned();
+    match value {
+        Value::Number(n) if n.is_finite() => {
+            cell.set_value_number(*n);
+        }
+        Value::Number(_) => {
+            cell.set_error("#NUM!");
+        }
+        Value::Text(s) => {
+            cell.set_value_string(s);
+        }
+        Value::Bool(b) => {
+            cell.set_value_bool(*b);
+        }
+        Value::Error(e) => {
+            cell.set_error(e);
+        }
+        // A formula pointing at an empty cell displays 0.
+        Value::Blank | Value::Array(_) => {
+            cell.set_value_number(0.0);
+        }
+    }
+    if let Some(f) = formula {
+        cell.get_cell_value_mut().set_formula_obj(f);
+    }
+}
+
+/// The stored (or cached) value of a cell, as the evaluator sees it.
+fn stored_value(cell: &Cell) -> Value {
+    if let Some(f) = cell.get_value_number() {
+        return Value::Number(f);
+    }
+    match cell.get_raw_value() {
+        CellRawValue::Empty => Value::Blank,
+        CellRawValue::Bool(b) => Value::Bool(*b),
+        CellRawValue::Error(_) => Value::Error(cell.get_value().into_owned()),
+        _ => {
+            let s = cell.get_value().into_owned();
+            if s.is_empty() {
+                Value::Blank
+            } else {
+                Value::Text(s)
+            }
+        }
+    }
+}
+
+impl Value {
+    fn error(token: &str) -> Self {
+        Value::Error(token.to_string())
+    }
+
+    /// Collapse a range to a single value: a 1x1 range is its only cell.
+    fn scalar(self) -> Value {
+        match self {
+            Value::Array(rows) if rows.len() == 1 && rows[0].len() == 1 => {
+                rows.into_iter().next().unwrap_or_default().swap_remove(0)
+            }
+            Value::Array(_) => Value::error("#VALUE!"),
+            v => v,
+        }
+    }
+
+    fn top_left(self) -> Value {
+        match self {
+            Value::Array(rows) => rows
+                .into_iter()
+                .next()
+                .and_then(|r| r.into_iter().next())
+                .unwrap_or(Value::Blank),
+            v => v,
+        }
+    }
+
+    /// The value an array formula shows at offset `(dr, dc)` of its range.
+    /// Single rows and columns repeat across the range, as in Excel; cells
+    /// past the end of a larger array get `#N/A`.
+    fn spill_at(&self, dr: usize, dc: usize) -> Value {
+        let Value::Array(rows) = self else {
+            return self.clone();
+        };
+        let dr = if rows.len() == 1 { 0 } else { dr };
+        let Some(row) = rows.get(dr) else {
+            return Value::error("#N/A");
+        };
+        let dc = if row.len() == 1 { 0 } else { dc };
+        row.get(dc).cloned().unwrap_or_else(|| Value::error("#N/A"))
+    }
+
+    fn rows(self) -> Vec<Vec<Value>> {
+        match self {
+            Value::Array(rows) => rows,
+            v => vec![vec![v]],
+        }
+    }
+
+    fn flat(self) -> Vec<Value> {
+        self.rows().into_iter().flatten().collect()
+    }
+
+    fn is_error(&self) -> bool {
+        matches!(self, Value::Error(_))
+    }
+}
+
+fn to_number(v: &Value) -> Result<f64, String> {
+    match v {
+        Value::Number(n) => Ok(*n),
+        Value::Bool(b) => Ok(if *b { 1.0 } else { 0.0 }),
+        Value::Blank => Ok(0.0),
+        Value::Text(s) => parse_number_text(s).ok_or_else(|| "#VALUE!".to_string()),
+        Value::Error(e) => Err(e.clone()),
+        Value::Array(_) => to_number(&v.clone().scalar()),
+    }
+}
+
+fn parse_number_text(s: &str) -> Option<f64> {
+    let t = s.trim();
+    if t.is_empty() {
+        return None;
+    }
+    if let Some(p) = t.strip_suffix('%') {
+        return p.trim().parse::<f64>().ok().map(|n| n / 100.0);
+    }
+    t.replace(',', "").parse::<f64>().ok()
+}
+
+fn to_text(v: &Value) -> Result<String, String> {
+    match v {
+        Value::Number(n) => Ok(format_general(*n)),
+        Value::Bool(b) => Ok(if *b { "TRUE" } else { "FALSE" }.to_string()),
+        Value::Blank => Ok(String::new()),
+        Value::Text(s) => Ok(s.clone()),
+        Value::Error(e) => Err(e.clone()),
+        Value::Array(_) => to_text(&v.clone().scalar()),
+    }
+}
+
+fn to_bool(v: &Value) -> Result<bool, String> {
+    match v {
+        Value::Number(n) => Ok(*n != 0.0),
+        Value::Bool(b) => Ok(*b),
+        Value::Blank => Ok(false),
+        Value::Text(s) if s.eq_ignore_ascii_case("TRUE") => Ok(true),
+        Value::Text(s) if s.eq_ignore_ascii_case("FALSE") => Ok(false),
+        Value::Text(_) => Err("#VALUE!".to_string()),
+        Value::Error(e) => Err(e.clone()),
+        Value::Array(_) => to_bool(&v.clone().scalar()),
+    }
+}
+
+/// Excel's ordering for comparisons: numbers < text < booleans, text is
+/// case-insensitive, and a blank takes the type of the other side.
+fn compare(a: &Value, b: &Value) -> Ordering {
+    fn rank(v: &Value) -> u8 {
+        match v {
+            Value::Text(_) => 1,
+            Value::Bool(_) => 2,
+            _ => 0,
+        }
+    }
+    fn blank_like(other: &Value) -> Value {
+        match other {
+            Value::Text(_) => Value::Text(String::new()),
+            Value::Bool(_) => Value::Bool(false),
+            _ => Value::Number(0.0),
+        }
+    }
+    match (a, b) {
+        (Value::Blank, Value::Blank) => Ordering::Equal,
+        (Value::Blank, other) => compare(&blank_like(other), other),
+        (other, Value::Blank) => compare(other, &blank_like(other)),
+        (Value::Number(x), Value::Number(y)) => x.partial_cmp(y).unwrap_or(Ordering::Equal),
+        (Value::Text(x), Value::Text(y)) => x.to_lowercase().cmp(&y.to_lowercase()),
+        (Value::Bool(x), Value::Bool(y)) => x.cmp(y),
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+fn same_type(a: &Value, b: &Value) -> bool {
+    std::mem::discriminant(a) == std::mem::discriminant(b)
+}
+
+/// Case-insensitive match with `*`, `?` and `~` escapes.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.to_lowercase().chars().collect();
+    let t: Vec<char> = text.to_lowercase().chars().collect();
+    // (pattern index, text index) to resume from after the last `*`.
+    let (mut pi, mut ti) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while ti < t.len() {
+        match p.get(pi) {
+            Some('*') => {
+                star = Some((pi + 1, ti));
+                pi += 1;
+                continue;
+            }
+            Some('~') if p.get(pi + 1).is_some_and(|&c| c == t[ti]) => {
+                pi += 2;
+                ti += 1;
+                continue;
+            }
+            Some('?') => {
+                pi += 1;
+                ti += 1;
+                continue;
+            }
+            Some(&c) if c == t[ti] => {
+                pi += 1;
+                ti += 1;
+                continue;
+            }
+            _ => {}
+        }
+        match star {
+            Some((sp, st)) => {
+                pi = sp;
+                ti = st + 1;
+                star = Some((sp, st + 1));
+            }
+            None => return false,
+        }
+    }
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+fn has_wildcards(s: &str) -> bool {
+    s.contains(['*', '?', '~'])
+}
+
+/// Exact-match equality used by lookups.
+fn lookup_eq(target: &Value, v: &Value) -> bool {
+    match (target, v) {
+        (Value::Text(p), Value::Text(s)) if has_wildcards(p) => wildcard_match(p, s),
+        _ => same_type(target, v) && compare(target, v) == Ordering::Equal,
+    }
+}
+
+/// A COUNTIF-style criterion such as `">=10"`, `"<>"`, `"ab*"` or `5`.
+struct Criterion {
+    op: &'static str,
+    target: Value,
+}
+
+impl Criterion {
+    fn parse(v: &Value) -> Criterion {
+        let Value::Text(s) = v else {
+            return Criterion {
+                op: "=",
+                target: match v {
+                    Value::Blank => Value::Text(String::new()),
+                    other => other.clone(),
+                },
+            };
+        };
+        let (op, rest) = ["<=", ">=", "<>", "<", ">", "="]
+            .iter()
+            .find_map(|op| s.strip_prefix(op).map(|rest| (*op, rest)))
+            .unwrap_or(("=", s.as_str()));
+        let target = if let Some(n) = parse_number_text(rest) {
+            Value::Number(n)
+        } else if rest.eq_ignore_ascii_case("TRUE") {
+            Value::Bool(true)
+        } else if rest.eq_ignore_ascii_case("FALSE") {
+            Value::Bool(false)
+        } else {
+            Value::Text(rest.to_string())
+        };
+        Criterion { op, target }
+    }
+
+    fn matches(&self, v: &Value) -> bool {
+        let equal = |v: &Value| match (&self.target, v) {
+            (Value::Text(t), Value::Blank) => t.is_empty(),
+            (Value::Text(t), Value::Text(s)) => wildcard_match(t, s),
+            (target, v) => same_type(target, v) && compare(target, v) == Ordering::Equal,
+        };
+        match self.op {
+            "=" => equal(v),
+            "<>" => !equal(v),
+            op => {
+                if !same_type(&self.target, v) {
+                    return false;
+                }
+                let ord = compare(v, &self.target);
+                match op {
+                    "<" => ord == Ordering::Less,
+                    "<=" => ord != Ordering::Greater,
+                    ">" => ord == Ordering::Greater,
+                    _ => ord != Ordering::Less,
+                }
+            }
+        }
+    }
+}
+
+struct Evaluator<'a> {
+    book: &'a Spreadsheet,
+    sheet_names: &'a [String],
+    results: HashMap<CellKey, Value>,
+    date1904: bool,
+    /// Sheet of the formula being evaluated, for unqualified references.
+    sheet: usize,
+}
+
+impl Evaluator<'_> {
+    fn worksheet(&self, si: usize) -> Option<&Worksheet> {
+        self.book.get_sheet_by_name(&self.sheet_names[si])
+    }
+
+    fn cell_value(&self, key: CellKey) -> Value {
+        if let Some(v) = self.results.get(&key) {
+            return v.clone();
+        }
+        let (si, row, col) = key;
+        self.worksheet(si)
+            .and_then(|ws| ws.get_cell((col, row)))
+            .map(stored_value)
+            .unwrap_or(Value::Blank)
+    }
+
+    fn reference(&self, r: &Reference) -> Value {
+        let Some(si) = resolve_sheet(self.sheet_names, r, self.sheet) else {
+            return Value::error("#REF!");
+        };
+        let (r1, c1, mut r2, mut c2) = r.bounds();
+        if r.is_single_cell() {
+            return self.cell_value((si, r1, c1));
+        }
+        // Whole-row and whole-column references stop at the used area.
+        if let Some(ws) = self.worksheet(si) {
+            if r.start.row.is_none() {
+                r2 = r2.min(ws.get_highest_row().max(r1));
+            }
+            if r.start.col.is_none() {
+                c2 = c2.min(ws.get_highest_column().max(c1));
+            }
+        }
+        let rows = (r1..=r2)
+            .map(|row| {
+                (c1..=c2)
+                    .map(|col| self.cell_value((si, row, col)))
+                    .collect()
+            })
+            .collect();
+        Value::Array(rows)
+    }
+
+    fn eval(&self, expr: &Expr) -> Value {
+        match expr {
+            Expr::Number(n) => Value::Number(*n),
+            Expr::Text(s) => Value::Text(s.clone()),
+            Expr::Bool(b) => Value::Bool(*b),
+            Expr::Error(e) => Value::Error(e.clone()),
+            Expr::Missing => Value::Blank,
+            // Names left after inlining are undefined.
+            Expr::Name(_) => Value::error("#NAME?"),
+            Expr::Ref(r) => self.reference(r),
+            Expr::Neg(e) => map_unary(self.eval(e), |v| to_number(v).map(|n| Value::Number(-n))),
+            Expr::Percent(e) => map_unary(self.eval(e), |v| {
+                to_number(v).map(|n| Value::Number(n / 100.0))
+            }),
+            Expr::Binary(op, a, b) => {
+                broadcast(self.eval(a), self.eval(b), |x, y| binary(*op, x, y))
+            }
+            Expr::Call(name, args) => self.call(name, args),
+        }
+    }
+
+    /// Evaluate an argument that must be a single value.
+    fn scalar(&self, expr: &Expr) -> Value {
+        self.eval(expr).scalar()
+    }
+
+    fn number_arg(&self, expr: &Expr) -> Result<f64, String> {
+        to_number(&self.scalar(expr))
+    }
+
+    fn text_arg(&self, expr: &Expr) -> Result<String, String> {
+        to_text(&self.scalar(expr))
+    }
+
+    /// Numbers for SUM-like functions. Values in ranges count only when they
+    /// are numbers; values typed directly into the call are coerced.
+    fn numbers(&self, args: &[Expr]) -> Result<Vec<f64>, String> {
+        let mut out = Vec::new();
+        for arg in args {
+            let v = self.eval(arg);
+            match v {
+                Value::Array(_) => {
+                    for cell in v.flat() {
+                        match cell {
+                            Value::Number(n) => out.push(n),
+                            Value::Error(e) => return Err(e),
+                            _ => {}
+                        }
+                    }
+                }
+                Value::Error(e) => return Err(e),
+                // A referenced text/boolean/blank cell is skipped like a range.
+                _ if matches!(arg, Expr::Ref(_)) => {
+                    if let Value::Number(n) = v {
+                        out.push(n);
+                    }
+                }
+                Value::Blank => {}
+                _ => out.push(to_number(&v)?),
+            }
+        }
+        Ok(out)
+    }
+
+    fn call(&self, name: &str, args: &[Expr]) -> Value {
+        let name = name
+            .strip_prefix("_XLFN.")
+            .or_else(|| name.strip_prefix("_XLWS."))
+            .unwrap_or(name);
+        let argc = |min: usize, max: usize| (min..=max).contains(&args.len());
+
+        let result: Result<Value, String> = match name {
+            // Logical.
+            "IF" if argc(1, 3) => {
+                let cond = self.scalar(&args[0]);
+                to_bool(&cond).map(|c| match (c, args.get(1), args.get(2)) {
+                    (true, Some(t), _) => self.eval(t),
+                    (true, None, _) => Value::Bool(true),
+                    (false, _, Some(f)) => self.eval(f),
+                    (false, _, None) => Value::Bool(false),
+                })
+            }
+            "IFERROR" if argc(2, 2) => {
+                let v = self.eval(&args[0]);
+                Ok(if v.is_error() { self.eval(&args[1]) } else { v })
+            }
+            "IFNA" if argc(2, 2) => {
+                let v = self.eval(&args[0]);
+                Ok(if v == Value::error("#N/A") {
+                    self.eval(&args[1])
+                } else {
+                    v
+                })
+            }
+            "AND" | "OR" if !args.is_empty() => self.logical(name == "AND", args),
+            "NOT" if argc(1, 1) => to_bool(&self.scalar(&args[0])).map(|b| Value::Bool(!b)),
+            "TRUE" if argc(0, 0) => Ok(Value::Bool(true)),
+            "FALSE" if argc(0, 0) => Ok(Value::Bool(false)),
+
+            // Aggregates.
+            "SUM" => self.numbers(args).map(|ns| Value::Number(ns.iter().sum())),
+            "PRODUCT" => self
+                .numbers(args)
+                .map(|ns| Value::Number(ns.iter().product())),
+            "AVERAGE" => self.numbers(args).and_then(|ns| average(&ns)),
+            "MIN" => self
+                .numbers(args)
+                .map(|ns| Value::Number(ns.iter().copied().reduce(f64::min).unwrap_or(0.0))),
+            "MAX" => self
+                .numbers(args)
+                .map(|ns| Value::Number(ns.iter().copied().reduce(f64::max).unwrap_or(0.0))),
+            "COUNT" => Ok(Value::Number(
+                self.count(args, |v| matches!(v, Value::Number(_))),
+            )),
+            "COUNTA" => Ok(Value::Number(
+                self.count(args, |v| !matches!(v, Value::Blank)),
+            )),
+            "COUNTBLANK" if argc(1, 1) => Ok(Value::Number(
+                self.eval(&args[0])
+                    .flat()
+                    .iter()
+                    .filter(|v| matches!(v, Value::Blank) || *v == &Value::Text(String::new()))
+                    .count() as f64,
+            )),
+            "SUMPRODUCT" if !args.is_empty() => self.sumproduct(args),
+
+            // Conditional aggregates.
+            "COUNTIF" if argc(2, 2) => self
+                .conditional(&args[..2], None)
+                .map(|ms| Value::Number(ms.len() as f64)),
+            "COUNTIFS" if !args.is_empty() && args.len() % 2 == 0 => self
+                .conditional(args, None)
+                .map(|ms| Value::Number(ms.len() as f64)),
+            "SUMIF" | "AVERAGEIF" if argc(2, 3) => {
+                let values = args.get(2).unwrap_or(&args[0]);
+                self.conditional(&args[..2], Some(values)).and_then(|ms| {
+                    let ns: Vec<f64> = ms.iter().filter_map(number_only).collect();
+                    if name == "SUMIF" {
+                        Ok(Value::Number(ns.iter().sum()))
+                    } else {
+                        average(&ns)
+                    }
+                })
+            }
+            "SUMIFS" | "AVERAGEIFS" if args.len() >= 3 && args.len() % 2 == 1 => {
+                self.conditional(&args[1..], Some(&args[0])).and_then(|ms| {
+                    let ns: Vec<f64> = ms.iter().filter_map(number_only).collect();
+                    if name == "SUMIFS" {
+                        Ok(Value::Number(ns.iter().sum()))
+                    } else {
+                        average(&ns)
+                    }
+                })
+            }
+
+            // Lookup.
+            "VLOOKUP" | "HLOOKUP" if argc(3, 4) => self.vhlookup(name == "VLOOKUP", args),
+            "MATCH" if argc(2, 3) => self.match_fn(args),
+            "INDEX" if argc(2, 3) => self.index(args),
+            "XLOOKUP" if argc(3, 4) => self.xlookup(args),
+
+            // Math.
+            "ROUND" | "ROUNDUP" | "ROUNDDOWN" if argc(2, 2) => self.round(name, args),
+            "INT" if argc(1, 1) => self.number_arg(&args[0]).map(|n| Value::Number(n.floor())),
+            "ABS" if argc(1, 1) => self.number_arg(&args[0]).map(|n| Value::Number(n.abs())),
+            "SQRT" if argc(1, 1) => self.number_arg(&args[0]).and_then(|n| {
+                if n < 0.0 {
+                    Err("#NUM!".to_string())
+                } else {
+                    Ok(Value::Number(n.sqrt()))
+                }
+            }),
+            "MOD" if argc(2, 2) => self.number_arg(&args[0]).and_then(|n| {
+                let d = self.number_arg(&args[1])?;
+                if d == 0.0 {
+                    Err("#DIV/0!".to_string())
+                } else {
+                    // The result takes the sign of the divisor.
+                    Ok(Value::Number(n - d * (n / d).floor()))
+                }
+            }),
+            "POWER" if argc(2, 2) => Ok(binary(
+                BinOp::Pow,
+                &self.scalar(&args[0]),
+                &self.scalar(&args[1]),
+            )),
+
+            // Text.
+            "CONCATENATE" => args
+                .iter()
+                .map(|a| self.text_arg(a))
+                .collect::<Result<String, String>>()
+                .map(Value::Text),
+            "CONCAT" => args
+                .iter()
+                .flat_map(|a| self.eval(a).flat())
+                .map(|v| to_text(&v))
+                .collect::<Result<String, String>>()
+                .map(Value::Text),
+            "LEN" if argc(1, 1) => self
+                .text_arg(&args[0])
+                .map(|s| Value::Number(s.chars().count() as f64)),
+            "LEFT" | "RIGHT" if argc(1, 2) => self.text_arg(&args[0]).and_then(|s| {
+                let n = match args.get(1) {
+                    Some(a) => self.number_arg(a)?,
+                    None => 1.0,
+                };
+                if n < 0.0 {
+                    return Err("#VALUE!".to_string());
+                }
+                let chars: Vec<char> = s.chars().collect();
+                let n = (n as usize).min(chars.len());
+                let part = if name == "LEFT" {
+                    &chars[..n]
+                } else {
+                    &chars[chars.len() - n..]
+                };
+                Ok(Value::Text(part.iter().collect()))
+            }),
+            "MID" if argc(3, 3) => self.text_arg(&args[0]).and_then(|s| {
+                let start = self.number_arg(&args[1])?;
+                let n = self.number_arg(&args[2])?;
+                if start < 1.0 || n < 0.0 {
+                    return Err("#VALUE!".to_string());
+                }
+                Ok(Value::Text(
+                    s.chars()
+                        .skip(start as usize - 1)
+                        .take(n as usize)
+                        .collect(),
+                ))
+            }),
+            "UPPER" if argc(1, 1) => self
+                .text_arg(&args[0])
+                .map(|s| Value::Text(s.to_uppercase())),
+            "LOWER" if argc(1, 1) => self
+                .text_arg(&args[0])
+                .map(|s| Value::Text(s.to_lowercase())),
+            "TRIM" if argc(1, 1) => self.text_arg(&args[0]).map(|s| {
+                Value::Text(
+                    s.split(' ')
+                        .filter(|w| !w.is_empty())
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                )
+            }),
+            "SUBSTITUTE" if argc(3, 4) => self.substitute(args),
+            "TEXT" if argc(2, 2) => {
+                let v = self.scalar(&args[0]);
+                self.text_arg(&args[1]).and_then(|fmt| match &v {
+                    Value::Error(e) => Err(e.clone()),
+                    // Text that is not a number passes through unchanged.
+                    Value::Text(s) if parse_number_text(s).is_none() => Ok(v.clone()),
+                    _ => Ok(Value::Text(format_value(
+                        to_number(&v)?,
+                        &fmt,
+                        self.date1904,
+                    ))),
+                })
+            }
+            "VALUE" if argc(1, 1) => self.number_arg(&args[0]).map(Value::Number),
+
+            // Information.
+            "ISBLANK" if argc(1, 1) => {
+                Ok(Value::Bool(matches!(self.scalar(&args[0]), Value::Blank)))
+            }
+            "ISNUMBER" if argc(1, 1) => Ok(Value::Bool(matches!(
+                self.scalar(&args[0]),
+                Value::Number(_)
+            ))),
+            "ISTEXT" if argc(1, 1) => {
+                Ok(Value::Bool(matches!(self.scalar(&args[0]), Value::Text(_))))
+            }
+            "ISLOGICAL" if argc(1, 1) => {
+                Ok(Value::Bool(matches!(self.scalar(&args[0]), Value::Bool(_))))
+            }
+            "ISERROR" if argc(1, 1) => Ok(Value::Bool(self.scalar(&args[0]).is_error())),
+            "ISNA" if argc(1, 1) => Ok(Value::Bool(self.scalar(&args[0]) == Value::error("#N/A"))),
+
+            // Dates.
+            "DATE" if argc(3, 3) => self.date(args),
+            "YEAR" | "MONTH" | "DAY" if argc(1, 1) => self.number_arg(&args[0]).and_then(|n| {
+                if n < 0.0 {
+                    return Err("#NUM!".to_string());
+                }
+                let dt = excel_serial_to_naive_datetime(n.floor(), self.date1904)
+                    .ok_or_else(|| "#NUM!".to_string())?;
+                Ok(Value::Number(match name {
+                    "YEAR" => dt.year() as f64,
+                    "MONTH" => dt.month() as f64,
+                    _ => dt.day() as f64,
+                }))
+            }),
+
+            _ if is_known_function(name) => Err("#VALUE!".to_string()),
+            _ => Err("#NAME?".to_string()),
+        };
+        result.unwrap_or_else(Value::Error)
+    }
+
+    fn logical(&self, all: bool, args: &[Expr]) -> Result<Value, String> {
+        let mut seen = false;
+        let mut acc = all;
+        for arg in args {
+            let v = self.eval(arg);
+            let values: Vec<Value> = match v {
+                Value::Array(_) => v
+                    .flat()
+                    .into_iter()
+                    .filter(|c| matches!(c, Value::Number(_) | Value::Bool(_) | Value::Error(_)))
+                    .collect(),
+                v => vec![v],
+            };
+            for v in values {
+                if matches!(v, Value::Blank) {
+                    continue;
+                }
+                let b = to_bool(&v)?;
+                seen = true;
+                acc = if all { acc && b } else { acc || b };
+            }
+        }
+        if seen {
+            Ok(Value::Bool(acc))
+        } else {
+            Err("#VALUE!".to_string())
+        }
+    }
+
+    fn count(&self, args: &[Expr], pred: impl Fn(&Value) -> bool) -> f64 {
+        args.iter()
+            .flat_map(|a| self.eval(a).flat())
+            .filter(|v| pred(v))
+            .count() as f64
+    }
+
+    fn sumproduct(&self, args: &[Expr]) -> Result<Value, String> {
+        let arrays: Vec<Vec<Value>> = args.iter().map(|a| self.eval(a).flat()).collect();
+        let len = arrays[0].len();
+        if arrays.iter().any(|a| a.len() != len) {
+            return Err("#VALUE!".to_string());
+        }
+        let mut total = 0.0;
+        for i in 0..len {
+            let mut product = 1.0;
+            for a in &arrays {
+                match &a[i] {
+                    Value::Number(n) => product *= n,
+                    Value::Error(e) => return Err(e.clone()),
+                    // Non-numeric entries count as zero.
+                    _ => product = 0.0,
+                }
+            }
+            total += product;
+        }
+        Ok(Value::Number(total))
+    }
+
+    /// Evaluate `(range, criterion)` pairs and return the entries of `values`
+    /// (or of the first range) at the positions where every criterion holds.
+    fn conditional(&self, pairs: &[Expr], values: Option<&Expr>) -> Result<Vec<Value>, String> {
+        let mut matched: Option<Vec<bool>> = None;
+        let mut first_range: Option<Vec<Value>> = None;
+        for pair in pairs.chunks(2) {
+            let range = self.eval(&pair[0]).flat();
+            let criterion = Criterion::parse(&self.scalar(&pair[1]));
+            let hits: Vec<bool> = range.iter().map(|v| criterion.matches(v)).collect();
+            matched = Some(match matched {
+                None => hits,
+                Some(prev) if prev.len() == hits.len() => {
+                    prev.iter().zip(&hits).map(|(a, b)| *a && *b).collect()
+                }
+                Some(_) => return Err("#VALUE!".to_string()),
+            });
+            first_range.get_or_insert(range);
+        }
+        let matched = matched.unwrap_or_default();
+        let values = match values {
+            Some(expr) => self.eval(expr).flat(),
+            None => first_range.unwrap_or_default(),
+        };
+        Ok(matched
+            .iter()
+            .enumerate()
+            .filter(|(_, hit)| **hit)
+            .map(|(i, _)| values.get(i).cloned().unwrap_or(Value::Blank))
+            .collect())
+    }
+
+    fn vhlookup(&self, vertical: bool, args: &[Expr]) -> Result<Value, String> {
+        let target = self.scalar(&args[0]);
+        if let Value::Error(e) = target {
+            return Err(e);
+        }
+        let table = self.eval(&args[1]).rows();
+        let index = self.number_arg(&args[2])? as usize;
+        let approximate = match args.get(3) {
+            Some(a) => to_bool(&self.scalar(a))?,
+            None => true,
+        };
+        let width = if vertical {
+            table.first().map_or(0, Vec::len)
+        } else {
+            table.len()
+        };
+        if index < 1 {
+            return Err("#VALUE!".to_string());
+        }
+        if index > width {
+            return Err("#REF!".to_string());
+        }
+        let keys: Vec<Value> = if vertical {
+            table.iter().map(|r| r[0].clone()).collect()
+        } else {
+            table.first().cloned().unwrap_or_default()
+        };
+        let pos = find_position(&target, &keys, if approximate { 1 } else { 0 })
+            .ok_or_else(|| "#N/A".to_string())?;
+        Ok(if vertical {
+            table[pos][index - 1].clone()
+        } else {
+            table[index - 1][pos].clone()
+        })
+    }
+
+    fn match_fn(&self, args: &[Expr]) -> Result<Value, String> {
+        let target = self.scalar(&args[0]);
+        if let Value::Error(e) = target {
+            return Err(e);
+        }
+        let values = self.eval(&args[1]).flat();
+        // `f64::signum` maps 0.0 to 1.0, so exact match has to be picked out first.
+        let mode = match args.get(2) {
+            Some(a) => match self.number_arg(a)? {
+                n if n == 0.0 => 0,
+                n if n > 0.0 => 1,
+                _ => -1,
+            },
+            None => 1,
+        };
+        find_position(&target, &values, mode)
+            .map(|p| Value::Number((p + 1) as f64))
+            .ok_or_else(|| "#N/A".to_string())
+    }
+
+    fn index(&self, args: &[Expr]) -> Result<Value, String> {
+        let table = self.eval(&args[0]).rows();
+        let height = table.len();
+        let width = table.first().map_or(0, Vec::len);
+        let first = self.number_arg(&args[1])?;
+        let (row, col) = match args.get(2) {
+            Some(a) => (first, self.number_arg(a)?),
+            // A single row or column takes one index along its length.
+            None if height == 1 => (1.0, first),
+            None => (first, if width == 1 { 1.0 } else { 0.0 }),
+        };
+        if row < 0.0 || col < 0.0 {
+            return Err("#VALUE!".to_string());
+        }
+        let (row, col) = (row as usize, col as usize);
+        if row > height || col > width {
+            return Err("#REF!".to_string());
+        }
+        Ok(match (row, col) {
+            (0, 0) => Value::Array(table),
+            (0, c) => Value::Array(table.iter().map(|r| vec![r[c - 1].clone()]).collect()),
+            (r, 0) => Value::Array(vec![table[r - 1].clone()]),
+            (r, c) => table[r - 1][c - 1].clone(),
+        })
+    }
+
+    fn xlookup(&self, args: &[Expr]) -> Result<Value, String> {
+        let target = self.scalar(&args[0]);
+        if let Value::Error(e) = target {
+            return Err(e);
+        }
+        let keys = self.eval(&args[1]).flat();
+        let results = self.eval(&args[2]).flat();
+        match find_position(&target, &keys, 0) {
+            Some(p) => Ok(results.get(p).cloned().unwrap_or(Value::Blank)),
+            None => match args.get(3) {
+                Some(a) => Ok(self.eval(a)),
+                None => Err("#N/A".to_string()),
+            },
+        }
+    }
+
+    fn round(&self, name: &str, args: &[Expr]) -> Result<Value, String> {
+        let n = self.number_arg(&args[0])?;
+        let digits = self.number_arg(&args[1])?.trunc() as i32;
+        let rounded = match name {
+            "ROUND" => round_half_away(n, digits),
+            _ => {
+                let factor = 10f64.powi(digits);
+                // Snap to 15 significant digits first so 0.1+0.2 rounds like 0.3.
+                let scaled: f64 = format!("{:.14e}", n * factor).parse().unwrap_or(n * factor);
+                let r = if name == "ROUNDUP" {
+                    scaled.abs().ceil()
+                } else {
+                    scaled.abs().floor()
+                };
+                r.copysign(n) / factor
+            }
+        };
+        Ok(Value::Number(rounded))
+    }
+
+    fn substitute(&self, args: &[Expr]) -> Result<Value, String> {
+        let text = self.text_arg(&args[0])?;
+        let old = self.text_arg(&args[1])?;
+        let new = self.text_arg(&args[2])?;
+        if old.is_empty() {
+            return Ok(Value::Text(text));
+        }
+        let Some(instance) = args.get(3) else {
+            return Ok(Value::Text(text.replace(&old, &new)));
+        };
+        let instance = self.number_arg(instance)?;
+        if instance < 1.0 {
+            return Err("#VALUE!".to_string());
+        }
+        let Some((at, _)) = text.match_indices(&old).nth(instance as usize - 1) else {
+            return Ok(Value::Text(text));
+        };
+        Ok(Value::Text(format!(
+            "{}{}{}",
+            &text[..at],
+            new,
+            &text[at + old.len()..]
+        )))
+    }
+
+    fn date(&self, args: &[Expr]) -> Result<Value, String> {
+        let mut year = self.number_arg(&args[0])?.trunc() as i64;
+        let month = self.number_arg(&args[1])?.trunc() as i64;
+        let day = self.number_arg(&args[2])?.trunc() as i64;
+        if !(0..=9999).contains(&year) {
+            return Err("#NUM!".to_string());
+        }
+        if year < 1900 {
+            year += 1900;
+        }
+        // Months and days outside their usual range roll over, as in Excel.
+        // Huge ones saturate in the casts above, so the arithmetic is checked.
+        let date = year
+            .checked_mul(12)
+            .and_then(|m| m.checked_add(month))
+            .and_then(|m| m.checked_sub(1))
+            .and_then(|months| {
+                let year = i32::try_from(months.div_euclid(12)).ok()?;
+                NaiveDate::from_ymd_opt(year, 1, 1)?
+                    .checked_add_months(Months::new(months.rem_euclid(12) as u32))
+            })
+            .zip(day.checked_sub(1).and_then(TimeDelta::try_days))
+            .and_then(|(first, days)| first.checked_add_signed(days))
+            .ok_or_else(|| "#NUM!".to_string())?;
+        let mut serial =
+            naive_datetime_to_excel_serial(date.and_time(NaiveTime::MIN), self.date1904)
+                .ok_or_else(|| "#NUM!".to_string())?;
+        // Serials before 1900-03-01 sit one below the epoch count because of
+        // Excel's phantom 1900-02-29.
+        if !self.date1904 && serial < 61.0 {
+            serial -= 1.0;
+        }
+        if serial < 0.0 {
+            return Err("#NUM!".to_string());
+        }
+        Ok(Value::Number(serial))
+    }
+}
+
+fn number_only(v: &Value) -> Option<f64> {
+    match v {
+        Value::Number(n) => Some(*n),
+        _ => None,
+    }
+}
+
+fn average(ns: &[f64]) -> Result<Value, String> {
+    if ns.is_empty() {
+        Err("#DIV/0!".to_string())
+    } else {
+        Ok(Value::Number(ns.iter().sum::<f64>() / ns.len() as f64))
+    }
+}
+
+/// Position of `target` in `values`: `mode` 0 is an exact match, 1 the largest
+/// value <= target (ascending data), -1 the smallest value >= target
+/// (descending data).
+fn find_position(target: &Value, values: &[Value], mode: i32) -> Option<usize> {
+    if mode == 0 {
+        return values.iter().position(|v| lookup_eq(target, v));
+    }
+    let mut best = None;
+    for (i, v) in values.iter().enumerate() {
+        if !same_type(target, v) {
+            continue;
+        }
+        let ord = compare(v, target);
+        let ok = if mode > 0 {
+            ord != Ordering::Greater
+        } else {
+            ord != Ordering::Less
+        };
+        if ok {
+            best = Some(i);
+        } else {
+            break;
+        }
+    }
+    best
+}
+
+fn map_unary(v: Value, f: impl Fn(&Value) -> Result<Value, String>) -> Value {
+    match v {
+        Value::Array(rows) => Value::Array(
+            rows.into_iter()
+                .map(|r| {
+                    r.iter()
+                        .map(|c| f(c).unwrap_or_else(Value::Error))
+                        .collect()
+                })
+                .collect(),
+        ),
+        v => f(&v).unwrap_or_else(Value::Error),
+    }
+}
+
+/// Apply a binary operator, element-wise when either side is a range.
+fn broadcast(a: Value, b: Value, f: impl Fn(&Value, &Value) -> Value) -> Value {
+    match (a, b) {
+        (Value::Array(x), Value::Array(y)) => {
+            let height = x.len().max(y.len());
+            let width = x
+                .first()
+                .map_or(0, Vec::len)
+                .max(y.first().map_or(0, Vec::len));
+            let pick = |m: &Vec<Vec<Value>>, r: usize, c: usize| -> Value {
+                // A single row or column stretches across the other operand.
+                let r = if m.len() == 1 { 0 } else { r };
+                let c = if m.first().map_or(0, Vec::len) == 1 {
+                    0
+                } else {
+                    c
+                };
+                m.get(r)
+                    .and_then(|row| row.get(c))
+                    .cloned()
+                    .unwrap_or_else(|| Value::error("#N/A"))
+            };
+            Value::Array(
+                (0..height)
+                    .map(|r| {
+                        (0..width)
+                            .map(|c| f(&pick(&x, r, c), &pick(&y, r, c)))
+                            .collect()
+                    })
+                    .collect(),
+            )
+        }
+        (Value::Array(x), b) => Value::Array(
+            x.iter()
+                .map(|r| r.iter().map(|c| f(c, &b)).collect())
+                .collect(),
+        ),
+        (a, Value::Array(y)) => Value::Array(
+            y.iter()
+                .map(|r| r.iter().map(|c| f(&a, c)).collect())
+                .collect(),
+        ),
+        (a, b) => f(&a, &b),
+    }
+}
+
+fn binary(op: BinOp, a: &Value, b: &Value) -> Value {
+    if let Value::Error(e) = a {
+        return Value::Error(e.clone());
+    }
+    if let Value::Error(e) = b {
+        return Value::Error(e.clone());
+    }
+    let result = match op {
+        BinOp::Concat => to_text(a).and_then(|x| Ok(Value::Text(x + &to_text(b)?))),
+        BinOp::Eq => Ok(Value::Bool(compare(a, b) == Ordering::Equal)),
+        BinOp::Ne => Ok(Value::Bool(compare(a, b) != Ordering::Equal)),
+        BinOp::Lt => Ok(Value::Bool(compare(a, b) == Ordering::Less)),
+        BinOp::Le => Ok(Value::Bool(compare(a, b) != Ordering::Greater)),
+        BinOp::Gt => Ok(Value::Bool(compare(a, b) == Ordering::Greater)),
+        BinOp::Ge => Ok(Value::Bool(compare(a, b) != Ordering::Less)),
+        _ => to_number(a).and_then(|x| {
+            let y = to_number(b)?;
+            let n = match op {
+                BinOp::Add => x + y,
+                BinOp::Sub => x - y,
+                BinOp::Mul => x * y,
+                BinOp::Div if y == 0.0 => return Err("#DIV/0!".to_string()),
+                BinOp::Div => x / y,
+                BinOp::Pow if x == 0.0 && y == 0.0 => return Err("#NUM!".to_string()),
+                _ => x.powf(y),
+            };
+            if n.is_finite() {
+                Ok(Value::Number(n))
+            } else if op == BinOp::Pow && x == 0.0 {
+                Err("#DIV/0!".to_string())
+            } else {
+                Err("#NUM!".to_string())
+            }
+        }),
+    };
+    result.unwrap_or_else(Value::Error)
+}
+
+/// Functions that exist but were called with the wrong number of arguments.
+fn is_known_function(name: &str) -> bool {
+    matches!(
+        name,
+        "IF" | "IFERROR"
+            | "IFNA"
+            | "AND"
+            | "OR"
+            | "NOT"
+            | "TRUE"
+            | "FALSE"
+            | "COUNTBLANK"
+            | "SUMPRODUCT"
+            | "COUNTIF"
+            | "COUNTIFS"
+            | "SUMIF"
+            | "SUMIFS"
+            | "AVERAGEIF"
+            | "AVERAGEIFS"
+            | "VLOOKUP"
+            | "HLOOKUP"
+            | "MATCH"
+            | "INDEX"
+            | "XLOOKUP"
+            | "ROUND"
+            | "ROUNDUP"
+            | "ROUNDDOWN"
+            | "INT"
+            | "ABS"
+            | "SQRT"
+            | "MOD"
+            | "POWER"
+            | "LEN"
+            | "LEFT"
+            | "RIGHT"
+            | "MID"
+            | "UPPER"
+            | "LOWER"
+            | "TRIM"
+            | "SUBSTITUTE"
+            | "TEXT"
+            | "VALUE"
+            | "ISBLANK"
+            | "ISNUMBER"
+            | "ISTEXT"
+            | "ISLOGICAL"
+            | "ISERROR"
+            | "ISNA"
+            | "DATE"
+            | "YEAR"
+            | "MONTH"
+            | "DAY"
+    )
+}
@@ -1,18 +1,221 @@
-use chrono::NaiveTime;
-use pyo3::exceptions::PyValueError;
+use std::cell::OnceCell;
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use pyo3::exceptions::{PyTypeError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::PyDict;
+use pyo3::types::{PyBool, PyDate, PyDateTime, PyDelta, PyDict, PyFloat, PyInt, PyList, PyTime};
 
-use umya_spreadsheet::structs::CellRawValue;
-use umya_spreadsheet::{NumberingFormat, Spreadsheet};
+use umya_spreadsheet::helper::coordinate::CellCoordinates;
+use umya_spreadsheet::structs::{Cell, CellFormula, CellFormulaValues, CellRawValue};
+use umya_spreadsheet::{NumberingFormat, Spreadsheet, Worksheet};
 
+use crate::formula_parser::{translate_formula, LineShift};
+use crate::number_format::{classify_number_format, NumberFormatKind};
+use crate::rich_text_ops::{has_alignment, parse_rich_text, rich_text_payload};
+use crate::structural_ops::cell_with_row_col_style;
 use crate::utils::{
-    a1_to_row_col, cell_blank, cell_with_value, excel_serial_to_naive_datetime,
-    looks_like_date_format, naive_datetime_to_excel_serial, parse_iso_date, parse_iso_datetime,
+    a1_range_to_bounds, a1_to_row_col, cell_blank, cell_with_value, duration_to_excel_serial,
+    excel_serial_to_duration, excel_serial_to_naive_datetime, excel_serial_to_naive_time,
+    is_error_token, naive_datetime_to_excel_serial, naive_time_to_excel_serial, parse_iso_date,
+    parse_iso_datetime, parse_iso_time, u32_to_col_letter,
 };
 
+/// Array-formula anchors flagged as dynamic arrays, by sheet name and 1-based
+/// `(row, col)`.
+pub(crate) type DynamicArrays = HashMap<String, HashSet<(u32, u32)>>;
+
+/// `vertAlign` of each run of rich text cells that have raised or lowered
+/// runs, by sheet name and 1-based `(row, col)`.
+pub(crate) type RunAlignments = HashMap<String, HashMap<(u32, u32), Vec<String>>>;
+
+/// Cell details umya does not model. They are read from the package on open,
+/// kept alongside the workbook, and patched back into the package on save.
+#[derive(Debug, Default)]
+pub(crate) struct CellExtras {
+    pub dynamic_arrays: DynamicArrays,
+    pub run_alignments: RunAlignments,
+}
+
+impl CellExtras {
+    /// Forget anything recorded for a cell that is being overwritten.
+    pub(crate) fn clear(&mut self, sheet: &str, cell: (u32, u32)) {
+        if let Some(cells) = self.dynamic_arrays.get_mut(sheet) {
+            cells.remove(&cell);
+        }
+        if let Some(cells) = self.run_alignments.get_mut(sheet) {
+            cells.remove(&cell);
+        }
+    }
+
+    /// Move the extras of a sheet along with its cells when rows or columns
+    /// are inserted or deleted.
+    pub(crate) fn shift(&mut self, sheet: &str, shift: LineShift) {
+        if let Some(cells) = self.dynamic_arrays.get_mut(sheet) {
+            *cells = cells.drain().filter_map(|cell| shift.cell(cell)).collect();
+        }
+        if let Some(cells) = self.run_alignments.get_mut(sheet) {
+            shift.move_cells(cells);
+        }
+    }
+
+    /// Re-key the extras of a renamed sheet.
+    pub(crate) fn rename_sheet(&mut self, old: &str, new: &str) {
+        if let Some(cells) = self.dynamic_arrays.remove(old) {
+            self.dynamic_arrays.insert(new.to_string(), cells);
+        }
+        if let Some(cells) = self.run_alignments.remove(old) {
+            self.run_alignments.insert(new.to_string(), cells);
+        }
+    }
+
+    /// Drop the extras of a removed sheet.
+    pub(crate) fn remove_sheet(&mut self, sheet: &str) {
+        self.dynamic_arrays.remove(sheet);
+        self.run_alignments.remove(sheet);
+    }
+
+    /// The extras that still apply to `book`: dynamic flags on cells that hold
+    /// an array formula, and run alignments on rich text with as many runs.
+    pub(crate) fn live(&self, book: &Spreadsheet) -> CellExtras {
+        let mut out = CellExtras::default();
+        for (sheet, cells) in &self.dynamic_arrays {
+            let Some(ws) = book.get_sheet_by_name(sheet) else {
+                continue;
+            };
+            let live: HashSet<(u32, u32)> = cells
+                .iter()
+                .copied()
+                .filter(|&(row, col)| {
+                    ws.get_cell((col, row))
+                        .and_then(|c| c.get_formula_obj())
+                        .is_some_and(|f| matches!(f.get_formula_type(), CellFormulaValues::Array))
+                })
+                .collect();
+            if !live.is_empty() {
+                out.dynamic_arrays.insert(sheet.clone(), live);
+            }
+        }
+        for (sheet, cells) in &self.run_alignments {
+            let Some(ws) = book.get_sheet_by_name(sheet) else {
+                continue;
+            };
+            let live: HashMap<(u32, u32), Vec<String>> = cells
+                .iter()
+                .filter(|&(&(row, col), aligns)| {
+                    ws.get_cell((col, row))
+                        .is_some_and(|c| match c.get_raw_value() {
+                            CellRawValue::RichText(rt) => {
+                                rt.get_rich_text_elements().len() == aligns.len()
+                            }
+                            _ => false,
+                        })
+                })
+                .map(|(&cell, aligns)| (cell, aligns.clone()))
+                .collect();
+            if !live.is_empty() {
+                out.run_alignments.insert(sheet.clone(), live);
+            }
+        }
+        out
+    }
+}
+
+/// Workbook-level settings that affect how stored cell values are decoded.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ReadOptions<'a> {
+    /// Serials count from 1904-01-01 instead of 1899-12-30.
+    pub date1904: bool,
+    /// Report the cached result of formula cells instead of the formula (openpyxl's `data_only`).
+    pub data_only: bool,
+    /// Report rich text cells as runs instead of plain strings (openpyxl's `rich_text`).
+    pub rich_text: bool,
+    pub extras: &'a CellExtras,
+}
+
+/// Master cell of a shared formula group.
+pub(crate) struct SharedMaster {
+    pub row: u32,
+    pub col: u32,
+    pub text: String,
+}
+
+impl SharedMaster {
+    /// The master's formula as it applies to the dependent at `(row, col)`.
+    pub(crate) fn formula_at(&self, row: u32, col: u32) -> String {
+        let rows = i64::from(row) - i64::from(self.row);
+        let cols = i64::from(col) - i64::from(self.col);
+        translate_formula(&self.text, rows, cols).unwrap_or_else(|_| self.text.clone())
+    }
+}
+
+/// Formula lookups for one sheet. Shared-formula masters are only indexed
+/// once a dependent cell needs one.
+pub(crate) struct SheetFormulas<'a> {
+    ws: &'a Worksheet,
+    dynamic: Option<&'a HashSet<(u32, u32)>>,
+    masters: OnceCell<HashMap<u32, SharedMaster>>,
+}
+
+impl<'a> SheetFormulas<'a> {
+    pub(crate) fn new(ws: &'a Worksheet, dynamic: Option<&'a HashSet<(u32, u32)>>) -> Self {
+        Self {
+            ws,
+            dynamic,
+            masters: OnceCell::new(),
+        }
+    }
+
+    fn master(&self, si: u32) -> Option<&SharedMaster> {
+        self.masters
+            .get_or_init(|| {
+                let mut masters = HashMap::new();
+                for cell in self.ws.get_cell_collection() {
+                    let Some(f) = cell.get_formula_obj() else {
+                        continue;
+                    };
+                    if matches!(f.get_formula_type(), CellFormulaValues::Shared)
+                        && !f.get_text().is_empty()
+                    {
+                        let coord = cell.get_coordinate();
+                        masters.insert(
+                            *f.get_shared_index(),
+                            SharedMaster {
+                                row: *coord.get_row_num(),
+                                col: *coord.get_col_num(),
+                                text: f.get_text().to_string(),
+                            },
+                        );
+                    }
+                }
+                masters
+            })
+            .get(&si)
+    }
+
+    /// The formula of a cell, with shared-formula dependents translated from
+    /// their master. `None` for cells without a formula.
+    pub(crate) fn formula_text(&self, cell: &Cell) -> Option<String> {
+        let f = cell.get_formula_obj()?;
+        if !f.get_text().is_empty() {
+            return Some(f.get_text().to_string());
+        }
+        if !matches!(f.get_formula_type(), CellFormulaValues::Shared) {
+            return None;
+        }
+        let coord = cell.get_coordinate();
+        self.master(*f.get_shared_index())
+            .map(|m| m.formula_at(*coord.get_row_num(), *coord.get_col_num()))
+    }
+
+    fn is_dynamic(&self, row: u32, col: u32) -> bool {
+        self.dynamic.is_some_and(|d| d.contains(&(row, col)))
+    }
+}
+
 pub(crate) fn read_cell_value(
     book: &Spreadsheet,
+    opts: ReadOptions<'_>,
     py: Python<'_>,
     sheet: &str,
     a1: &str,
@@ -24,51 +227,168 @@ pub(crate) fn read_cell_value(
     let (row0, col0) = a1_to_row_col(a1).map_err(|msg| PyErr::new::<PyValueError, _>(msg))?;
     let coord = (col0 + 1, row0 + 1);
 
-    let cell = match ws.get_cell(coord) {
-        Some(c) => c,
-        None => return cell_blank(py),
-    };
+    let formulas = SheetFormulas::new(ws, opts.extras.dynamic_arrays.get(sheet));
+    match ws.get_cell(coord) {
+        Some(cell) => cell_payload(py, cell, opts, sheet, &formulas),
+        None => cell_blank(py),
+    }
+}
 
-    // Formula wins over value.
-    let formula = cell.get_formula();
-    if !formula.is_empty() {
-        // Map well-known error formulas to error tokens.
-        let norm = if formula.starts_with('=') {
-            formula.to_string()
-        } else {
-            format!("={formula}")
-        };
-        let token = match norm.as_str() {
-            "=1/0" => Some("#DIV/0!"),
-            "=NA()" => Some("#N/A"),
-            "=\"text\"+1" => Some("#VALUE!"),
-            _ => None,
-        };
-        if let Some(t) = token {
-            return cell_with_value(py, "error", t);
+/// Read a rectangular range as a list of row lists of typed payloads.
+///
+/// Walks the sorted cell collection once instead of probing every coordinate,
+/// so sparse and large ranges cost a single FFI crossing. The grid stops at the
+/// sheet's last used row and column, so asking for a whole column or the full
+/// sheet never allocates past the data; callers treat anything beyond it as
+/// blank. Missing cells inside the grid are reported as blank payloads.
+pub(crate) fn read_range_values(
+    book: &Spreadsheet,
+    opts: ReadOptions<'_>,
+    py: Python<'_>,
+    sheet: &str,
+    range: &str,
+) -> PyResult<Py<PyAny>> {
+    let ws = book
+        .get_sheet_by_name(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+
+    let (min_row0, min_col0, max_row0, max_col0) =
+        a1_range_to_bounds(range).map_err(|msg| PyErr::new::<PyValueError, _>(msg))?;
+    let (min_row, min_col) = (min_row0 + 1, min_col0 + 1);
+    let max_row = (max_row0 + 1).min(ws.get_highest_row());
+    let max_col = (max_col0 + 1).min(ws.get_highest_column());
+    let height = (max_row + 1).saturating_sub(min_row) as usize;
+    let width = (max_col + 1).saturating_sub(min_col) as usize;
+
+    let mut grid: Vec<Vec<Option<Py<PyAny>>>> = (0..height)
+        .map(|_| (0..width).map(|_| None).collect())
+        .collect();
+
+    let formulas = SheetFormulas::new(ws, opts.extras.dynamic_arrays.get(sheet));
+    for cell in ws.get_cell_collection_sorted() {
+        let coord = cell.get_coordinate();
+        let row = *coord.get_row_num();
+        let col = *coord.get_col_num();
+        if row < min_row || row > max_row || col < min_col || col > max_col {
+            continue;
+        }
+        grid[(row - min_row) as usize][(col - min_col) as usize] =
+            Some(cell_payload(py, cell, opts, sheet, &formulas)?);
+    }
+
+    let rows = PyList::empty(py);
+    for grid_row in grid {
+        let row_list = PyList::empty(py);
+        for payload in grid_row {
+            match payload {
+                Some(p) => row_list.append(p)?,
+                None => row_list.append(cell_blank(py)?)?,
+            }
+        }
+        rows.append(row_list)?;
+    }
+
+    Ok(rows.into_any().unbind())
+}
+
+/// Build the typed payload dict for a single existing cell.
+fn cell_payload(
+    py: Python<'_>,
+    cell: &Cell,
+    opts: ReadOptions<'_>,
+    sheet: &str,
+    formulas: &SheetFormulas<'_>,
+) -> PyResult<Py<PyAny>> {
+    // Formula wins over value, unless only cached results were requested.
+    let formula = match formulas.formula_text(cell) {
+        Some(f) if !opts.data_only => f,
+        _ => {
+            if let (true, CellRawValue::RichText(rt)) = (opts.rich_text, cell.get_raw_value()) {
+                let coord = cell.get_coordinate();
+                let aligns = opts
+                    .extras
+                    .run_alignments
+                    .get(sheet)
+                    .and_then(|cells| cells.get(&(*coord.get_row_num(), *coord.get_col_num())));
+                return rich_text_payload(py, rt, aligns);
+            }
+            return value_payload(py, cell, opts.date1904);
         }
+    };
+
+    let d = PyDict::new(py);
+    d.set_item("type", "formula")?;
+    d.set_item("formula", &formula)?;
+    d.set_item("value", &formula)?;
+    // The last result Excel computed, typed like any other cell value.
+    d.set_item("cached_value", value_payload(py, cell, opts.date1904)?)?;
 
-        let d = PyDict::new(py);
-        d.set_item("type", "formula")?;
-        d.set_item("formula", formula.to_string())?;
-        d.set_item("value", formula.to_string())?;
+    let Some(f) = cell.get_formula_obj() else {
         return Ok(d.into_any().unbind());
+    };
+    let coord = cell.get_coordinate();
+    let (row, col) = (*coord.get_row_num(), *coord.get_col_num());
+    match f.get_formula_type() {
+        CellFormulaValues::Shared => {
+            let si = *f.get_shared_index();
+            d.set_item("formula_type", "shared")?;
+            d.set_item("shared_index", si)?;
+            if !f.get_reference().is_empty() {
+                d.set_item("ref", f.get_reference())?;
+            } else if let Some(m) = formulas.master(si) {
+                d.set_item(
+                    "shared_master",
+                    format!("{}{}", u32_to_col_letter(m.col), m.row),
+                )?;
+            }
+        }
+        CellFormulaValues::Array => {
+            let reference = match f.get_reference() {
+                "" => format!("{}{}", u32_to_col_letter(col), row),
+                r => r.to_string(),
+            };
+            d.set_item("formula_type", "array")?;
+            d.set_item("ref", reference)?;
+            d.set_item("dynamic", formulas.is_dynamic(row, col))?;
+        }
+        CellFormulaValues::DataTable => {
+            d.set_item("formula_type", "dataTable")?;
+            d.set_item("ref", f.get_reference())?;
+        }
+        _ => {
+            d.set_item("formula_type", "normal")?;
+        }
     }
+    Ok(d.into_any().unbind())
+}
 
+/// Build the typed payload for the stored value of a cell, ignoring any formula.
+///
+/// For formula cells this is the cached result written by the last application
+/// that calculated the workbook.
+fn value_payload(py: Python<'_>, cell: &Cell, date1904: bool) -> PyResult<Py<PyAny>> {
     // Numeric typed access.
     if let Some(f) = cell.get_value_number() {
         if let Some(nf) = cell.get_style().get_number_format() {
-            let code = nf.get_format_code();
-            if looks_like_date_format(code) {
-                if let Some(ndt) = excel_serial_to_naive_datetime(f) {
-                    let midnight = NaiveTime::from_hms_opt(0, 0, 0).unwrap();
-                    if ndt.time() == midnight {
-                        let s = ndt.date().format("%Y-%m-%d").to_string();
-                        return cell_with_value(py, "date", s);
+            let kind = classify_number_format(*nf.get_number_format_id(), nf.get_format_code());
+            match kind {
+                NumberFormatKind::Duration => {
+                    return cell_with_value(py, "duration", excel_serial_to_duration(f));
+                }
+                NumberFormatKind::Time if (0.0..1.0).contains(&f) => {
+                    if let Some(t) = excel_serial_to_naive_time(f) {
+                        return cell_with_value(py, "time", t);
                     }
-                    let s = ndt.format("%Y-%m-%dT%H:%M:%S").to_string();
-                    return cell_with_value(py, "datetime", s);
                 }
+                NumberFormatKind::Date | NumberFormatKind::DateTime | NumberFormatKind::Time => {
+                    if let Some(ndt) = excel_serial_to_naive_datetime(f, date1904) {
+                        if kind == NumberFormatKind::Date && ndt.time() == NaiveTime::MIN {
+                            return cell_with_value(py, "date", ndt.date());
+                        }
+                        return cell_with_value(py, "datetime", ndt);
+                    }
+                }
+                _ => {}
             }
         }
         return cell_with_value(py, "number", f);
@@ -89,18 +409,14 @@ pub(crate) fn read_cell_value(
         _ => {}
     }
 
-    // Fallback typing: error/bool/string.
+    // Fallback typing: string. Error tokens stored as text stay text; genuine
+    // errors are `t="e"` cells handled above.
     let raw = cell
         .get_value()
         .into_owned()
         .replace("\r\n", "\n")
         .replace('\r', "\n");
 
-    // Errors
-    if raw == "#N/A" || (raw.starts_with('#') && raw.ends_with('!')) {
-        return cell_with_value(py, "error", raw);
-    }
-
     if raw.is_empty() {
         return cell_blank(py);
     }
@@ -110,6 +426,8 @@ pub(crate) fn read_cell_value(
 
 pub(crate) fn write_cell_value(
     book: &mut Spreadsheet,
+    date1904: bool,
+    extras: &mut CellExtras,
     sheet: &str,
     a1: &str,
     payload: &Bound<'_, PyAny>,
@@ -118,6 +436,13 @@ pub(crate) fn write_cell_value(
         .get_sheet_by_name_mut(sheet)
         .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
 
+    // Any write replaces what was recorded for the cell; dynamic array
+    // formulas and aligned rich text record it again below.
+    let (row0, col0) = a1_to_row_col(a1).map_err(|msg| PyErr::new::<PyValueError, _>(msg))?;
+    extras.clear(sheet, (row0 + 1, col0 + 1));
+    // A new cell in a formatted row or column starts from that formatting.
+    cell_with_row_col_style(ws, (col0 + 1, row0 + 1));
+
     let dict = payload
         .cast::<PyDict>()
         .map_err(|_| PyErr::new::<PyValueError, _>("payload must be a dict"))?;
@@ -149,6 +474,22 @@ pub(crate) fn write_cell_value(
             ws.get_cell_mut(a1).set_value_number(f);
             Ok(())
         }
+        "rich_text" => {
+            let base = ws
+                .get_cell(a1)
+                .and_then(|c| c.get_style().get_font())
+                .cloned();
+            let (rt, aligns) = parse_rich_text(dict, base.as_ref())?;
+            ws.get_cell_mut(a1).set_rich_text(rt);
+            if has_alignment(&aligns) {
+                extras
+                    .run_alignments
+                    .entry(sheet.to_string())
+                    .or_default()
+                    .insert((row0 + 1, col0 + 1), aligns);
+            }
+            Ok(())
+        }
         "boolean" => {
             let v = dict
                 .get_item("value")?
@@ -169,7 +510,14 @@ pub(crate) fn write_cell_value(
             };
             let formula = v.extract::<String>()?;
             let f = formula.strip_prefix('=').unwrap_or(&formula);
-            ws.get_cell_mut(a1).set_formula(f);
+            let dynamic = write_formula(ws, (row0 + 1, col0 + 1), f, dict)?;
+            if dynamic {
+                extras
+                    .dynamic_arrays
+                    .entry(sheet.to_string())
+                    .or_default()
+                    .insert((row0 + 1, col0 + 1));
+            }
             Ok(())
         }
         "error" => {
@@ -177,52 +525,54 @@ pub(crate) fn write_cell_value(
                 .get_item("value")?
                 .ok_or_else(|| PyErr::new::<PyValueError, _>("error payload missing 'value'"))?;
             let token = v.extract::<String>()?;
-
-            // Prefer formulas for well-known errors; otherwise write the literal token.
-            let formula = match token.as_str() {
-                "#DIV/0!" => Some("1/0"),
-                "#N/A" => Some("NA()"),
-                "#VALUE!" => Some("\"text\"+1"),
-                _ => None,
-            };
-            if let Some(f) = formula {
-                ws.get_cell_mut(a1).set_formula(f);
-            } else {
-                ws.get_cell_mut(a1).set_value_string(token);
-            }
-            Ok(())
+            write_error(ws, a1, &token)
         }
         "date" => {
             let v = dict
                 .get_item("value")?
                 .ok_or_else(|| PyErr::new::<PyValueError, _>("date payload missing 'value'"))?;
-            let s = v.extract::<String>()?;
-            let d = parse_iso_date(&s)
-                .ok_or_else(|| PyErr::new::<PyValueError, _>("Invalid ISO date"))?;
-            let dt = d.and_time(NaiveTime::from_hms_opt(0, 0, 0).unwrap());
-            let serial = naive_datetime_to_excel_serial(dt)
-                .ok_or_else(|| PyErr::new::<PyValueError, _>("Failed to convert date"))?;
-
-            ws.get_cell_mut(a1).set_value_number(serial);
-            ws.get_style_mut(a1)
-                .get_number_format_mut()
-                .set_format_code(NumberingFormat::FORMAT_DATE_YYYYMMDD);
-            Ok(())
+            // Accept a native `datetime.date` or an ISO string. A `datetime`
+            // is a `date` too, but would lose its time here.
+            if v.is_instance_of::<PyDateTime>() {
+                return Err(PyErr::new::<PyValueError, _>(
+                    "date payload got a datetime; use a datetime payload",
+                ));
+            }
+            let d = match v.extract::<NaiveDate>() {
+                Ok(d) => d,
+                Err(_) => parse_iso_date(&v.extract::<String>()?)
+                    .ok_or_else(|| PyErr::new::<PyValueError, _>("Invalid ISO date"))?,
+            };
+            write_date(ws, a1, d, date1904)
         }
         "datetime" => {
             let v = dict
                 .get_item("value")?
                 .ok_or_else(|| PyErr::new::<PyValueError, _>("datetime payload missing 'value'"))?;
-            let s = v.extract::<String>()?;
-            let dt = parse_iso_datetime(&s)
-                .ok_or_else(|| PyErr::new::<PyValueError, _>("Invalid ISO datetime"))?;
-            let serial = naive_datetime_to_excel_serial(dt)
-                .ok_or_else(|| PyErr::new::<PyValueError, _>("Failed to convert datetime"))?;
-
-            ws.get_cell_mut(a1).set_value_number(serial);
-            ws.get_style_mut(a1)
-                .get_number_format_mut()
-                .set_format_code("yyyy-mm-dd h:mm:ss");
+            let dt = match v.extract::<NaiveDateTime>() {
+                Ok(dt) => dt,
+                Err(_) => parse_iso_datetime(&v.extract::<String>()?)
+                    .ok_or_else(|| PyErr::new::<PyValueError, _>("Invalid ISO datetime"))?,
+            };
+            write_datetime(ws, a1, dt, date1904)
+        }
+        "time" => {
+            let v = dict
+                .get_item("value")?
+                .ok_or_else(|| PyErr::new::<PyValueError, _>("time payload missing 'value'"))?;
+            let t = match v.extract::<NaiveTime>() {
+                Ok(t) => t,
+                Err(_) => parse_iso_time(&v.extract::<String>()?)
+                    .ok_or_else(|| PyErr::new::<PyValueError, _>("Invalid ISO time"))?,
+            };
+            write_time(ws, a1, t);
+            Ok(())
+        }
+        "duration" => {
+            let v = dict
+                .get_item("value")?
+                .ok_or_else(|| PyErr::new::<PyValueError, _>("duration payload missing 'value'"))?;
+            write_duration(ws, a1, v.extract::<Duration>()?);
             Ok(())
         }
         other => Err(PyErr::new::<PyValueError, _>(format!(
@@ -230,3 +580,305 @@ pub(crate) fn write_cell_value(
         ))),
     }
 }
+
+/// Write a formula payload at `(row, col)`, honoring the optional
+/// `formula_type` (`"normal"`, `"shared"` or `"array"`), `ref`,
+/// `shared_index` and `dynamic` fields. Returns whether the cell is a dynamic
+/// array anchor.
+fn write_formula(
+    ws: &mut Worksheet,
+    (row, col): (u32, u32),
+    text: &str,
+    dict: &Bound<'_, PyDict>,
+) -> PyResult<bool> {
+    let formula_type = match dict.get_item("formula_type")? {
+        Some(v) if !v.is_none() => v.extract::<String>()?,
+        _ => "normal".to_string(),
+    };
+    let reference = match dict.get_item("ref")? {
+        Some(v) if !v.is_none() => Some(v.extract::<String>()?.replace('$', "").to_uppercase()),
+        _ => None,
+    };
+    let bounds = match &reference {
+        Some(r) => {
+            let (r1, c1, r2, c2) =
+                a1_range_to_bounds(r).map_err(|msg| PyErr::new::<PyValueError, _>(msg))?;
+            if (r1 + 1, c1 + 1) != (row, col) {
+                return Err(PyErr::new::<PyValueError, _>(format!(
+                    "Formula ref {r} must start at the formula cell"
+                )));
+            }
+            Some((r1 + 1, c1 + 1, r2 + 1, c2 + 1))
+        }
+        None => None,
+    };
+
+    match formula_type.as_str() {
+        "normal" => {
+            ws.get_cell_mut((col, row)).set_formula(text);
+            Ok(false)
+        }
+        "array" => {
+            let mut f = CellFormula::default();
+            f.set_formula_type(CellFormulaValues::Array);
+            f.set_reference(
+                reference.unwrap_or_else(|| format!("{}{}", u32_to_col_letter(col), row)),
+            );
+            f.set_text(text);
+            set_formula_obj(ws, (col, row), f);
+            let dynamic = match dict.get_item("dynamic")? {
+                Some(v) => v.extract::<bool>()?,
+                None => false,
+            };
+            Ok(dynamic)
+        }
+        "shared" => {
+            let si = match dict.get_item("shared_index")? {
+                Some(v) if !v.is_none() => v.extract::<u32>()?,
+                _ if bounds.is_some() => next_shared_index(ws),
+                _ => {
+                    return Err(PyErr::new::<PyValueError, _>(
+                        "shared formula payload needs 'ref' or 'shared_index'",
+                    ))
+                }
+            };
+            let Some((r1, c1, r2, c2)) = bounds else {
+                // A dependent of an existing group.
+                let mut f = CellFormula::default();
+                f.set_formula_type(CellFormulaValues::Shared);
+                f.set_shared_index(si);
+                set_formula_obj(ws, (col, row), f);
+                return Ok(false);
+            };
+            for r in r1..=r2 {
+                for c in c1..=c2 {
+                    let mut f = CellFormula::default();
+                    f.set_formula_type(CellFormulaValues::Shared);
+                    f.set_shared_index(si);
+                    if (r, c) == (row, col) {
+                        f.set_reference(reference.clone().unwrap_or_default());
+                        f.set_text(text);
+                    }
+                    set_formula_obj(ws, (c, r), f);
+                }
+            }
+            Ok(false)
+        }
+        other => Err(PyErr::new::<PyValueError, _>(format!(
+            "Unsupported formula_type: {other}"
+        ))),
+    }
+}
+
+pub(crate) fn set_formula_obj(ws: &mut Worksheet, coord: (u32, u32), f: CellFormula) {
+    ws.get_cell_mut(coord)
+        .get_cell_value_mut()
+        .set_formula_obj(f);
+}
+
+/// First shared-formula index not used on the sheet.
+fn next_shared_index(ws: &Worksheet) -> u32 {
+    ws.get_cell_collection()
+        .iter()
+        .filter_map(|c| c.get_formula_obj())
+        .filter(|f| matches!(f.get_formula_type(), CellFormulaValues::Shared))
+        .map(|f| *f.get_shared_index() + 1)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Array formula anchors on a sheet (`"A1"`) mapped to their `ref`.
+pub(crate) fn array_formula_ranges(
+    book: &Spreadsheet,
+    sheet: &str,
+) -> PyResult<HashMap<String, String>> {
+    let ws = book
+        .get_sheet_by_name(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+    let mut out = HashMap::new();
+    for cell in ws.get_cell_collection() {
+        let Some(f) = cell.get_formula_obj() else {
+            continue;
+        };
+        if !matches!(f.get_formula_type(), CellFormulaValues::Array) {
+            continue;
+        }
+        let coord = cell.get_coordinate();
+        let a1 = format!(
+            "{}{}",
+            u32_to_col_letter(*coord.get_col_num()),
+            coord.get_row_num()
+        );
+        let reference = match f.get_reference() {
+            "" => a1.clone(),
+            r => r.to_string(),
+        };
+        out.insert(a1, reference);
+    }
+    Ok(out)
+}
+
+/// Write a 2D block of native Python values starting at `top_left`.
+///
+/// Each row is any iterable of values; the cell type is inferred in Rust so a
+/// whole table costs one FFI call. Supported values: `None` (leaves the cell
+/// blank), `bool`, `int`, `float`, `datetime.datetime`, `datetime.date`,
+/// `datetime.time`, `datetime.timedelta`, and `str` (formulas when prefixed
+/// with `=`, error tokens like `#N/A`).
+pub(crate) fn write_range_values(
+    book: &mut Spreadsheet,
+    date1904: bool,
+    extras: &mut CellExtras,
+    sheet: &str,
+    top_left: &str,
+    rows: &Bound<'_, PyAny>,
+) -> PyResult<()> {
+    let ws = book
+        .get_sheet_by_name_mut(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+
+    let (row0, col0) = a1_to_row_col(top_left).map_err(|msg| PyErr::new::<PyValueError, _>(msg))?;
+
+    for (r, row) in rows.try_iter()?.enumerate() {
+        let row = row?;
+        for (c, value) in row.try_iter()?.enumerate() {
+            let value = value?;
+            let coord = (col0 + 1 + c as u32, row0 + 1 + r as u32);
+            // As in `write_cell_value`, a write replaces what was recorded.
+            extras.clear(sheet, (coord.1, coord.0));
+            write_native_value(ws, coord, &value, date1904)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_native_value(
+    ws: &mut Worksheet,
+    coord: (u32, u32),
+    value: &Bound<'_, PyAny>,
+    date1904: bool,
+) -> PyResult<()> {
+    if value.is_none() {
+        // Only clear cells that already exist; don't materialize empty ones.
+        if ws.get_cell(coord).is_some() {
+            ws.get_cell_mut(coord).set_blank();
+        }
+        return Ok(());
+    }
+    cell_with_row_col_style(ws, coord);
+
+    // bool is a subclass of int in Python, so it must be checked first.
+    if value.is_instance_of::<PyBool>() {
+        ws.get_cell_mut(coord)
+            .set_value_bool(value.extract::<bool>()?);
+        return Ok(());
+    }
+    if value.is_instance_of::<PyInt>() || value.is_instance_of::<PyFloat>() {
+        ws.get_cell_mut(coord)
+            .set_value_number(value.extract::<f64>()?);
+        return Ok(());
+    }
+
+    // datetime is a subclass of date, so it must be checked first.
+    if value.is_instance_of::<PyDateTime>() {
+        // Rejects timezone-aware datetimes.
+        return write_datetime(ws, coord, value.extract::<NaiveDateTime>()?, date1904);
+    }
+    if value.is_instance_of::<PyDate>() {
+        return write_date(ws, coord, value.extract::<NaiveDate>()?, date1904);
+    }
+    if value.is_instance_of::<PyTime>() {
+        write_time(ws, coord, value.extract::<NaiveTime>()?);
+        return Ok(());
+    }
+    if value.is_instance_of::<PyDelta>() {
+        write_duration(ws, coord, value.extract::<Duration>()?);
+        return Ok(());
+    }
+
+    if let Ok(s) = value.extract::<String>() {
+        if let Some(f) = s.strip_prefix('=') {
+            ws.get_cell_mut(coord).set_formula(f);
+        } else if is_error_token(&s) {
+            write_error(ws, coord, &s)?;
+        } else {
+            ws.get_cell_mut(coord).set_value_string(s);
+        }
+        return Ok(());
+    }
+
+    Err(PyErr::new::<PyTypeError, _>(format!(
+        "Unsupported cell value type: {}",
+        value.get_type().name()?
+    )))
+}
+
+fn write_date<C>(ws: &mut Worksheet, coord: C, d: NaiveDate, date1904: bool) -> PyResult<()>
+where
+    C: Into<CellCoordinates> + Copy,
+{
+    let dt = d.and_time(NaiveTime::from_hms_opt(0, 0, 0).unwrap());
+    let serial = naive_datetime_to_excel_serial(dt, date1904)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>("Failed to convert date"))?;
+
+    ws.get_cell_mut(coord).set_value_number(serial);
+    ws.get_style_mut(coord)
+        .get_number_format_mut()
+        .set_format_code(NumberingFormat::FORMAT_DATE_YYYYMMDD);
+    Ok(())
+}
+
+fn write_datetime<C>(
+    ws: &mut Worksheet,
+    coord: C,
+    dt: NaiveDateTime,
+    date1904: bool,
+) -> PyResult<()>
+where
+    C: Into<CellCoordinates> + Copy,
+{
+    let serial = naive_datetime_to_excel_serial(dt, date1904)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>("Failed to convert datetime"))?;
+
+    ws.get_cell_mut(coord).set_value_number(serial);
+    ws.get_style_mut(coord)
+        .get_number_format_mut()
+        .set_format_code("yyyy-mm-dd h:mm:ss");
+    Ok(())
+}
+
+fn write_time<C>(ws: &mut Worksheet, coord: C, t: NaiveTime)
+where
+    C: Into<CellCoordinates> + Copy,
+{
+    ws.get_cell_mut(coord)
+        .set_value_number(naive_time_to_excel_serial(t));
+    ws.get_style_mut(coord)
+        .get_number_format_mut()
+        .set_format_code("h:mm:ss");
+}
+
+fn write_duration<C>(ws: &mut Worksheet, coord: C, d: Duration)
+where
+    C: Into<CellCoordinates> + Copy,
+{
+    ws.get_cell_mut(coord)
+        .set_value_number(duration_to_excel_serial(d));
+    ws.get_style_mut(coord)
+        .get_number_format_mut()
+        .set_format_code("[h]:mm:ss");
+}
+
+fn write_error<C>(ws: &mut Worksheet, coord: C, token: &str) -> PyResult<()>
+where
+    C: Into<CellCoordinates> + Copy,
+{
+    if !is_error_token(token) {
+        return Err(PyErr::new::<PyValueError, _>(format!(
+            "Unknown error value: {token}"
+        )));
+    }
+    // A genuine `t="e"` cell, exactly as Excel stores it.
+    ws.get_cell_mut(coord).set_error(token);
+    Ok(())
+}
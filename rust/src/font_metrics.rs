@@ -0,0 +1,182 @@
+// Character metrics of common fonts, for estimating the size of rendered text.
+//
+// Widths are advance widths in thousandths of an em for printable ASCII
+// (U+0020..=U+007E), in code point order: space to `/`, the digits, `:` to
+// `@`, the capitals, `[` to `` ` ``, the small letters, then `{` to `~`.
+// Fonts without a table of their own are measured as the closest one bundled;
+// anything unknown is measured as Calibri, Excel's default font.
+
+/// Advance widths of printable ASCII in one face of a font.
+type Widths = [u16; 95];
+
+const CALIBRI: Widths = [
+    226, 326, 401, 498, 507, 715, 682, 221, 303, 303, 498, 498, 250, 306, 252, 386, //
+    507, 507, 507, 507, 507, 507, 507, 507, 507, 507, //
+    268, 268, 498, 498, 498, 463, 894, //
+    579, 544, 533, 615, 488, 459, 631, 623, 252, 319, 520, 420, 855, 646, 662, 517, 673, 543, 459,
+    487, 642, 567, 890, 519, 487, 468, //
+    307, 386, 307, 498, 498, 291, //
+    479, 525, 423, 525, 498, 305, 471, 525, 230, 239, 455, 230, 799, 525, 527, 525, 525, 349, 391,
+    335, 525, 452, 715, 433, 453, 395, //
+    314, 460, 314, 498,
+];
+
+const CALIBRI_BOLD: Widths = [
+    226, 326, 438, 498, 507, 729, 705, 233, 312, 312, 498, 498, 258, 306, 267, 430, //
+    507, 507, 507, 507, 507, 507, 507, 507, 507, 507, //
+    276, 276, 498, 498, 498, 463, 898, //
+    606, 561, 529, 630, 488, 459, 637, 631, 267, 331, 547, 423, 874, 659, 676, 532, 686, 563, 473,
+    495, 653, 591, 906, 551, 520, 478, //
+    325, 430, 325, 498, 498, 300, //
+    494, 537, 418, 537, 503, 316, 474, 537, 246, 255, 480, 246, 813, 537, 538, 537, 537, 355, 399,
+    347, 537, 473, 745, 459, 474, 397, //
+    344, 475, 344, 498,
+];
+
+const ARIAL: Widths = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, //
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, //
+    278, 278, 584, 584, 584, 556, 1015, //
+    667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667,
+    611, 722, 667, 944, 667, 667, 611, //
+    278, 278, 278, 469, 556, 333, //
+    556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556, 556, 556, 333, 500,
+    278, 556, 500, 722, 500, 500, 500, //
+    334, 260, 334, 584,
+];
+
+const ARIAL_BOLD: Widths = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, //
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, //
+    333, 333, 584, 584, 584, 611, 975, //
+    722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778, 667, 778, 722, 667,
+    611, 722, 667, 944, 667, 667, 611, //
+    333, 278, 333, 584, 556, 333, //
+    556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611, 611, 611, 389, 556,
+    333, 611, 556, 778, 556, 556, 500, //
+    389, 280, 389, 584,
+];
+
+const TIMES: Widths = [
+    250, 333, 408, 500, 500, 833, 778, 180, 333, 333, 500, 564, 250, 333, 250, 278, //
+    500, 500, 500, 500, 500, 500, 500, 500, 500, 500, //
+    278, 278, 564, 564, 564, 444, 921, //
+    722, 667, 667, 722, 611, 556, 722, 722, 333, 389, 722, 611, 889, 722, 722, 556, 722, 667, 556,
+    611, 722, 722, 944, 722, 722, 611, //
+    333, 278, 333, 469, 500, 333, //
+    444, 500, 444, 500, 444, 333, 500, 500, 278, 278, 500, 278, 778, 500, 500, 500, 500, 333, 389,
+    278, 500, 500, 722, 500, 500, 444, //
+    480, 200, 480, 541,
+];
+
+const TIMES_BOLD: Widths = [
+    250, 333, 555, 500, 500, 1000, 833, 278, 333, 333, 500, 570, 250, 333, 250, 278, //
+    500, 500, 500, 500, 500, 500, 500, 500, 500, 500, //
+    333, 333, 570, 570, 570, 500, 930, //
+    722, 667, 722, 722, 667, 611, 778, 778, 389, 500, 778, 667, 944, 722, 778, 611, 778, 722, 556,
+    667, 722, 722, 1000, 722, 722, 667, //
+    333, 278, 333, 581, 500, 333, //
+    500, 556, 444, 556, 444, 333, 500, 556, 278, 333, 556, 278, 833, 556, 500, 556, 556, 444, 389,
+    333, 556, 500, 722, 500, 500, 444, //
+    394, 220, 394, 520,
+];
+
+/// Every character of Courier New, in either weight.
+const COURIER: Widths = [600; 95];
+
+/// Every character of Consolas, in either weight.
+const CONSOLAS: Widths = [550; 95];
+
+/// Metrics of one font: its regular and bold widths and its line height, as
+/// a multiple of the point size (the row height Excel gives a line of it).
+pub(crate) struct FontMetrics {
+    regular: &'static Widths,
+    bold: &'static Widths,
+    line_height: f64,
+}
+
+const CALIBRI_METRICS: FontMetrics = FontMetrics {
+    regular: &CALIBRI,
+    bold: &CALIBRI_BOLD,
+    line_height: 15.0 / 11.0,
+};
+
+const ARIAL_METRICS: FontMetrics = FontMetrics {
+    regular: &ARIAL,
+    bold: &ARIAL_BOLD,
+    line_height: 12.75 / 10.0,
+};
+
+const TIMES_METRICS: FontMetrics = FontMetrics {
+    regular: &TIMES,
+    bold: &TIMES_BOLD,
+    line_height: 15.75 / 12.0,
+};
+
+const COURIER_METRICS: FontMetrics = FontMetrics {
+    regular: &COURIER,
+    bold: &COURIER,
+    line_height: 13.5 / 10.0,
+};
+
+const CONSOLAS_METRICS: FontMetrics = FontMetrics {
+    regular: &CONSOLAS,
+    bold: &CONSOLAS,
+    line_height: 15.0 / 11.0,
+};
+
+impl FontMetrics {
+    /// Metrics for a font name, falling back to the closest bundled font.
+    pub(crate) fn for_font(name: &str) -> &'static FontMetrics {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "arial" | "arial narrow" | "helvetica" | "liberation sans" | "tahoma" | "verdana"
+            | "segoe ui" => &ARIAL_METRICS,
+            "times new roman" | "times" | "liberation serif" | "cambria" | "georgia"
+            | "garamond" | "book antiqua" => &TIMES_METRICS,
+            "courier new" | "courier" | "liberation mono" => &COURIER_METRICS,
+            "consolas" | "lucida console" | "menlo" => &CONSOLAS_METRICS,
+            _ => &CALIBRI_METRICS,
+        }
+    }
+
+    /// Advance width of `c`, in thousandths of an em.
+    fn char_width(&self, c: char, bold: bool) -> u16 {
+        let widths = if bold { self.bold } else { self.regular };
+        match c {
+            ' '..='~' => widths[c as usize - 0x20],
+            _ if is_wide(c) => 1000,
+            // Accented letters and other symbols are about as wide as a digit.
+            _ => widths[usize::from(b'0' - 0x20)],
+        }
+    }
+
+    /// Width of one line of `text`, in points.
+    pub(crate) fn text_width(&self, text: &str, size: f64, bold: bool) -> f64 {
+        let ems: u32 = text
+            .chars()
+            .map(|c| u32::from(self.char_width(c, bold)))
+            .sum();
+        f64::from(ems) / 1000.0 * size
+    }
+
+    /// Height of one line of text, in points.
+    pub(crate) fn line_height(&self, size: f64) -> f64 {
+        self.line_height * size
+    }
+}
+
+/// Whether `c` is an East Asian wide or fullwidth character, which takes a
+/// full em.
+fn is_wide(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1100}'..='\u{115F}'
+            | '\u{2E80}'..='\u{A4CF}'
+            | '\u{AC00}'..='\u{D7A3}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FE30}'..='\u{FE4F}'
+            | '\u{FF00}'..='\u{FF60}'
+            | '\u{FFE0}'..='\u{FFE6}'
+            | '\u{20000}'..='\u{3FFFD}'
+    )
+}
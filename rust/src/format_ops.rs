@@ -1,18 +1,285 @@
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
-use pyo3::types::PyDict;
+use pyo3::types::{PyDict, PyList};
 
+use std::collections::HashMap;
 use std::str::FromStr;
 
 use umya_spreadsheet::structs::{
-    EnumTrait, HorizontalAlignmentValues, PatternValues, VerticalAlignmentValues,
+    Border, Borders, Color, EnumTrait, Fill, GradientFill, GradientStop, HorizontalAlignmentValues,
+    PatternValues, Style, VerticalAlignmentValues,
 };
 use umya_spreadsheet::Spreadsheet;
 
-use crate::utils::{a1_to_row_col, argb_to_hex, hex_to_argb, umya_border_style_to_str};
+use crate::formula_parser::LineShift;
+use crate::named_style_ops::NamedStyles;
+use crate::number_format::classify_number_format;
+use crate::package_ops::{color_key, gradient_key};
+use crate::palette::Palette;
+use crate::structural_ops::{cell_with_row_col_style, row_col_style};
+use crate::utils::{
+    a1_areas_to_bounds, a1_to_row_col, argb_to_hex, hex_to_argb, umya_border_style_to_str,
+};
+
+/// Inner rectangle (`left`, `right`, `top`, `bottom`, each 0-1) a path
+/// gradient radiates from.
+pub(crate) type PathGeometry = [f64; 4];
+
+/// Path gradients by stop signature ([`gradient_key`]). umya only models
+/// linear gradients, so path gradients are written as linear ones and
+/// patched in the package on save.
+pub(crate) type GradientPaths = HashMap<String, PathGeometry>;
+
+/// Font settings umya does not model: raised or lowered text (`vertAlign`)
+/// and the legacy `outline`, `shadow`, `condense` and `extend` flags.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct FontExtras {
+    /// `"superscript"` or `"subscript"`.
+    pub vert_align: Option<String>,
+    pub outline: bool,
+    pub shadow: bool,
+    pub condense: bool,
+    pub extend: bool,
+}
+
+impl FontExtras {
+    pub(crate) fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply the `vert_align`, `outline`, `shadow`, `condense` and `extend`
+    /// keys present in a format dict.
+    fn update(&mut self, dict: &Bound<'_, PyDict>) -> PyResult<()> {
+        if let Some(v) = dict.get_item("vert_align")? {
+            let v: Option<String> = v.extract()?;
+            self.vert_align = match v.as_deref() {
+                None | Some("baseline") => None,
+                Some("superscript") | Some("subscript") => v,
+                Some(other) => {
+                    return Err(PyErr::new::<PyValueError, _>(format!(
+                        "Invalid vert_align: {other}"
+                    )))
+                }
+            };
+        }
+        for (key, on) in [
+            ("outline", &mut self.outline),
+            ("shadow", &mut self.shadow),
+            ("condense", &mut self.condense),
+            ("extend", &mut self.extend),
+        ] {
+            if let Some(v) = dict.get_item(key)? {
+                *on = v.extract()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn items(&self, d: &Bound<'_, PyDict>) -> PyResult<()> {
+        if let Some(align) = &self.vert_align {
+            d.set_item("vert_align", align)?;
+        }
+        for (key, on) in [
+            ("outline", self.outline),
+            ("shadow", self.shadow),
+            ("condense", self.condense),
+            ("extend", self.extend),
+        ] {
+            if on {
+                d.set_item(key, true)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Alignment settings umya does not model.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct AlignmentExtras {
+    pub shrink_to_fit: bool,
+    /// Distribute the last line of justified text too.
+    pub justify_last_line: bool,
+    /// Indent added to the style's, in steps of three spaces.
+    pub relative_indent: i32,
+    /// 0 follows the text, 1 is left-to-right, 2 right-to-left.
+    pub reading_order: u32,
+}
+
+impl AlignmentExtras {
+    pub(crate) fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply the `shrink_to_fit`, `justify_last_line`, `relative_indent` and
+    /// `reading_order` keys present in a format dict.
+    fn update(&mut self, dict: &Bound<'_, PyDict>) -> PyResult<()> {
+        if let Some(v) = dict.get_item("shrink_to_fit")? {
+            self.shrink_to_fit = v.extract()?;
+        }
+        if let Some(v) = dict.get_item("justify_last_line")? {
+            self.justify_last_line = v.extract()?;
+        }
+        if let Some(v) = dict.get_item("relative_indent")? {
+            let v: i32 = v.extract()?;
+            if !(-15..=15).contains(&v) {
+                return Err(PyErr::new::<PyValueError, _>(
+                    "relative_indent must be between -15 and 15",
+                ));
+            }
+            self.relative_indent = v;
+        }
+        if let Some(v) = dict.get_item("reading_order")? {
+            let v: u32 = v.extract()?;
+            if v > 2 {
+                return Err(PyErr::new::<PyValueError, _>(
+                    "reading_order must be 0 (context), 1 (left-to-right) or 2 (right-to-left)",
+                ));
+            }
+            self.reading_order = v;
+        }
+        Ok(())
+    }
+
+    fn items(&self, d: &Bound<'_, PyDict>) -> PyResult<()> {
+        if self.shrink_to_fit {
+            d.set_item("shrink_to_fit", true)?;
+        }
+        if self.justify_last_line {
+            d.set_item("justify_last_line", true)?;
+        }
+        if self.relative_indent != 0 {
+            d.set_item("relative_indent", self.relative_indent)?;
+        }
+        if self.reading_order != 0 {
+            d.set_item("reading_order", self.reading_order)?;
+        }
+        Ok(())
+    }
+}
+
+/// Border settings umya does not model.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct BorderExtras {
+    /// `outline="0"`: the edges are not limited to the outline of a range.
+    pub no_outline: bool,
+}
+
+impl BorderExtras {
+    pub(crate) fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply the `outline` key of a border dict.
+    pub(crate) fn update(&mut self, dict: &Bound<'_, PyDict>) -> PyResult<()> {
+        if let Some(v) = dict.get_item("outline")? {
+            self.no_outline = !v.extract::<bool>()?;
+        }
+        Ok(())
+    }
+}
+
+/// Cell format settings umya does not model. Cells using any get their own
+/// copy of their cell format, patched into the package on save.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct XfExtras {
+    pub font: FontExtras,
+    pub alignment: AlignmentExtras,
+    pub border: BorderExtras,
+}
+
+impl XfExtras {
+    pub(crate) fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply the extras keys present in a format dict.
+    pub(crate) fn update(&mut self, dict: &Bound<'_, PyDict>) -> PyResult<()> {
+        self.font.update(dict)?;
+        self.alignment.update(dict)
+    }
+
+    fn items(&self, d: &Bound<'_, PyDict>) -> PyResult<()> {
+        self.font.items(d)?;
+        self.alignment.items(d)
+    }
+}
+
+/// Extras of the cells that have any, by sheet name and 1-based `(row, col)`.
+pub(crate) type CellXfExtras = HashMap<String, HashMap<(u32, u32), XfExtras>>;
+
+/// Workbook-wide style details umya does not model.
+#[derive(Debug, Default)]
+pub(crate) struct StyleExtras {
+    /// Theme and indexed colors for resolving color references.
+    pub palette: Palette,
+    pub gradient_paths: GradientPaths,
+    pub named: NamedStyles,
+    pub cell_xfs: CellXfExtras,
+}
+
+impl StyleExtras {
+    /// Cell format extras of a cell, by 1-based `(row, col)`.
+    pub(crate) fn cell_extras(&self, sheet: &str, cell: (u32, u32)) -> Option<&XfExtras> {
+        self.cell_xfs.get(sheet)?.get(&cell)
+    }
+
+    /// Replace the cell format extras of a cell.
+    pub(crate) fn set_cell_extras(&mut self, sheet: &str, cell: (u32, u32), extras: XfExtras) {
+        if extras.is_empty() {
+            if let Some(cells) = self.cell_xfs.get_mut(sheet) {
+                cells.remove(&cell);
+                if cells.is_empty() {
+                    self.cell_xfs.remove(sheet);
+                }
+            }
+        } else {
+            self.cell_xfs
+                .entry(sheet.to_string())
+                .or_default()
+                .insert(cell, extras);
+        }
+    }
+
+    /// Change the cell format extras of a cell.
+    pub(crate) fn update_cell_extras(
+        &mut self,
+        sheet: &str,
+        cell: (u32, u32),
+        update: impl FnOnce(&mut XfExtras) -> PyResult<()>,
+    ) -> PyResult<()> {
+        let mut extras = self.cell_extras(sheet, cell).cloned().unwrap_or_default();
+        update(&mut extras)?;
+        self.set_cell_extras(sheet, cell, extras);
+        Ok(())
+    }
+
+    /// Move per-cell extras and style links along with the cells of a sheet
+    /// when rows or columns are inserted or deleted.
+    pub(crate) fn shift_cells(&mut self, sheet: &str, shift: LineShift) {
+        if let Some(cells) = self.cell_xfs.get_mut(sheet) {
+            shift.move_cells(cells);
+        }
+        self.named.shift_cells(sheet, shift);
+    }
+
+    /// Re-key the per-cell extras and style links of a renamed sheet.
+    pub(crate) fn rename_sheet(&mut self, old: &str, new: &str) {
+        if let Some(cells) = self.cell_xfs.remove(old) {
+            self.cell_xfs.insert(new.to_string(), cells);
+        }
+        self.named.rename_sheet(old, new);
+    }
+
+    /// Drop the per-cell extras and style links of a removed sheet.
+    pub(crate) fn remove_sheet(&mut self, sheet: &str) {
+        self.cell_xfs.remove(sheet);
+        self.named.remove_sheet(sheet);
+    }
+}
 
 pub(crate) fn read_cell_format(
     book: &Spreadsheet,
+    styles: &StyleExtras,
     py: Python<'_>,
     sheet: &str,
     a1: &str,
@@ -26,13 +293,33 @@ pub(crate) fn read_cell_format(
 
     let d = PyDict::new(py);
 
-    let cell = match ws.get_cell(coord) {
-        Some(c) => c,
-        None => return Ok(d.into_any().unbind()),
+    // An empty cell shows its row's or column's formatting.
+    let style = match ws.get_cell(coord) {
+        Some(cell) => {
+            if let Some(name) = styles.named.cell_style(sheet, (row0 + 1, col0 + 1)) {
+                d.set_item("style_name", name)?;
+            }
+            cell.get_style()
+        }
+        None => match row_col_style(ws, coord) {
+            Some(style) => style,
+            None => return Ok(d.into_any().unbind()),
+        },
     };
+    format_items(py, &d, style, styles)?;
+    if let Some(extras) = styles.cell_extras(sheet, (row0 + 1, col0 + 1)) {
+        extras.items(&d)?;
+    }
+    Ok(d.into_any().unbind())
+}
 
-    let style = cell.get_style();
-
+/// Add the format-dict keys describing `style` to `d`.
+pub(crate) fn format_items(
+    py: Python<'_>,
+    d: &Bound<'_, PyDict>,
+    style: &Style,
+    styles: &StyleExtras,
+) -> PyResult<()> {
     // Font properties
     if let Some(font) = style.get_font() {
         if *font.get_bold() {
@@ -62,18 +349,22 @@ pub(crate) fn read_cell_format(
                 d.set_item("font_size", size)?;
             }
         }
+        if *font.get_family() > 0 {
+            d.set_item("font_family", *font.get_family())?;
+        }
+        if *font.get_charset() > 0 {
+            d.set_item("font_charset", *font.get_charset())?;
+        }
         {
-            let argb = font.get_color().get_argb();
-            if !argb.is_empty() {
-                let rgb = argb_to_hex(argb);
-                if rgb != "000000" {
-                    d.set_item("font_color", rgb)?;
-                }
+            let scheme = font.get_scheme();
+            if !scheme.is_empty() && scheme != "none" {
+                d.set_item("font_scheme", scheme.to_string())?;
             }
         }
+        color_items(d, "font_color", font.get_color(), &styles.palette, true)?;
     }
 
-    // Fill / background color
+    // Fill: a pattern (`bg_color` is its foreground) or a gradient
     if let Some(fill) = style.get_fill() {
         if let Some(pf) = fill.get_pattern_fill() {
             let p = pf.get_pattern_type().get_value_string();
@@ -81,12 +372,14 @@ pub(crate) fn read_cell_format(
                 d.set_item("fill_type", p.to_string())?;
             }
             if let Some(fg) = pf.get_foreground_color() {
-                let argb = fg.get_argb();
-                if !argb.is_empty() {
-                    let rgb = argb_to_hex(argb);
-                    d.set_item("bg_color", rgb)?;
-                }
+                color_items(d, "bg_color", fg, &styles.palette, false)?;
             }
+            if let Some(bg) = pf.get_background_color() {
+                color_items(d, "pattern_bg_color", bg, &styles.palette, false)?;
+            }
+        }
+        if let Some(gf) = fill.get_gradient_fill() {
+            d.set_item("gradient", gradient_payload(py, gf, styles)?)?;
         }
     }
 
@@ -95,6 +388,8 @@ pub(crate) fn read_cell_format(
         let code = nf.get_format_code();
         if !code.is_empty() && code != "General" {
             d.set_item("number_format", code.to_string())?;
+            let kind = classify_number_format(*nf.get_number_format_id(), code);
+            d.set_item("number_format_kind", kind.as_str())?;
         }
     }
 
@@ -122,11 +417,134 @@ pub(crate) fn read_cell_format(
         }
     }
 
-    Ok(d.into_any().unbind())
+    // Protection (cells are locked by default)
+    if let Some(protection) = style.get_protection() {
+        if !*protection.get_locked() {
+            d.set_item("locked", false)?;
+        }
+        if *protection.get_hidden() {
+            d.set_item("hidden", true)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn gradient_payload<'py>(
+    py: Python<'py>,
+    gf: &GradientFill,
+    styles: &StyleExtras,
+) -> PyResult<Bound<'py, PyDict>> {
+    let stops = PyList::empty(py);
+    let mut keys = Vec::new();
+    for stop in gf.get_gradient_stop() {
+        let s = PyDict::new(py);
+        s.set_item("position", *stop.get_position())?;
+        color_items(&s, "color", stop.get_color(), &styles.palette, false)?;
+        stops.append(s)?;
+        keys.push((*stop.get_position(), umya_color_key(stop.get_color())));
+    }
+
+    let g = PyDict::new(py);
+    match styles.gradient_paths.get(&gradient_key(&keys)) {
+        Some(&[left, right, top, bottom]) => {
+            g.set_item("type", "path")?;
+            g.set_item("left", left)?;
+            g.set_item("right", right)?;
+            g.set_item("top", top)?;
+            g.set_item("bottom", bottom)?;
+        }
+        None => {
+            g.set_item("type", "linear")?;
+            g.set_item("degree", *gf.get_degree())?;
+        }
+    }
+    g.set_item("stops", stops)?;
+    Ok(g)
+}
+
+fn umya_color_key(color: &Color) -> String {
+    color_key(
+        color.get_argb(),
+        *color.get_theme_index(),
+        *color.get_tint(),
+        *color.get_indexed(),
+    )
+}
+
+/// Report `color` under `key` as resolved `RRGGBB`, plus the theme or indexed
+/// reference it came from under `{key}_theme`, `{key}_tint` and
+/// `{key}_indexed`. Plain black is left out when `omit_black` is set.
+pub(crate) fn color_items(
+    d: &Bound<'_, PyDict>,
+    key: &str,
+    color: &Color,
+    palette: &Palette,
+    omit_black: bool,
+) -> PyResult<()> {
+    let indexed = *color.get_indexed();
+    let theme = *color.get_theme_index();
+    let tint = *color.get_tint();
+    if indexed != 0 {
+        if let Some(rgb) = palette.indexed_color(indexed) {
+            d.set_item(key, rgb)?;
+        }
+        d.set_item(format!("{key}_indexed"), indexed)?;
+    } else if theme != 0 || tint != 0.0 {
+        // umya cannot tell `theme="0"` from no theme at all, so an untinted
+        // reference to theme color 0 falls through to the RGB value.
+        if let Some(rgb) = palette.theme_color(theme, tint) {
+            d.set_item(key, rgb)?;
+        }
+        d.set_item(format!("{key}_theme"), theme)?;
+        if tint != 0.0 {
+            d.set_item(format!("{key}_tint"), tint)?;
+        }
+    } else {
+        let argb = color.get_argb();
+        if !argb.is_empty() {
+            let rgb = argb_to_hex(argb);
+            if !(omit_black && rgb == "000000") {
+                d.set_item(key, rgb)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Apply `{key}_theme`/`{key}_tint` or `{key}_indexed` from a format dict.
+/// Returns whether the color was set by reference.
+pub(crate) fn write_color_reference(
+    color: &mut Color,
+    dict: &Bound<'_, PyDict>,
+    key: &str,
+) -> PyResult<bool> {
+    if let Some(indexed) = dict
+        .get_item(format!("{key}_indexed"))?
+        .filter(|v| !v.is_none())
+    {
+        color.set_indexed(indexed.extract::<u32>()?);
+        return Ok(true);
+    }
+    if let Some(theme) = dict
+        .get_item(format!("{key}_theme"))?
+        .filter(|v| !v.is_none())
+    {
+        color.set_theme_index(theme.extract::<u32>()?);
+        if let Some(tint) = dict
+            .get_item(format!("{key}_tint"))?
+            .filter(|v| !v.is_none())
+        {
+            color.set_tint(tint.extract::<f64>()?);
+        }
+        return Ok(true);
+    }
+    Ok(false)
 }
 
 pub(crate) fn write_cell_format(
     book: &mut Spreadsheet,
+    styles: &mut StyleExtras,
     sheet: &str,
     a1: &str,
     format_dict: &Bound<'_, PyAny>,
@@ -139,8 +557,61 @@ pub(crate) fn write_cell_format(
         .cast::<PyDict>()
         .map_err(|_| PyErr::new::<PyValueError, _>("format_dict must be a dict"))?;
 
-    let style = ws.get_style_mut(a1);
+    let (row0, col0) = a1_to_row_col(a1).map_err(|msg| PyErr::new::<PyValueError, _>(msg))?;
+    let cell = cell_with_row_col_style(ws, (col0 + 1, row0 + 1));
+    apply_format(cell.get_style_mut(), &mut styles.gradient_paths, dict)?;
+    styles.update_cell_extras(sheet, (row0 + 1, col0 + 1), |extras| extras.update(dict))
+}
 
+/// Apply a format dict to every cell of a range (`"B2:F200"`) or of several
+/// areas (`"A1:A5 C1:C5"`).
+pub(crate) fn write_range_format(
+    book: &mut Spreadsheet,
+    styles: &mut StyleExtras,
+    sheet: &str,
+    range: &str,
+    format_dict: &Bound<'_, PyAny>,
+) -> PyResult<()> {
+    let ws = book
+        .get_sheet_by_name_mut(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+
+    let dict = format_dict
+        .cast::<PyDict>()
+        .map_err(|_| PyErr::new::<PyValueError, _>("format_dict must be a dict"))?;
+
+    let areas = a1_areas_to_bounds(range).map_err(|msg| PyErr::new::<PyValueError, _>(msg))?;
+    for (min_row0, min_col0, max_row0, max_col0) in areas {
+        for row in min_row0 + 1..=max_row0 + 1 {
+            for col in min_col0 + 1..=max_col0 + 1 {
+                apply_format(
+                    cell_with_row_col_style(ws, (col, row)).get_style_mut(),
+                    &mut styles.gradient_paths,
+                    dict,
+                )?;
+                styles.update_cell_extras(sheet, (row, col), |extras| extras.update(dict))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `<u>` values: plain and accounting (full cell width) underlines.
+const UNDERLINES: [&str; 5] = [
+    "none",
+    "single",
+    "double",
+    "singleAccounting",
+    "doubleAccounting",
+];
+
+/// Apply the keys present in a format dict to `style`, leaving the rest as is.
+/// Extras are per cell and kept by the caller ([`XfExtras::update`]).
+pub(crate) fn apply_format(
+    style: &mut Style,
+    gradient_paths: &mut GradientPaths,
+    dict: &Bound<'_, PyDict>,
+) -> PyResult<()> {
     // Font properties
     {
         let font = style.get_font_mut();
@@ -152,7 +623,13 @@ pub(crate) fn write_cell_format(
             font.set_italic(italic.extract::<bool>()?);
         }
         if let Some(ul) = dict.get_item("underline")? {
-            font.set_underline(ul.extract::<String>()?);
+            let ul = ul.extract::<String>()?;
+            if !UNDERLINES.contains(&ul.as_str()) {
+                return Err(PyErr::new::<PyValueError, _>(format!(
+                    "Invalid underline: {ul}"
+                )));
+            }
+            font.set_underline(ul);
         }
         if let Some(st) = dict.get_item("strikethrough")? {
             font.set_strikethrough(st.extract::<bool>()?);
@@ -163,28 +640,86 @@ pub(crate) fn write_cell_format(
         if let Some(size) = dict.get_item("font_size")? {
             font.set_size(size.extract::<f64>()?);
         }
-        if let Some(color) = dict.get_item("font_color")? {
-            let c = color.extract::<String>()?;
-            font.get_color_mut().set_argb(hex_to_argb(&c));
+        if let Some(family) = dict.get_item("font_family")? {
+            font.set_family(family.extract::<i32>()?);
+        }
+        if let Some(charset) = dict.get_item("font_charset")? {
+            font.set_charset(charset.extract::<i32>()?);
+        }
+        if let Some(scheme) = dict.get_item("font_scheme")? {
+            let scheme = scheme.extract::<String>()?;
+            if !matches!(scheme.as_str(), "major" | "minor" | "none") {
+                return Err(PyErr::new::<PyValueError, _>(format!(
+                    "Invalid font_scheme: {scheme}"
+                )));
+            }
+            font.set_scheme(scheme);
+        }
+        if !write_color_reference(font.get_color_mut(), dict, "font_color")? {
+            if let Some(color) = dict.get_item("font_color")? {
+                let c = color.extract::<String>()?;
+                font.get_color_mut().set_argb(hex_to_argb(&c));
+            }
         }
     }
 
-    // Fill / background via pattern fill
-    if let Some(fill_type) = dict.get_item("fill_type")? {
-        let ft = fill_type.extract::<String>()?;
-        if ft.eq_ignore_ascii_case("none") {
-            style
-                .get_fill_mut()
-                .get_pattern_fill_mut()
-                .set_pattern_type(PatternValues::None);
-        }
+    // Fill: a gradient, or a pattern with foreground (`bg_color`) and
+    // background (`pattern_bg_color`) colors
+    if let Some(g) = dict.get_item("gradient")?.filter(|g| !g.is_none()) {
+        let g = g
+            .cast::<PyDict>()
+            .map_err(|_| PyErr::new::<PyValueError, _>("gradient must be a dict"))?;
+        let mut fill = Fill::default();
+        fill.set_gradient_fill(parse_gradient(g, gradient_paths)?);
+        style.set_fill(fill);
     }
-    if let Some(bg) = dict.get_item("bg_color")? {
-        let bg = bg.extract::<String>()?;
-        let fill = style.get_fill_mut();
-        let pf = fill.get_pattern_fill_mut();
-        pf.set_pattern_type(PatternValues::Solid);
-        pf.get_foreground_color_mut().set_argb(hex_to_argb(&bg));
+    let pattern = match dict.get_item("fill_type")? {
+        Some(v) => Some(v.extract::<String>()?),
+        None => None,
+    };
+    let fg = dict.get_item("bg_color")?;
+    let fg_ref = dict.contains("bg_color_theme")? || dict.contains("bg_color_indexed")?;
+    let bg = dict.get_item("pattern_bg_color")?;
+    let bg_ref =
+        dict.contains("pattern_bg_color_theme")? || dict.contains("pattern_bg_color_indexed")?;
+    if pattern.is_some() || fg.is_some() || fg_ref || bg.is_some() || bg_ref {
+        // A pattern replaces any gradient.
+        if style
+            .get_fill()
+            .is_some_and(|f| f.get_gradient_fill().is_some())
+        {
+            style.set_fill(Fill::default());
+        }
+        let pf = style.get_fill_mut().get_pattern_fill_mut();
+        match pattern.as_deref() {
+            Some(p) => {
+                let pv = PatternValues::from_str(p).map_err(|_| {
+                    PyErr::new::<PyValueError, _>(format!("Invalid fill_type: {p}"))
+                })?;
+                pf.set_pattern_type(pv);
+            }
+            // A color alone means a solid fill.
+            None if fg.is_some() || fg_ref => {
+                pf.set_pattern_type(PatternValues::Solid);
+            }
+            None => {}
+        }
+        if (fg.is_some() || fg_ref)
+            && !write_color_reference(pf.get_foreground_color_mut(), dict, "bg_color")?
+        {
+            if let Some(fg) = fg {
+                pf.get_foreground_color_mut()
+                    .set_argb(hex_to_argb(&fg.extract::<String>()?));
+            }
+        }
+        if (bg.is_some() || bg_ref)
+            && !write_color_reference(pf.get_background_color_mut(), dict, "pattern_bg_color")?
+        {
+            if let Some(bg) = bg {
+                pf.get_background_color_mut()
+                    .set_argb(hex_to_argb(&bg.extract::<String>()?));
+            }
+        }
     }
 
     // Number format
@@ -249,11 +784,100 @@ pub(crate) fn write_cell_format(
         );
     }
 
+    // Protection: whether the cell stays read-only (`locked`) and its formula
+    // hidden once the sheet is protected. Both flags are written together, as
+    // an unwritten `locked` would read back as unlocked.
+    let locked = dict.get_item("locked")?;
+    let hidden = dict.get_item("hidden")?;
+    if locked.is_some() || hidden.is_some() {
+        let (mut is_locked, mut is_hidden) = style
+            .get_protection()
+            .map_or((true, false), |p| (*p.get_locked(), *p.get_hidden()));
+        if let Some(v) = locked {
+            is_locked = v.extract()?;
+        }
+        if let Some(v) = hidden {
+            is_hidden = v.extract()?;
+        }
+        let protection = style.get_protection_mut();
+        protection.set_locked(is_locked);
+        protection.set_hidden(is_hidden);
+    }
+
     Ok(())
 }
 
+/// Build a gradient fill from its format dict, recording path gradients.
+fn parse_gradient(
+    g: &Bound<'_, PyDict>,
+    gradient_paths: &mut GradientPaths,
+) -> PyResult<GradientFill> {
+    let number = |key: &str| -> PyResult<f64> {
+        match g.get_item(key)? {
+            Some(v) if !v.is_none() => v.extract::<f64>(),
+            _ => Ok(0.0),
+        }
+    };
+
+    let mut gf = GradientFill::default();
+    let mut keys = Vec::new();
+    let stops = g
+        .get_item("stops")?
+        .ok_or_else(|| PyErr::new::<PyValueError, _>("gradient missing 'stops'"))?;
+    for stop in stops.try_iter()? {
+        let stop = stop?;
+        let stop = stop
+            .cast::<PyDict>()
+            .map_err(|_| PyErr::new::<PyValueError, _>("gradient stop must be a dict"))?;
+        let position = stop
+            .get_item("position")?
+            .ok_or_else(|| PyErr::new::<PyValueError, _>("gradient stop missing 'position'"))?
+            .extract::<f64>()?;
+        let mut color = Color::default();
+        if !write_color_reference(&mut color, stop, "color")? {
+            let c = stop
+                .get_item("color")?
+                .ok_or_else(|| PyErr::new::<PyValueError, _>("gradient stop missing 'color'"))?
+                .extract::<String>()?;
+            color.set_argb(hex_to_argb(&c));
+        }
+        keys.push((position, umya_color_key(&color)));
+
+        let mut gs = GradientStop::default();
+        gs.set_position(position);
+        gs.set_color(color);
+        gf.set_gradient_stop(gs);
+    }
+
+    let kind = match g.get_item("type")? {
+        Some(v) if !v.is_none() => v.extract::<String>()?,
+        _ => "linear".to_string(),
+    };
+    match kind.as_str() {
+        "linear" => {
+            gf.set_degree(number("degree")?);
+        }
+        "path" => {
+            let geometry = [
+                number("left")?,
+                number("right")?,
+                number("top")?,
+                number("bottom")?,
+            ];
+            gradient_paths.insert(gradient_key(&keys), geometry);
+        }
+        other => {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "Invalid gradient type: {other}"
+            )))
+        }
+    }
+    Ok(gf)
+}
+
 pub(crate) fn read_cell_border(
     book: &Spreadsheet,
+    styles: &StyleExtras,
     py: Python<'_>,
     sheet: &str,
     a1: &str,
@@ -267,79 +891,67 @@ pub(crate) fn read_cell_border(
 
     let d = PyDict::new(py);
 
-    let cell = match ws.get_cell(coord) {
-        Some(c) => c,
-        None => return Ok(d.into_any().unbind()),
+    let style = match ws.get_cell(coord) {
+        Some(cell) => cell.get_style(),
+        None => match row_col_style(ws, coord) {
+            Some(style) => style,
+            None => return Ok(d.into_any().unbind()),
+        },
     };
-
-    let style = cell.get_style();
     if let Some(borders) = style.get_borders() {
-        // Helper: read one edge. Returns None if style is "none" or empty.
-        let read_edge = |e: &umya_spreadsheet::structs::Border| -> Option<(String, String)> {
-            let style_str = e.get_border_style();
-            if style_str.is_empty() || style_str == "none" {
-                return None;
+        for (key, edge) in [
+            ("top", borders.get_top()),
+            ("bottom", borders.get_bottom()),
+            ("left", borders.get_left()),
+            ("right", borders.get_right()),
+        ] {
+            if let Some(edge) = edge_items(py, edge, &styles.palette)? {
+                d.set_item(key, edge)?;
             }
-            let argb = e.get_color().get_argb();
-            let color_str = if argb.is_empty() {
-                "000000".to_string()
-            } else {
-                argb_to_hex(argb)
-            };
-            Some((umya_border_style_to_str(style_str).to_string(), color_str))
-        };
-
-        if let Some((s, c)) = read_edge(borders.get_top()) {
-            let edge = PyDict::new(py);
-            edge.set_item("style", s)?;
-            edge.set_item("color", c)?;
-            d.set_item("top", edge)?;
-        }
-        if let Some((s, c)) = read_edge(borders.get_bottom()) {
-            let edge = PyDict::new(py);
-            edge.set_item("style", s)?;
-            edge.set_item("color", c)?;
-            d.set_item("bottom", edge)?;
-        }
-        if let Some((s, c)) = read_edge(borders.get_left()) {
-            let edge = PyDict::new(py);
-            edge.set_item("style", s)?;
-            edge.set_item("color", c)?;
-            d.set_item("left", edge)?;
-        }
-        if let Some((s, c)) = read_edge(borders.get_right()) {
-            let edge = PyDict::new(py);
-            edge.set_item("style", s)?;
-            edge.set_item("color", c)?;
-            d.set_item("right", edge)?;
-        }
-        if let Some((s, c)) = read_edge(borders.get_diagonal()) {
-            let edge = PyDict::new(py);
-            edge.set_item("style", s)?;
-            edge.set_item("color", c)?;
-
-            let up = *borders.get_diagonal_up();
-            let down = *borders.get_diagonal_down();
-
-            if up {
+        }
+        // The diagonal line is reported on its own and under each direction
+        // it is drawn in.
+        if let Some(edge) = edge_items(py, borders.get_diagonal(), &styles.palette)? {
+            if *borders.get_diagonal_up() {
                 d.set_item("diagonal_up", &edge)?;
             }
-            if down {
+            if *borders.get_diagonal_down() {
                 d.set_item("diagonal_down", &edge)?;
             }
-
-            // Fallback for files that set a diagonal border style but omit flags.
-            if !up && !down {
-                d.set_item("diagonal_up", &edge)?;
-            }
+            d.set_item("diagonal", edge)?;
         }
     }
+    if styles
+        .cell_extras(sheet, (row0 + 1, col0 + 1))
+        .is_some_and(|extras| extras.border.no_outline)
+    {
+        d.set_item("outline", false)?;
+    }
 
     Ok(d.into_any().unbind())
 }
 
+/// One border edge as `style` and `color` (with any theme or indexed
+/// reference), or None if it has no line.
+fn edge_items<'py>(
+    py: Python<'py>,
+    edge: &Border,
+    palette: &Palette,
+) -> PyResult<Option<Bound<'py, PyDict>>> {
+    let style = umya_border_style_to_str(edge.get_border_style());
+    if style == "none" {
+        return Ok(None);
+    }
+    let d = PyDict::new(py);
+    d.set_item("style", style)?;
+    d.set_item("color", "000000")?;
+    color_items(&d, "color", edge.get_color(), palette, false)?;
+    Ok(Some(d))
+}
+
 pub(crate) fn write_cell_border(
     book: &mut Spreadsheet,
+    styles: &mut StyleExtras,
     sheet: &str,
     a1: &str,
     border_dict: &Bound<'_, PyAny>,
@@ -352,96 +964,199 @@ pub(crate) fn write_cell_border(
         .cast::<PyDict>()
         .map_err(|_| PyErr::new::<PyValueError, _>("border_dict must be a dict"))?;
 
-    let style = ws.get_style_mut(a1);
-    let borders = style.get_borders_mut();
+    let (row0, col0) = a1_to_row_col(a1).map_err(|msg| PyErr::new::<PyValueError, _>(msg))?;
+    apply_border(
+        cell_with_row_col_style(ws, (col0 + 1, row0 + 1)).get_style_mut(),
+        dict,
+    )?;
+    styles.update_cell_extras(sheet, (row0 + 1, col0 + 1), |extras| {
+        extras.border.update(dict)
+    })
+}
 
-    fn apply_edge(
-        edge: &mut umya_spreadsheet::structs::Border,
-        sub: &Bound<'_, PyDict>,
-    ) -> PyResult<()> {
-        if let Some(s) = sub.get_item("style")? {
-            edge.set_border_style(s.extract::<String>()?);
-        }
+/// Canonical name of a border style given in any capitalization.
+fn border_style(style: &str) -> PyResult<&'static str> {
+    let canonical = umya_border_style_to_str(style);
+    if canonical == "none" && !style.is_empty() && !style.eq_ignore_ascii_case("none") {
+        return Err(PyErr::new::<PyValueError, _>(format!(
+            "Invalid border style: {style}"
+        )));
+    }
+    Ok(canonical)
+}
+
+fn apply_edge(edge: &mut Border, sub: &Bound<'_, PyDict>) -> PyResult<()> {
+    if let Some(s) = sub.get_item("style")? {
+        edge.set_border_style(border_style(&s.extract::<String>()?)?);
+    }
+    if !write_color_reference(edge.get_color_mut(), sub, "color")? {
         if let Some(c) = sub.get_item("color")? {
             let c = c.extract::<String>()?;
             edge.get_color_mut().set_argb(hex_to_argb(&c));
         }
-        Ok(())
     }
+    Ok(())
+}
 
-    fn is_enabled(sub: &Bound<'_, PyDict>) -> PyResult<bool> {
-        if let Some(s) = sub.get_item("style")? {
-            let style = s.extract::<String>()?;
-            Ok(!style.is_empty() && style != "none")
-        } else {
-            Ok(false)
-        }
-    }
+/// Mutable access to one edge of a border.
+type EdgeMut = fn(&mut Borders) -> &mut Border;
+
+/// Apply the edges present in a border dict to `style`.
+pub(crate) fn apply_border(style: &mut Style, dict: &Bound<'_, PyDict>) -> PyResult<()> {
+    let borders = style.get_borders_mut();
 
-    if let Some(sub) = dict.get_item("top")? {
-        if let Ok(d) = sub.cast::<PyDict>() {
-            apply_edge(borders.get_top_mut(), &d)?;
+    let edges: [(&str, EdgeMut); 5] = [
+        ("top", Borders::get_top_mut),
+        ("bottom", Borders::get_bottom_mut),
+        ("left", Borders::get_left_mut),
+        ("right", Borders::get_right_mut),
+        ("diagonal", Borders::get_diagonal_mut),
+    ];
+    for (key, edge) in edges {
+        if let Some(sub) = edge_dict(dict, key)? {
+            apply_edge(edge(borders), &sub)?;
         }
     }
-    if let Some(sub) = dict.get_item("bottom")? {
-        if let Ok(d) = sub.cast::<PyDict>() {
-            apply_edge(borders.get_bottom_mut(), &d)?;
+
+    // Diagonal borders share a single style ("diagonal") plus two direction
+    // flags (diagonalUp/diagonalDown). `diagonal_up` and `diagonal_down` set
+    // the flags, and the first enabled one the style, so a "none" for the
+    // other direction does not overwrite a real diagonal style.
+    let up = edge_dict(dict, "diagonal_up")?;
+    let down = edge_dict(dict, "diagonal_down")?;
+    if up.is_some() || down.is_some() {
+        let is_enabled = |sub: &Option<Bound<'_, PyDict>>| -> PyResult<bool> {
+            let Some(sub) = sub else {
+                return Ok(false);
+            };
+            Ok(match sub.get_item("style")? {
+                Some(s) => border_style(&s.extract::<String>()?)? != "none",
+                None => false,
+            })
+        };
+        let up_enabled = is_enabled(&up)?;
+        let down_enabled = is_enabled(&down)?;
+        let source = if up_enabled {
+            up.as_ref()
+        } else if down_enabled {
+            down.as_ref()
+        } else {
+            None
+        };
+        let diag = borders.get_diagonal_mut();
+        match source {
+            Some(sub) => apply_edge(diag, sub)?,
+            None => {
+                diag.set_border_style("none");
+            }
         }
+        borders.set_diagonal_up(up_enabled);
+        borders.set_diagonal_down(down_enabled);
     }
-    if let Some(sub) = dict.get_item("left")? {
-        if let Ok(d) = sub.cast::<PyDict>() {
-            apply_edge(borders.get_left_mut(), &d)?;
+
+    Ok(())
+}
+
+/// Apply a border dict to a range (`"B2:F200"`) or several areas.
+///
+/// Normally every cell gets the full border. With `outline`, `top`, `bottom`,
+/// `left` and `right` draw only the outer box of each area, and the grid
+/// lines inside come from `inner_horizontal` and `inner_vertical` (left
+/// untouched when absent). The dict's own `outline` key is the `<border>`
+/// attribute and is set on every cell.
+pub(crate) fn write_range_border(
+    book: &mut Spreadsheet,
+    styles: &mut StyleExtras,
+    sheet: &str,
+    range: &str,
+    border_dict: &Bound<'_, PyAny>,
+    outline: bool,
+) -> PyResult<()> {
+    let ws = book
+        .get_sheet_by_name_mut(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+
+    let dict = border_dict
+        .cast::<PyDict>()
+        .map_err(|_| PyErr::new::<PyValueError, _>("border_dict must be a dict"))?;
+
+    let areas = a1_areas_to_bounds(range).map_err(|msg| PyErr::new::<PyValueError, _>(msg))?;
+    if dict.contains("outline")? {
+        for &(min_row0, min_col0, max_row0, max_col0) in &areas {
+            for row in min_row0 + 1..=max_row0 + 1 {
+                for col in min_col0 + 1..=max_col0 + 1 {
+                    styles.update_cell_extras(sheet, (row, col), |extras| {
+                        extras.border.update(dict)
+                    })?;
+                }
+            }
         }
     }
-    if let Some(sub) = dict.get_item("right")? {
-        if let Ok(d) = sub.cast::<PyDict>() {
-            apply_edge(borders.get_right_mut(), &d)?;
+    if !outline {
+        for (min_row0, min_col0, max_row0, max_col0) in areas {
+            for row in min_row0 + 1..=max_row0 + 1 {
+                for col in min_col0 + 1..=max_col0 + 1 {
+                    apply_border(
+                        cell_with_row_col_style(ws, (col, row)).get_style_mut(),
+                        dict,
+                    )?;
+                }
+            }
         }
+        return Ok(());
     }
 
-    // Diagonal borders share a single style ("diagonal") plus two direction flags
-    // (diagonalUp/diagonalDown). Choose the first enabled direction as the style
-    // source so we don't overwrite a real diagonal style with a "none" from the
-    // other direction.
-    let mut saw_diag = false;
-    let mut diag_up_enabled = false;
-    let mut diag_down_enabled = false;
-    let mut diag_style: Option<String> = None;
-    let mut diag_color: Option<String> = None;
+    let top = edge_dict(dict, "top")?;
+    let bottom = edge_dict(dict, "bottom")?;
+    let left = edge_dict(dict, "left")?;
+    let right = edge_dict(dict, "right")?;
+    let inner_h = edge_dict(dict, "inner_horizontal")?;
+    let inner_v = edge_dict(dict, "inner_vertical")?;
 
-    for (key, enabled_flag) in [
-        ("diagonal_up", &mut diag_up_enabled),
-        ("diagonal_down", &mut diag_down_enabled),
-    ] {
-        if let Some(sub) = dict.get_item(key)? {
-            if let Ok(d) = sub.cast::<PyDict>() {
-                saw_diag = true;
-                *enabled_flag = is_enabled(&d)?;
-                if *enabled_flag && diag_style.is_none() {
-                    if let Some(s) = d.get_item("style")? {
-                        diag_style = Some(s.extract::<String>()?);
-                    }
-                    if let Some(c) = d.get_item("color")? {
-                        diag_color = Some(c.extract::<String>()?);
+    for (min_row0, min_col0, max_row0, max_col0) in areas {
+        let (min_row, max_row) = (min_row0 + 1, max_row0 + 1);
+        let (min_col, max_col) = (min_col0 + 1, max_col0 + 1);
+        for row in min_row..=max_row {
+            for col in min_col..=max_col {
+                // Outer edges of the area, or the inner grid.
+                let edges: [(Option<&Bound<'_, PyDict>>, EdgeMut); 4] = [
+                    (
+                        if row == min_row { &top } else { &inner_h }.as_ref(),
+                        Borders::get_top_mut,
+                    ),
+                    (
+                        if row == max_row { &bottom } else { &inner_h }.as_ref(),
+                        Borders::get_bottom_mut,
+                    ),
+                    (
+                        if col == min_col { &left } else { &inner_v }.as_ref(),
+                        Borders::get_left_mut,
+                    ),
+                    (
+                        if col == max_col { &right } else { &inner_v }.as_ref(),
+                        Borders::get_right_mut,
+                    ),
+                ];
+                if edges.iter().all(|(sub, _)| sub.is_none()) {
+                    continue;
+                }
+                let borders = cell_with_row_col_style(ws, (col, row))
+                    .get_style_mut()
+                    .get_borders_mut();
+                for (sub, edge) in edges {
+                    if let Some(sub) = sub {
+                        apply_edge(edge(borders), sub)?;
                     }
                 }
             }
         }
     }
-
-    if saw_diag {
-        let diag = borders.get_diagonal_mut();
-        if let Some(style_str) = diag_style {
-            diag.set_border_style(style_str);
-            if let Some(color_str) = diag_color {
-                diag.get_color_mut().set_argb(hex_to_argb(&color_str));
-            }
-        } else {
-            diag.set_border_style("none".to_string());
-        }
-        borders.set_diagonal_up(diag_up_enabled);
-        borders.set_diagonal_down(diag_down_enabled);
-    }
-
     Ok(())
 }
+
+/// The side dict under `key` of a border dict, if present.
+fn edge_dict<'py>(dict: &Bound<'py, PyDict>, key: &str) -> PyResult<Option<Bound<'py, PyDict>>> {
+    Ok(dict
+        .get_item(key)?
+        .and_then(|sub| sub.cast_into::<PyDict>().ok()))
+}
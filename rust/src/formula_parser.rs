@@ -0,0 +1,970 @@
+// Formula tokenizer and parser.
+//
+// Formulas are stored without the leading `=`. The tokenizer keeps the source
+// span of every token so callers can rewrite references in place without
+// disturbing the rest of the text; the parser builds a small expression tree
+// for evaluation.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::utils::{col_letter_to_u32, u32_to_col_letter, ERROR_TOKENS};
+
+/// Largest row and column Excel addresses (1048576 and XFD).
+pub(crate) const MAX_ROW: u32 = 1_048_576;
+pub(crate) const MAX_COL: u32 = 16_384;
+
+/// One corner of a reference. Whole-column references have no row and
+/// whole-row references have no column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct RefPoint {
+    pub row: Option<u32>,
+    pub col: Option<u32>,
+    pub row_abs: bool,
+    pub col_abs: bool,
+}
+
+/// A cell, area, whole-column or whole-row reference, optionally sheet-qualified.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Reference {
+    pub sheet: Option<String>,
+    pub start: RefPoint,
+    pub end: Option<RefPoint>,
+}
+
+impl Reference {
+    /// 1-based inclusive bounds `(min_row, min_col, max_row, max_col)`.
+    /// Open row/column ends extend to the sheet limits.
+    pub(crate) fn bounds(&self) -> (u32, u32, u32, u32) {
+        let end = self.end.unwrap_or(self.start);
+        let (r1, r2) = (self.start.row.unwrap_or(1), end.row.unwrap_or(MAX_ROW));
+        let (c1, c2) = (self.start.col.unwrap_or(1), end.col.unwrap_or(MAX_COL));
+        (r1.min(r2), c1.min(c2), r1.max(r2), c1.max(c2))
+    }
+
+    pub(crate) fn is_single_cell(&self) -> bool {
+        self.end.is_none() && self.start.row.is_some() && self.start.col.is_some()
+    }
+}
+
+impl fmt::Display for RefPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(col) = self.col {
+            if self.col_abs {
+                f.write_str("$")?;
+            }
+            f.write_str(&u32_to_col_letter(col))?;
+        }
+        if let Some(row) = self.row {
+            if self.row_abs {
+                f.write_str("$")?;
+            }
+            write!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Reference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(sheet) = &self.sheet {
+            write!(f, "{}!", quote_sheet_name(sheet))?;
+        }
+        write!(f, "{}", self.start)?;
+        if let Some(end) = &self.end {
+            write!(f, ":{end}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Quote a sheet name for use in a formula when it is not a plain identifier.
+pub(crate) fn quote_sheet_name(name: &str) -> String {
+    let plain = !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+        && parse_point(name).is_none();
+    if plain {
+        name.to_string()
+    } else {
+        format!("'{}'", name.replace('\'', "''"))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Token {
+    Number(f64),
+    Text(String),
+    Bool(bool),
+    Error(String),
+    Ref(Reference),
+    /// A defined name or other bare identifier.
+    Name(String),
+    /// A function name; the opening parenthesis is consumed with it.
+    Func(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+    Comma,
+    /// Text the tokenizer does not interpret (array constant braces and row
+    /// separators, structured references, `@`, ...), kept as written so the
+    /// references around it can still be rewritten.
+    Other(String),
+}
+
+/// A token with its `[start, end)` character span in the source formula.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Spanned {
+    pub token: Token,
+    pub start: usize,
+    pub end: usize,
+}
+
+pub(crate) fn tokenize(formula: &str) -> Result<Vec<Spanned>, String> {
+    let chars: Vec<char> = formula.chars().collect();
+    let mut out = Vec::new();
+    let mut i = 0;
+    // Skip a stray leading `=`.
+    if chars.first() == Some(&'=') {
+        i = 1;
+    }
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        let token = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '"' => {
+                let mut s = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        Some('"') if chars.get(i + 1) == Some(&'"') => {
+                            s.push('"');
+                            i += 2;
+                        }
+                        Some('"') => {
+                            i += 1;
+                            break;
+                        }
+                        Some(&ch) => {
+                            s.push(ch);
+                            i += 1;
+                        }
+                        None => return Err("Unterminated string literal".to_string()),
+                    }
+                }
+                Token::Text(s)
+            }
+            '#' => {
+                let rest: String = chars[i..].iter().collect();
+                let upper = rest.to_ascii_uppercase();
+                match ERROR_TOKENS.iter().find(|t| upper.starts_with(*t)) {
+                    Some(token) => {
+                        i += token.chars().count();
+                        Token::Error(token.to_string())
+                    }
+                    None => {
+                        i += 1;
+                        Token::Other("#".to_string())
+                    }
+                }
+            }
+            '\'' => {
+                let mut sheet = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        Some('\'') if chars.get(i + 1) == Some(&'\'') => {
+                            sheet.push('\'');
+                            i += 2;
+                        }
+                        Some('\'') => {
+                            i += 1;
+                            break;
+                        }
+                        Some(&ch) => {
+                            sheet.push(ch);
+                            i += 1;
+                        }
+                        None => return Err("Unterminated sheet name".to_string()),
+                    }
+                }
+                if chars.get(i) != Some(&'!') {
+                    return Err(format!("Expected '!' after sheet name in: {formula}"));
+                }
+                i += 1;
+                match scan_reference(&chars, &mut i, Some(sheet)) {
+                    Some(token) => token,
+                    None => sheet_name_token(&chars, &mut i, start),
+                }
+            }
+            '[' => {
+                // A structured reference's brackets, which may nest.
+                let mut depth = 0;
+                while let Some(&ch) = chars.get(i) {
+                    i += 1;
+                    match ch {
+                        '[' => depth += 1,
+                        ']' if depth == 1 => break,
+                        ']' => depth -= 1,
+                        _ => {}
+                    }
+                }
+                // An external workbook prefix (`[1]Sheet1!A1`) keeps the
+                // reference after it, which points into the other workbook.
+                while i < chars.len() && (is_word_char(chars[i]) || matches!(chars[i], '!' | ':')) {
+                    i += 1;
+                }
+                Token::Other(chars[start..i].iter().collect())
+            }
+            '(' => {
+                i += 1;
+                Token::LParen
+            }
+            ')' => {
+                i += 1;
+                Token::RParen
+            }
+            ',' => {
+                i += 1;
+                Token::Comma
+            }
+            '+' | '-' | '*' | '/' | '^' | '&' | '%' | '=' => {
+                i += 1;
+                Token::Op(match c {
+                    '+' => "+",
+                    '-' => "-",
+                    '*' => "*",
+                    '/' => "/",
+                    '^' => "^",
+                    '&' => "&",
+                    '%' => "%",
+                    _ => "=",
+                })
+            }
+            '<' | '>' => {
+                i += 1;
+                let op = match (c, chars.get(i)) {
+                    ('<', Some('=')) => "<=",
+                    ('<', Some('>')) => "<>",
+                    ('>', Some('=')) => ">=",
+                    ('<', _) => "<",
+                    _ => ">",
+                };
+                if op.len() == 2 {
+                    i += 1;
+                }
+                Token::Op(op)
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                // `1:3` is a whole-row range, anything else a number.
+                let mut j = i;
+                if let Some(token) = scan_reference(&chars, &mut j, None) {
+                    i = j;
+                    token
+                } else {
+                    Token::Number(scan_number(&chars, &mut i, formula)?)
+                }
+            }
+            c if c.is_alphabetic() || c == '_' || c == '\\' || c == '$' => {
+                scan_word_token(&chars, &mut i)
+            }
+            _ => {
+                i += 1;
+                Token::Other(c.to_string())
+            }
+        };
+        out.push(Spanned {
+            token,
+            start,
+            end: i,
+        });
+    }
+    Ok(out)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '.' | '$' | '\\')
+}
+
+fn scan_number(chars: &[char], i: &mut usize, formula: &str) -> Result<f64, String> {
+    let start = *i;
+    while *i < chars.len() && (chars[*i].is_ascii_digit() || chars[*i] == '.') {
+        *i += 1;
+    }
+    if matches!(chars.get(*i), Some('e' | 'E')) {
+        let mut j = *i + 1;
+        if matches!(chars.get(j), Some('+' | '-')) {
+            j += 1;
+        }
+        if chars.get(j).is_some_and(|c| c.is_ascii_digit()) {
+            while j < chars.len() && chars[j].is_ascii_digit() {
+                j += 1;
+            }
+            *i = j;
+        }
+    }
+    let text: String = chars[start..*i].iter().collect();
+    text.parse::<f64>()
+        .map_err(|_| format!("Invalid number '{text}' in: {formula}"))
+}
+
+/// An identifier: sheet prefix, function, boolean, reference or name.
+fn scan_word_token(chars: &[char], i: &mut usize) -> Token {
+    let start = *i;
+    let mut end = start;
+    while end < chars.len() && is_word_char(chars[end]) {
+        end += 1;
+    }
+    let word: String = chars[start..end].iter().collect();
+
+    match chars.get(end) {
+        Some('!') => {
+            *i = end + 1;
+            return scan_reference(chars, i, Some(word))
+                .unwrap_or_else(|| sheet_name_token(chars, i, start));
+        }
+        Some('(') => {
+            *i = end + 1;
+            return Token::Func(word.to_ascii_uppercase());
+        }
+        _ => {}
+    }
+
+    let mut j = start;
+    if let Some(token) = scan_reference(chars, &mut j, None) {
+        *i = j;
+        return token;
+    }
+    *i = end;
+    match word.to_ascii_uppercase().as_str() {
+        "TRUE" => Token::Bool(true),
+        "FALSE" => Token::Bool(false),
+        _ => Token::Name(word),
+    }
+}
+
+/// What follows a sheet prefix that is not a reference, such as a
+/// sheet-level defined name: a name spelled as written from `start`.
+fn sheet_name_token(chars: &[char], i: &mut usize, start: usize) -> Token {
+    while *i < chars.len() && is_word_char(chars[*i]) {
+        *i += 1;
+    }
+    Token::Name(chars[start..*i].iter().collect())
+}
+
+/// Scan a reference (`A1`, `$A$1:B2`, `A:C`, `1:3`, `#REF!`) at `*i`.
+/// On success `*i` is advanced past it; otherwise it is left unchanged.
+fn scan_reference(chars: &[char], i: &mut usize, sheet: Option<String>) -> Option<Token> {
+    let read_part = |from: usize| -> (String, usize) {
+        let mut end = from;
+        while end < chars.len() && (chars[end].is_ascii_alphanumeric() || chars[end] == '$') {
+            end += 1;
+        }
+        (chars[from..end].iter().collect(), end)
+    };
+
+    // `Sheet1!#REF!` after a deleted area.
+    if sheet.is_some() && chars.get(*i) == Some(&'#') {
+        let rest: String = chars[*i..].iter().take(5).collect();
+        if rest.eq_ignore_ascii_case("#REF!") {
+            *i += 5;
+            return Some(Token::Error("#REF!".to_string()));
+        }
+        return None;
+    }
+
+    let (first, after_first) = read_part(*i);
+    let start = parse_point(&first)?;
+    // A word continuing past the alphanumerics (e.g. `A1.x`) is a name.
+    if chars
+        .get(after_first)
+        .is_some_and(|&c| is_word_char(c) && c != '$')
+    {
+        return None;
+    }
+
+    let mut end_point = None;
+    let mut next = after_first;
+    if chars.get(after_first) == Some(&':') {
+        let (second, after_second) = read_part(after_first + 1);
+        if let Some(p) = parse_point(&second) {
+            let same_kind = (start.row.is_some() == p.row.is_some())
+                && (start.col.is_some() == p.col.is_some());
+            if same_kind {
+                end_point = Some(p);
+                next = after_second;
+            }
+        }
+    }
+
+    // A bare column (`A`) or row (`1`) is only a reference as part of a range.
+    let is_cell = start.row.is_some() && start.col.is_some();
+    if !is_cell && end_point.is_none() {
+        return None;
+    }
+    // Cell-looking function and table names such as `LOG10(` and `Tbl1[` are
+    // not references.
+    if matches!(chars.get(next), Some('(' | '[')) {
+        return None;
+    }
+
+    *i = next;
+    Some(Token::Ref(Reference {
+        sheet,
+        start,
+        end: end_point,
+    }))
+}
+
+/// Parse `$A$1`, `A`, `$1` and friends into a reference corner.
+pub(crate) fn parse_point(s: &str) -> Option<RefPoint> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut i = 0;
+
+    let col_abs = chars.get(i) == Some(&'$');
+    if col_abs {
+        i += 1;
+    }
+    let col_start = i;
+    while i < chars.len() && chars[i].is_ascii_alphabetic() {
+        i += 1;
+    }
+    let col_letters: String = chars[col_start..i].iter().collect();
+
+    let mut row_abs = false;
+    if chars.get(i) == Some(&'$') {
+        row_abs = true;
+        i += 1;
+    }
+    let row_start = i;
+    while i < chars.len() && chars[i].is_ascii_digit() {
+        i += 1;
+    }
+    let row_digits: String = chars[row_start..i].iter().collect();
+    if i != chars.len() {
+        return None;
+    }
+
+    let col = if col_letters.is_empty() {
+        if col_abs {
+            // `$1`: the `$` belongs to the row.
+            row_abs = true;
+        }
+        None
+    } else {
+        if col_letters.len() > 3 {
+            return None;
+        }
+        let n = col_letter_to_u32(&col_letters).ok()?;
+        if n > MAX_COL {
+            return None;
+        }
+        Some(n)
+    };
+    let row = if row_digits.is_empty() {
+        if row_abs {
+            return None;
+        }
+        None
+    } else {
+        let n: u32 = row_digits.parse().ok()?;
+        if n == 0 || n > MAX_ROW {
+            return None;
+        }
+        Some(n)
+    };
+    if col.is_none() && row.is_none() {
+        return None;
+    }
+    Some(RefPoint {
+        row,
+        col,
+        row_abs,
+        col_abs: col_abs && col.is_some(),
+    })
+}
+
+/// Rebuild a formula, replacing each reference token with the text returned by
+/// `rewrite`. Everything else keeps its original spelling.
+pub(crate) fn rewrite_references<F>(formula: &str, mut rewrite: F) -> Result<String, String>
+where
+    F: FnMut(&Reference) -> Option<String>,
+{
+    let tokens = tokenize(formula)?;
+    let chars: Vec<char> = formula.chars().collect();
+    let mut out = String::with_capacity(formula.len());
+    let mut pos = 0;
+    for t in &tokens {
+        if let Token::Ref(r) = &t.token {
+            if let Some(text) = rewrite(r) {
+                out.extend(&chars[pos..t.start]);
+                out.push_str(&text);
+                pos = t.end;
+            }
+        }
+    }
+    out.extend(&chars[pos..]);
+    Ok(out)
+}
+
+/// Move a reference corner by `(rows, cols)`, leaving absolute parts alone.
+/// Returns `None` when it would leave the sheet.
+fn shift_point(p: RefPoint, rows: i64, cols: i64) -> Option<RefPoint> {
+    let shift = |v: Option<u32>, abs: bool, by: i64, max: u32| -> Option<Option<u32>> {
+        match v {
+            Some(n) if !abs => {
+                let moved = i64::from(n) + by;
+                (1..=i64::from(max))
+                    .contains(&moved)
+                    .then_some(Some(moved as u32))
+            }
+            other => Some(other),
+        }
+    };
+    Some(RefPoint {
+        row: shift(p.row, p.row_abs, rows, MAX_ROW)?,
+        col: shift(p.col, p.col_abs, cols, MAX_COL)?,
+        ..p
+    })
+}
+
+/// Translate a formula copied `rows` down and `cols` right, as Excel does when
+/// filling a shared formula or pasting a cell: relative references move,
+/// absolute ones stay, and references pushed off the sheet become `#REF!`.
+pub(crate) fn translate_formula(formula: &str, rows: i64, cols: i64) -> Result<String, String> {
+    if rows == 0 && cols == 0 {
+        return Ok(formula.to_string());
+    }
+    rewrite_references(formula, |r| {
+        let start = shift_point(r.start, rows, cols);
+        let end = r.end.map(|e| shift_point(e, rows, cols));
+        let moved = match (start, end) {
+            (Some(start), None) => Reference {
+                start,
+                end: None,
+                sheet: r.sheet.clone(),
+            },
+            (Some(start), Some(Some(end))) => Reference {
+                start,
+                end: Some(end),
+                sheet: r.sheet.clone(),
+            },
+            _ => return Some(ref_error(r.sheet.as_deref())),
+        };
+        (moved != *r).then(|| moved.to_string())
+    })
+}
+
+/// The `#REF!` a reference collapses to, keeping its sheet qualifier.
+pub(crate) fn ref_error(sheet: Option<&str>) -> String {
+    match sheet {
+        Some(s) => format!("{}!#REF!", quote_sheet_name(s)),
+        None => "#REF!".to_string(),
+    }
+}
+
+/// Whether two sheet names are the same sheet. Excel ignores case, beyond
+/// ASCII too, when matching sheet names.
+pub(crate) fn same_sheet(a: &str, b: &str) -> bool {
+    a == b || a.to_lowercase() == b.to_lowercase()
+}
+
+/// Point references to sheet `old` at sheet `new`, as renaming a sheet does.
+pub(crate) fn rename_sheet_refs(formula: &str, old: &str, new: &str) -> Result<String, String> {
+    rewrite_references(formula, |r| {
+        let sheet = r.sheet.as_deref()?;
+        same_sheet(sheet, old).then(|| {
+            Reference {
+                sheet: Some(new.to_string()),
+                ..r.clone()
+            }
+            .to_string()
+        })
+    })
+}
+
+/// Rows or columns inserted into or deleted from a sheet: `by` lines before
+/// line `at` when positive, `-by` lines starting at `at` when negative.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct LineShift {
+    pub rows: bool,
+    pub at: u32,
+    pub by: i64,
+}
+
+impl LineShift {
+    /// Where a span of lines `lo..=hi` ends up. Deleting part of it shrinks
+    /// it, inserting inside it grows it; `None` when it is deleted entirely
+    /// or pushed off the sheet.
+    fn span(&self, lo: u32, hi: u32) -> Option<(u32, u32)> {
+        let max = if self.rows { MAX_ROW } else { MAX_COL };
+        let (lo, hi, at) = (i64::from(lo), i64::from(hi), i64::from(self.at));
+        let (lo, hi) = if self.by >= 0 {
+            let grow = |v: i64| if v >= at { v + self.by } else { v };
+            (grow(lo), grow(hi))
+        } else {
+            let last = at - self.by - 1;
+            let lo = if lo > last { lo + self.by } else { lo.min(at) };
+            let hi = if hi > last {
+                hi + self.by
+            } else {
+                hi.min(at - 1)
+            };
+            (lo, hi)
+        };
+        (lo <= hi && hi <= i64::from(max)).then_some((lo as u32, hi as u32))
+    }
+
+    /// Whether any of the 1-based `(min_row, min_col, max_row, max_col)` area
+    /// is left, rather than deleted entirely or pushed off the sheet.
+    pub(crate) fn keeps_area(&self, (r1, c1, r2, c2): (u32, u32, u32, u32)) -> bool {
+        if self.rows {
+            self.span(r1, r2).is_some()
+        } else {
+            self.span(c1, c2).is_some()
+        }
+    }
+
+    /// Where a single line ends up, if it is not deleted.
+    pub(crate) fn line(&self, line: u32) -> Option<u32> {
+        self.span(line, line).map(|(lo, _)| lo)
+    }
+
+    /// Where the cell at 1-based `(row, col)` ends up, if it is not deleted.
+    pub(crate) fn cell(&self, (row, col): (u32, u32)) -> Option<(u32, u32)> {
+        if self.rows {
+            self.line(row).map(|r| (r, col))
+        } else {
+            self.line(col).map(|c| (row, c))
+        }
+    }
+
+    /// Move entries keyed by 1-based `(row, col)` along with their cells,
+    /// dropping those of deleted cells.
+    pub(crate) fn move_cells<V>(&self, cells: &mut HashMap<(u32, u32), V>) {
+        *cells = cells
+            .drain()
+            .filter_map(|(cell, v)| Some((self.cell(cell)?, v)))
+            .collect();
+    }
+
+    fn reference(&self, r: &Reference) -> Option<Reference> {
+        let line = |p: &RefPoint| if self.rows { p.row } else { p.col };
+        let end = r.end.unwrap_or(r.start);
+        let (Some(a), Some(b)) = (line(&r.start), line(&end)) else {
+            // Whole columns are unaffected by rows, and whole rows by columns.
+            return Some(r.clone());
+        };
+        let (lo, hi) = self.span(a.min(b), a.max(b))?;
+        let (a, b) = if a <= b { (lo, hi) } else { (hi, lo) };
+        let set = |p: RefPoint, v: u32| {
+            if self.rows {
+                RefPoint { row: Some(v), ..p }
+            } else {
+                RefPoint { col: Some(v), ..p }
+            }
+        };
+        Some(Reference {
+            sheet: r.sheet.clone(),
+            start: set(r.start, a),
+            end: r.end.map(|e| set(e, b)),
+        })
+    }
+}
+
+/// Adjust a formula on sheet `host` (`None` when it belongs to no sheet,
+/// like a workbook-level defined name) to lines inserted into or deleted from
+/// `sheet`, as Excel does: references to `sheet` move whether relative or
+/// absolute, areas grow or shrink, and deleted ones become `#REF!`.
+pub(crate) fn shift_formula(
+    formula: &str,
+    host: Option<&str>,
+    sheet: &str,
+    shift: LineShift,
+) -> Result<String, String> {
+    rewrite_references(formula, |r| {
+        let target = r.sheet.as_deref().or(host)?;
+        if !same_sheet(target, sheet) {
+            return None;
+        }
+        match shift.reference(r) {
+            Some(moved) => (moved != *r).then(|| moved.to_string()),
+            None => Some(ref_error(r.sheet.as_deref())),
+        }
+    })
+}
+
+/// Adjust a formula on sheet `host` to the cells in `area` (1-based
+/// `(min_row, min_col, max_row, max_col)`) on `sheet` being moved `rows` down
+/// and `cols` right, as Excel does on cut and paste: references inside the
+/// area follow it, relative or absolute, and references to the cells it
+/// lands on become `#REF!`.
+pub(crate) fn move_formula(
+    formula: &str,
+    host: Option<&str>,
+    sheet: &str,
+    area: (u32, u32, u32, u32),
+    rows: i64,
+    cols: i64,
+) -> Result<String, String> {
+    let (r1, c1, r2, c2) = area;
+    let inside = |(a1, b1, a2, b2): (u32, u32, u32, u32), rows: i64, cols: i64| {
+        let (a1, a2) = (i64::from(a1) - rows, i64::from(a2) - rows);
+        let (b1, b2) = (i64::from(b1) - cols, i64::from(b2) - cols);
+        a1 >= i64::from(r1) && a2 <= i64::from(r2) && b1 >= i64::from(c1) && b2 <= i64::from(c2)
+    };
+    let move_point = |p: RefPoint| RefPoint {
+        row: p.row.map(|v| (i64::from(v) + rows) as u32),
+        col: p.col.map(|v| (i64::from(v) + cols) as u32),
+        ..p
+    };
+    rewrite_references(formula, |r| {
+        let target = r.sheet.as_deref().or(host)?;
+        if !same_sheet(target, sheet) {
+            return None;
+        }
+        if inside(r.bounds(), 0, 0) {
+            let moved = Reference {
+                sheet: r.sheet.clone(),
+                start: move_point(r.start),
+                end: r.end.map(move_point),
+            };
+            return Some(moved.to_string());
+        }
+        inside(r.bounds(), rows, cols).then(|| ref_error(r.sheet.as_deref()))
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Expression tree
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+    Concat,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Expr {
+    Number(f64),
+    Text(String),
+    Bool(bool),
+    Error(String),
+    Ref(Reference),
+    Name(String),
+    Neg(Box<Expr>),
+    Percent(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+    /// An omitted function argument, as in `IF(A1,,1)`.
+    Missing,
+}
+
+impl Expr {
+    /// Visit every reference in the expression.
+    pub(crate) fn for_each_ref<F: FnMut(&Reference)>(&self, f: &mut F) {
+        match self {
+            Expr::Ref(r) => f(r),
+            Expr::Neg(e) | Expr::Percent(e) => e.for_each_ref(f),
+            Expr::Binary(_, a, b) => {
+                a.for_each_ref(f);
+                b.for_each_ref(f);
+            }
+            Expr::Call(_, args) => args.iter().for_each(|a| a.for_each_ref(f)),
+            _ => {}
+        }
+    }
+}
+
+pub(crate) fn parse_formula(formula: &str) -> Result<Expr, String> {
+    let tokens = tokenize(formula)?;
+    let mut p = Parser {
+        tokens,
+        pos: 0,
+        source: formula.chars().collect(),
+    };
+    let expr = p.comparison()?;
+    if p.pos < p.tokens.len() {
+        return Err(format!(
+            "Unexpected '{}' in: {formula}",
+            p.token_text(p.pos)
+        ));
+    }
+    Ok(expr)
+}
+
+struct Parser {
+    tokens: Vec<Spanned>,
+    pos: usize,
+    source: Vec<char>,
+}
+
+impl Parser {
+    /// Source text of the token at `idx`, for error messages.
+    fn token_text(&self, idx: usize) -> String {
+        let t = &self.tokens[idx];
+        self.source[t.start..t.end].iter().collect()
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|t| &t.token)
+    }
+
+    fn peek_op(&self) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Op(op)) => Some(op),
+            _ => None,
+        }
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let t = self.tokens.get(self.pos).map(|t| t.token.clone());
+        self.pos += 1;
+        t
+    }
+
+    // Precedence, loosest first: comparison, `&`, `+ -`, `* /`, `^`, `%`,
+    // unary minus, then primaries.
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.concat()?;
+        while let Some(op) = self.peek_op() {
+            let op = match op {
+                "=" => BinOp::Eq,
+                "<>" => BinOp::Ne,
+                "<" => BinOp::Lt,
+                "<=" => BinOp::Le,
+                ">" => BinOp::Gt,
+                ">=" => BinOp::Ge,
+                _ => break,
+            };
+            self.pos += 1;
+            let rhs = self.concat()?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn concat(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.additive()?;
+        while self.peek_op() == Some("&") {
+            self.pos += 1;
+            let rhs = self.additive()?;
+            lhs = Expr::Binary(BinOp::Concat, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn additive(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.multiplicative()?;
+        while let Some(op @ ("+" | "-")) = self.peek_op() {
+            self.pos += 1;
+            let rhs = self.multiplicative()?;
+            let op = if op == "+" { BinOp::Add } else { BinOp::Sub };
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.power()?;
+        while let Some(op @ ("*" | "/")) = self.peek_op() {
+            self.pos += 1;
+            let rhs = self.power()?;
+            let op = if op == "*" { BinOp::Mul } else { BinOp::Div };
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn power(&mut self) -> Result<Expr, String> {
+        // Excel evaluates `^` left to right: 2^3^2 is 64.
+        let mut lhs = self.percent()?;
+        while self.peek_op() == Some("^") {
+            self.pos += 1;
+            let rhs = self.percent()?;
+            lhs = Expr::Binary(BinOp::Pow, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn percent(&mut self) -> Result<Expr, String> {
+        let mut e = self.unary()?;
+        while self.peek_op() == Some("%") {
+            self.pos += 1;
+            e = Expr::Percent(Box::new(e));
+        }
+        Ok(e)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        match self.peek_op() {
+            Some("-") => {
+                self.pos += 1;
+                Ok(Expr::Neg(Box::new(self.unary()?)))
+            }
+            Some("+") => {
+                self.pos += 1;
+                self.unary()
+            }
+            _ => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Number(n)),
+            Some(Token::Text(s)) => Ok(Expr::Text(s)),
+            Some(Token::Bool(b)) => Ok(Expr::Bool(b)),
+            Some(Token::Error(e)) => Ok(Expr::Error(e)),
+            Some(Token::Ref(r)) => Ok(Expr::Ref(r)),
+            Some(Token::Name(n)) => Ok(Expr::Name(n)),
+            Some(Token::LParen) => {
+                let e = self.comparison()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(e),
+                    _ => Err("Expected ')'".to_string()),
+                }
+            }
+            Some(Token::Func(name)) => {
+                let mut args = Vec::new();
+                if self.peek() == Some(&Token::RParen) {
+                    self.pos += 1;
+                    return Ok(Expr::Call(name, args));
+                }
+                loop {
+                    if matches!(self.peek(), Some(Token::Comma | Token::RParen)) {
+                        args.push(Expr::Missing);
+                    } else {
+                        args.push(self.comparison()?);
+                    }
+                    match self.next() {
+                        Some(Token::Comma) => continue,
+                        Some(Token::RParen) => break,
+                        _ => return Err(format!("Expected ',' or ')' in call to {name}")),
+                    }
+                }
+                Ok(Expr::Call(name, args))
+            }
+            Some(_) => Err(format!("Unexpected '{}'", self.token_text(self.pos - 1))),
+            None => Err("Unexpected end of formula".to_string()),
+        }
+    }
+}
@@ -1,14 +1,26 @@
 use pyo3::prelude::*;
 
+mod autofit_ops;
+mod calc_ops;
 mod cell_ops;
 mod comment_ops;
 mod conditional_format_ops;
 mod data_validation_ops;
+mod font_metrics;
 mod format_ops;
+mod formula_parser;
 mod hyperlink_ops;
 mod image_ops;
+mod named_style_ops;
+mod number_format;
+mod package_ops;
+mod palette;
+mod range_ops;
+mod rich_text_ops;
+mod sheet_ops;
 mod structural_ops;
 mod utils;
+mod view_ops;
 mod workbook;
 mod worksheet;
 
@@ -0,0 +1,657 @@
+// Named cell styles ("Normal", "Heading 1", custom ones).
+//
+// umya formats each cell independently and only writes the "Normal" style, so
+// the registry lives here: styles read from the package on open, or defined
+// from a format dict, plus which cells use each one. Applying a style copies
+// its formatting onto the cells, so they render the same in readers that
+// ignore named styles; on save the registry and the cell links are written
+// into the styles part.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use umya_spreadsheet::structs::{
+    Alignment, Border, Borders, Color, EnumTrait, Fill, Font, GradientFill, GradientStop,
+    HorizontalAlignmentValues, NumberingFormat, PatternValues, Style, VerticalAlignmentValues,
+};
+use umya_spreadsheet::Spreadsheet;
+
+use crate::format_ops::{apply_border, apply_format, StyleExtras, XfExtras};
+use crate::formula_parser::LineShift;
+use crate::package_ops::{
+    alignment_extras, alignment_with_extras, attr_value, border_extras, border_with_extras,
+    escape_xml, find_attr, find_elements, find_tags, flag, font_extras, font_with_extras,
+    StyleRecord,
+};
+use crate::utils::a1_areas_to_bounds;
+
+const NORMAL: &str = "Normal";
+
+/// `builtinId` of Excel's built-in styles, so a style defined under one of
+/// these names is recognized as the built-in one.
+const BUILTIN_STYLES: [(&str, u32); 25] = [
+    ("Normal", 0),
+    ("Comma", 3),
+    ("Currency", 4),
+    ("Percent", 5),
+    ("Comma [0]", 6),
+    ("Currency [0]", 7),
+    ("Hyperlink", 8),
+    ("Followed Hyperlink", 9),
+    ("Note", 10),
+    ("Warning Text", 11),
+    ("Title", 15),
+    ("Heading 1", 16),
+    ("Heading 2", 17),
+    ("Heading 3", 18),
+    ("Heading 4", 19),
+    ("Input", 20),
+    ("Output", 21),
+    ("Calculation", 22),
+    ("Check Cell", 23),
+    ("Linked Cell", 24),
+    ("Total", 25),
+    ("Good", 26),
+    ("Bad", 27),
+    ("Neutral", 28),
+    ("Explanatory Text", 53),
+];
+
+/// Cells using a named style other than "Normal", by sheet name and 1-based
+/// `(row, col)`.
+pub(crate) type CellStyles = HashMap<String, HashMap<(u32, u32), String>>;
+
+/// [`CellStyles`] with each style given by its index in the registry.
+pub(crate) type LinkedCells = HashMap<String, HashMap<(u32, u32), usize>>;
+
+#[derive(Debug)]
+struct NamedStyle {
+    name: String,
+    builtin_id: Option<u32>,
+    /// Formatting copied onto cells the style is applied to.
+    style: Style,
+    extras: XfExtras,
+    /// The style as read from the package, written back as-is unless the
+    /// style is redefined.
+    record: Option<StyleRecord>,
+}
+
+impl NamedStyle {
+    fn from_record(record: StyleRecord) -> Self {
+        Self {
+            name: record.name.clone(),
+            builtin_id: record.builtin_id,
+            style: record_style(&record),
+            extras: XfExtras {
+                font: record.font.as_deref().map(font_extras).unwrap_or_default(),
+                alignment: record
+                    .alignment
+                    .as_deref()
+                    .map(alignment_extras)
+                    .unwrap_or_default(),
+                border: record
+                    .border
+                    .as_deref()
+                    .map(border_extras)
+                    .unwrap_or_default(),
+            },
+            record: Some(record),
+        }
+    }
+
+    fn record(&self) -> StyleRecord {
+        self.record.clone().unwrap_or_else(|| style_record(self))
+    }
+}
+
+/// Named styles in `xfId` order ("Normal" first) and the cells using them.
+#[derive(Debug)]
+pub(crate) struct NamedStyles {
+    styles: Vec<NamedStyle>,
+    cells: CellStyles,
+}
+
+impl Default for NamedStyles {
+    fn default() -> Self {
+        Self::new(Vec::new(), CellStyles::new())
+    }
+}
+
+impl NamedStyles {
+    pub(crate) fn new(records: Vec<StyleRecord>, cells: CellStyles) -> Self {
+        let mut styles: Vec<NamedStyle> =
+            records.into_iter().map(NamedStyle::from_record).collect();
+        let normal = styles
+            .iter()
+            .position(|s| s.builtin_id == Some(0) || s.name == NORMAL);
+        match normal {
+            Some(i) => styles[..=i].rotate_right(1),
+            None => styles.insert(
+                0,
+                NamedStyle {
+                    name: NORMAL.to_string(),
+                    builtin_id: Some(0),
+                    style: Style::default(),
+                    extras: XfExtras::default(),
+                    record: None,
+                },
+            ),
+        }
+        Self { styles, cells }
+    }
+
+    /// Named style of a cell, if it uses one other than "Normal".
+    pub(crate) fn cell_style(&self, sheet: &str, cell: (u32, u32)) -> Option<&str> {
+        self.cells.get(sheet)?.get(&cell).map(String::as_str)
+    }
+
+    /// Link a cell to a named style; `None` or "Normal" unlinks it.
+    pub(crate) fn set_cell_style(&mut self, sheet: &str, cell: (u32, u32), name: Option<&str>) {
+        let cells = self.cells.entry(sheet.to_string()).or_default();
+        match name {
+            Some(name) if name != NORMAL => {
+                cells.insert(cell, name.to_string());
+            }
+            _ => {
+                cells.remove(&cell);
+            }
+        }
+    }
+
+    /// Move style links along with the cells of a sheet.
+    pub(crate) fn shift_cells(&mut self, sheet: &str, shift: LineShift) {
+        if let Some(cells) = self.cells.get_mut(sheet) {
+            shift.move_cells(cells);
+        }
+    }
+
+    pub(crate) fn rename_sheet(&mut self, old: &str, new: &str) {
+        if let Some(cells) = self.cells.remove(old) {
+            self.cells.insert(new.to_string(), cells);
+        }
+    }
+
+    pub(crate) fn remove_sheet(&mut self, sheet: &str) {
+        self.cells.remove(sheet);
+    }
+
+    /// Style links of the cells of a sheet.
+    pub(crate) fn sheet_cells(&self, sheet: &str) -> HashMap<(u32, u32), String> {
+        self.cells.get(sheet).cloned().unwrap_or_default()
+    }
+
+    /// Link the cells of a copied sheet, keeping only links to styles defined
+    /// here.
+    pub(crate) fn link_sheet_cells(&mut self, sheet: &str, cells: HashMap<(u32, u32), String>) {
+        let cells = cells
+            .into_iter()
+            .filter(|(_, name)| self.styles.iter().any(|s| &s.name == name))
+            .collect();
+        self.cells.insert(sheet.to_string(), cells);
+    }
+
+    /// Whether the registry differs from a new workbook's.
+    pub(crate) fn is_custom(&self) -> bool {
+        self.styles.len() > 1 || self.cells.values().any(|c| !c.is_empty())
+    }
+
+    /// Style records in `xfId` order and the cells linked to each, by index.
+    pub(crate) fn records(&self) -> (Vec<StyleRecord>, LinkedCells) {
+        let index: HashMap<&str, usize> = self
+            .styles
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (s.name.as_str(), i))
+            .collect();
+        let cells = self
+            .cells
+            .iter()
+            .map(|(sheet, cells)| {
+                let linked = cells
+                    .iter()
+                    .filter_map(|(&cell, name)| Some((cell, *index.get(name.as_str())?)))
+                    .collect();
+                (sheet.clone(), linked)
+            })
+            .collect();
+        (self.styles.iter().map(NamedStyle::record).collect(), cells)
+    }
+}
+
+pub(crate) fn named_styles(styles: &StyleExtras) -> Vec<String> {
+    styles.named.styles.iter().map(|s| s.name.clone()).collect()
+}
+
+/// Add a named style built from a format dict and a border dict, or redefine
+/// an existing one. Cells already using the style pick up the new formatting.
+pub(crate) fn define_named_style(
+    book: &mut Spreadsheet,
+    styles: &mut StyleExtras,
+    name: &str,
+    format_dict: &Bound<'_, PyAny>,
+    border_dict: Option<&Bound<'_, PyAny>>,
+) -> PyResult<()> {
+    if name.trim().is_empty() {
+        return Err(PyErr::new::<PyValueError, _>(
+            "Style name must not be empty",
+        ));
+    }
+    let dict = format_dict
+        .cast::<PyDict>()
+        .map_err(|_| PyErr::new::<PyValueError, _>("format_dict must be a dict"))?;
+    let mut style = Style::default();
+    apply_format(&mut style, &mut styles.gradient_paths, dict)?;
+    let mut extras = XfExtras::default();
+    extras.update(dict)?;
+    if let Some(border) = border_dict.filter(|b| !b.is_none()) {
+        let border = border
+            .cast::<PyDict>()
+            .map_err(|_| PyErr::new::<PyValueError, _>("border_dict must be a dict"))?;
+        apply_border(&mut style, border)?;
+        extras.border.update(border)?;
+    }
+
+    let named = &mut styles.named;
+    match named.styles.iter_mut().find(|s| s.name == name) {
+        Some(existing) => {
+            existing.style = style.clone();
+            existing.extras = extras.clone();
+            existing.record = None;
+        }
+        None => named.styles.push(NamedStyle {
+            name: name.to_string(),
+            builtin_id: BUILTIN_STYLES
+                .iter()
+                .find(|(builtin, _)| *builtin == name)
+                .map(|&(_, id)| id),
+            style: style.clone(),
+            extras: extras.clone(),
+            record: None,
+        }),
+    }
+
+    let linked: Vec<(String, (u32, u32))> = named
+        .cells
+        .iter()
+        .flat_map(|(sheet, cells)| {
+            cells
+                .iter()
+                .filter(|(_, n)| n.as_str() == name)
+                .map(|(&cell, _)| (sheet.clone(), cell))
+        })
+        .collect();
+    for (sheet, (row, col)) in linked {
+        let Some(ws) = book.get_sheet_by_name_mut(&sheet) else {
+            continue;
+        };
+        ws.get_cell_mut((col, row)).set_style(style.clone());
+        styles.set_cell_extras(&sheet, (row, col), extras.clone());
+    }
+    Ok(())
+}
+
+/// Apply a named style to a cell, range or several areas (`"B2"`,
+/// `"B2:F200"`, `"A1:A5 C1:C5"`), replacing the cells' formatting with the
+/// style's.
+pub(crate) fn apply_named_style(
+    book: &mut Spreadsheet,
+    styles: &mut StyleExtras,
+    sheet: &str,
+    range: &str,
+    name: &str,
+) -> PyResult<()> {
+    let ws = book
+        .get_sheet_by_name_mut(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+    let areas = a1_areas_to_bounds(range).map_err(|msg| PyErr::new::<PyValueError, _>(msg))?;
+    let (style, extras) = styles
+        .named
+        .styles
+        .iter()
+        .find(|s| s.name == name)
+        .map(|s| (s.style.clone(), s.extras.clone()))
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown style: {name}")))?;
+
+    for (min_row0, min_col0, max_row0, max_col0) in areas {
+        for row in min_row0 + 1..=max_row0 + 1 {
+            for col in min_col0 + 1..=max_col0 + 1 {
+                ws.get_cell_mut((col, row)).set_style(style.clone());
+                styles.named.set_cell_style(sheet, (row, col), Some(name));
+                styles.set_cell_extras(sheet, (row, col), extras.clone());
+            }
+        }
+    }
+    Ok(())
+}
+
+// Conversion between umya styles and the XML of a style record.
+
+fn read_color(color: &mut Color, head: &str) {
+    if let Some(indexed) = attr_value(head, "indexed").and_then(|v| v.parse().ok()) {
+        color.set_indexed(indexed);
+    } else if let Some(theme) = attr_value(head, "theme").and_then(|v| v.parse().ok()) {
+        color.set_theme_index(theme);
+        if let Some(tint) = attr_value(head, "tint").and_then(|v| v.parse().ok()) {
+            color.set_tint(tint);
+        }
+    } else if let Some(rgb) = attr_value(head, "rgb") {
+        color.set_argb(rgb);
+    }
+}
+
+fn record_style(record: &StyleRecord) -> Style {
+    let mut style = Style::default();
+    if let Some((id, code)) = &record.num_fmt {
+        let nf = style.get_number_format_mut();
+        if code.is_empty() {
+            nf.set_number_format_id(*id);
+        } else {
+            nf.set_format_code(code.as_str());
+        }
+    }
+    if let Some(xml) = &record.font {
+        let font = style.get_font_mut();
+        font.set_bold(flag(xml, "b"));
+        font.set_italic(flag(xml, "i"));
+        font.set_strikethrough(flag(xml, "strike"));
+        if let Some((_, h)) = find_tags(xml, "u").first() {
+            font.set_underline(attr_value(h, "val").unwrap_or("single"));
+        }
+        if let Some(size) = find_attr(xml, "sz", "val").and_then(|v| v.parse().ok()) {
+            font.set_size(size);
+        }
+        if let Some(name) = find_attr(xml, "name", "val") {
+            font.set_name(name);
+        }
+        if let Some(family) = find_attr(xml, "family", "val").and_then(|v| v.parse().ok()) {
+            font.set_family(family);
+        }
+        if let Some(charset) = find_attr(xml, "charset", "val").and_then(|v| v.parse().ok()) {
+            font.set_charset(charset);
+        }
+        if let Some(scheme) = find_attr(xml, "scheme", "val") {
+            font.set_scheme(scheme);
+        }
+        if let Some((_, h)) = find_tags(xml, "color").first() {
+            read_color(font.get_color_mut(), h);
+        }
+    }
+    if let Some(xml) = &record.fill {
+        if let Some((_, gradient)) = find_elements(xml, "gradientFill").first() {
+            let mut gf = GradientFill::default();
+            if let Some(degree) =
+                find_attr(gradient, "gradientFill", "degree").and_then(|v| v.parse().ok())
+            {
+                gf.set_degree(degree);
+            }
+            for (_, stop) in find_elements(gradient, "stop") {
+                let mut gs = GradientStop::default();
+                if let Some(position) =
+                    find_attr(stop, "stop", "position").and_then(|v| v.parse().ok())
+                {
+                    gs.set_position(position);
+                }
+                let mut color = Color::default();
+                if let Some((_, h)) = find_tags(stop, "color").first() {
+                    read_color(&mut color, h);
+                }
+                gs.set_color(color);
+                gf.set_gradient_stop(gs);
+            }
+            let mut fill = Fill::default();
+            fill.set_gradient_fill(gf);
+            style.set_fill(fill);
+        } else if let Some(pattern) = find_attr(xml, "patternFill", "patternType") {
+            let pf = style.get_fill_mut().get_pattern_fill_mut();
+            if let Ok(pv) = PatternValues::from_str(pattern) {
+                pf.set_pattern_type(pv);
+            }
+            if let Some((_, h)) = find_tags(xml, "fgColor").first() {
+                read_color(pf.get_foreground_color_mut(), h);
+            }
+            if let Some((_, h)) = find_tags(xml, "bgColor").first() {
+                read_color(pf.get_background_color_mut(), h);
+            }
+        }
+    }
+    if let Some(xml) = &record.border {
+        let borders = style.get_borders_mut();
+        let edges: [(&str, fn(&mut Borders) -> &mut Border); 5] = [
+            ("left", Borders::get_left_mut),
+            ("right", Borders::get_right_mut),
+            ("top", Borders::get_top_mut),
+            ("bottom", Borders::get_bottom_mut),
+            ("diagonal", Borders::get_diagonal_mut),
+        ];
+        for (tag, edge) in edges {
+            let Some((_, element)) = find_elements(xml, tag).into_iter().next() else {
+                continue;
+            };
+            let Some(border_style) = find_attr(element, tag, "style") else {
+                continue;
+            };
+            let edge = edge(borders);
+            edge.set_border_style(border_style);
+            if let Some((_, h)) = find_tags(element, "color").first() {
+                read_color(edge.get_color_mut(), h);
+            }
+        }
+        borders.set_diagonal_up(matches!(
+            find_attr(xml, "border", "diagonalUp"),
+            Some("1") | Some("true")
+        ));
+        borders.set_diagonal_down(matches!(
+            find_attr(xml, "border", "diagonalDown"),
+            Some("1") | Some("true")
+        ));
+    }
+    if let Some(xml) = &record.alignment {
+        let align = style.get_alignment_mut();
+        if let Some(h) = find_attr(xml, "alignment", "horizontal") {
+            if let Ok(ha) = HorizontalAlignmentValues::from_str(h) {
+                align.set_horizontal(ha);
+            }
+        }
+        if let Some(v) = find_attr(xml, "alignment", "vertical") {
+            if let Ok(va) = VerticalAlignmentValues::from_str(v) {
+                align.set_vertical(va);
+            }
+        }
+        align.set_wrap_text(matches!(
+            find_attr(xml, "alignment", "wrapText"),
+            Some("1") | Some("true")
+        ));
+        if let Some(rot) = find_attr(xml, "alignment", "textRotation").and_then(|v| v.parse().ok())
+        {
+            align.set_text_rotation(rot);
+        }
+        if let Some(indent) = find_attr(xml, "alignment", "indent").and_then(|v| v.parse().ok()) {
+            align.set_indent(indent);
+        }
+    }
+    style
+}
+
+fn color_xml(tag: &str, color: &Color) -> String {
+    let indexed = *color.get_indexed();
+    let theme = *color.get_theme_index();
+    let tint = *color.get_tint();
+    let argb = color.get_argb();
+    if indexed != 0 {
+        format!(r#"<{tag} indexed="{indexed}"/>"#)
+    } else if theme != 0 || tint != 0.0 {
+        let tint = if tint != 0.0 {
+            format!(r#" tint="{tint}""#)
+        } else {
+            String::new()
+        };
+        format!(r#"<{tag} theme="{theme}"{tint}/>"#)
+    } else if !argb.is_empty() {
+        format!(r#"<{tag} rgb="{argb}"/>"#)
+    } else {
+        String::new()
+    }
+}
+
+fn font_xml(font: &Font) -> String {
+    let mut xml = String::from("<font>");
+    for (tag, on) in [
+        ("b", *font.get_bold()),
+        ("i", *font.get_italic()),
+        ("strike", *font.get_strikethrough()),
+    ] {
+        if on {
+            xml.push_str(&format!("<{tag}/>"));
+        }
+    }
+    let ul = font.get_underline();
+    if !ul.is_empty() && ul != "none" {
+        xml.push_str(&format!(r#"<u val="{ul}"/>"#));
+    }
+    let size = *font.get_size();
+    if size > 0.0 {
+        xml.push_str(&format!(r#"<sz val="{size}"/>"#));
+    }
+    xml.push_str(&color_xml("color", font.get_color()));
+    let name = font.get_name();
+    if !name.is_empty() {
+        xml.push_str(&format!(r#"<name val="{}"/>"#, escape_xml(name)));
+    }
+    for (tag, val) in [
+        ("family", *font.get_family()),
+        ("charset", *font.get_charset()),
+    ] {
+        if val > 0 {
+            xml.push_str(&format!(r#"<{tag} val="{val}"/>"#));
+        }
+    }
+    let scheme = font.get_scheme();
+    if !scheme.is_empty() && scheme != "none" {
+        xml.push_str(&format!(r#"<scheme val="{scheme}"/>"#));
+    }
+    xml.push_str("</font>");
+    xml
+}
+
+fn fill_xml(fill: &Fill) -> Option<String> {
+    if let Some(gf) = fill.get_gradient_fill() {
+        let stops: String = gf
+            .get_gradient_stop()
+            .iter()
+            .map(|stop| {
+                format!(
+                    r#"<stop position="{}">{}</stop>"#,
+                    stop.get_position(),
+                    color_xml("color", stop.get_color())
+                )
+            })
+            .collect();
+        return Some(format!(
+            r#"<fill><gradientFill degree="{}">{stops}</gradientFill></fill>"#,
+            gf.get_degree()
+        ));
+    }
+    let pf = fill.get_pattern_fill()?;
+    let pattern = pf.get_pattern_type().get_value_string();
+    let fg = pf
+        .get_foreground_color()
+        .map_or(String::new(), |c| color_xml("fgColor", c));
+    let bg = pf
+        .get_background_color()
+        .map_or(String::new(), |c| color_xml("bgColor", c));
+    Some(format!(
+        r#"<fill><patternFill patternType="{pattern}">{fg}{bg}</patternFill></fill>"#
+    ))
+}
+
+fn border_xml(borders: &Borders) -> String {
+    let mut xml = String::from("<border");
+    if *borders.get_diagonal_up() {
+        xml.push_str(r#" diagonalUp="1""#);
+    }
+    if *borders.get_diagonal_down() {
+        xml.push_str(r#" diagonalDown="1""#);
+    }
+    xml.push('>');
+    for (tag, edge) in [
+        ("left", borders.get_left()),
+        ("right", borders.get_right()),
+        ("top", borders.get_top()),
+        ("bottom", borders.get_bottom()),
+        ("diagonal", borders.get_diagonal()),
+    ] {
+        let border_style = edge.get_border_style();
+        if border_style.is_empty() || border_style == "none" {
+            xml.push_str(&format!("<{tag}/>"));
+        } else {
+            xml.push_str(&format!(
+                r#"<{tag} style="{border_style}">{}</{tag}>"#,
+                color_xml("color", edge.get_color())
+            ));
+        }
+    }
+    xml.push_str("</border>");
+    xml
+}
+
+fn alignment_xml(align: &Alignment) -> Option<String> {
+    let mut attrs = String::new();
+    let h = align.get_horizontal().get_value_string();
+    if !h.is_empty() && h != "general" {
+        attrs.push_str(&format!(r#" horizontal="{h}""#));
+    }
+    let v = align.get_vertical().get_value_string();
+    if !v.is_empty() && v != "bottom" {
+        attrs.push_str(&format!(r#" vertical="{v}""#));
+    }
+    if *align.get_wrap_text() {
+        attrs.push_str(r#" wrapText="1""#);
+    }
+    let rot = *align.get_text_rotation();
+    if rot != 0 {
+        attrs.push_str(&format!(r#" textRotation="{rot}""#));
+    }
+    let indent = *align.get_indent();
+    if indent != 0 {
+        attrs.push_str(&format!(r#" indent="{indent}""#));
+    }
+    (!attrs.is_empty()).then(|| format!("<alignment{attrs}/>"))
+}
+
+fn num_fmt(nf: &NumberingFormat) -> Option<(u32, String)> {
+    let code = nf.get_format_code();
+    if code.is_empty() || code == "General" {
+        return None;
+    }
+    let id = *nf.get_number_format_id();
+    // Built-in formats are referenced by id alone.
+    if id != 0 && id < 164 {
+        Some((id, String::new()))
+    } else {
+        Some((0, code.to_string()))
+    }
+}
+
+fn style_record(named: &NamedStyle) -> StyleRecord {
+    let style = &named.style;
+    StyleRecord {
+        name: named.name.clone(),
+        builtin_id: named.builtin_id,
+        num_fmt: style.get_number_format().and_then(num_fmt),
+        font: style
+            .get_font()
+            .map(|font| font_with_extras(&font_xml(font), &named.extras.font)),
+        fill: style.get_fill().and_then(fill_xml),
+        border: style
+            .get_borders()
+            .map(|borders| border_with_extras(&border_xml(borders), &named.extras.border)),
+        alignment: alignment_with_extras(
+            style.get_alignment().and_then(alignment_xml),
+            &named.extras.alignment,
+        ),
+    }
+}
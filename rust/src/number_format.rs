@@ -0,0 +1,869 @@
+// Number-format classification.
+//
+// Excel format codes are small programs: up to four `;`-separated sections,
+// quoted literals, backslash escapes, `_x`/`*x` spacing and fill directives, and
+// bracketed color/condition/locale/elapsed-time tokens. Only the unquoted,
+// unescaped characters decide what kind of value a code displays.
+
+use chrono::{Datelike, Timelike};
+
+use crate::utils::excel_serial_to_naive_datetime;
+
+/// What kind of value a number format displays.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum NumberFormatKind {
+    General,
+    Number,
+    Percent,
+    Currency,
+    Text,
+    Date,
+    Time,
+    DateTime,
+    Duration,
+}
+
+impl NumberFormatKind {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            NumberFormatKind::General => "general",
+            NumberFormatKind::Number => "number",
+            NumberFormatKind::Percent => "percent",
+            NumberFormatKind::Currency => "currency",
+            NumberFormatKind::Text => "text",
+            NumberFormatKind::Date => "date",
+            NumberFormatKind::Time => "time",
+            NumberFormatKind::DateTime => "datetime",
+            NumberFormatKind::Duration => "duration",
+        }
+    }
+}
+
+/// Format code of a built-in number format ID (en-US defaults).
+///
+/// IDs that are absent here (e.g. 23-36 and 50-81) are locale-specific.
+pub(crate) fn builtin_format_code(id: u32) -> Option<&'static str> {
+    let code = match id {
+        0 => "General",
+        1 => "0",
+        2 => "0.00",
+        3 => "#,##0",
+        4 => "#,##0.00",
+        5 => "\"$\"#,##0_);(\"$\"#,##0)",
+        6 => "\"$\"#,##0_);[Red](\"$\"#,##0)",
+        7 => "\"$\"#,##0.00_);(\"$\"#,##0.00)",
+        8 => "\"$\"#,##0.00_);[Red](\"$\"#,##0.00)",
+        9 => "0%",
+        10 => "0.00%",
+        11 => "0.00E+00",
+        12 => "# ?/?",
+        13 => "# ??/??",
+        14 => "mm-dd-yy",
+        15 => "d-mmm-yy",
+        16 => "d-mmm",
+        17 => "mmm-yy",
+        18 => "h:mm AM/PM",
+        19 => "h:mm:ss AM/PM",
+        20 => "h:mm",
+        21 => "h:mm:ss",
+        22 => "m/d/yy h:mm",
+        37 => "#,##0 ;(#,##0)",
+        38 => "#,##0 ;[Red](#,##0)",
+        39 => "#,##0.00;(#,##0.00)",
+        40 => "#,##0.00;[Red](#,##0.00)",
+        41 => "_(* #,##0_);_(* \\(#,##0\\);_(* \"-\"_);_(@_)",
+        42 => "_(\"$\"* #,##0_);_(\"$\"* \\(#,##0\\);_(\"$\"* \"-\"_);_(@_)",
+        43 => "_(* #,##0.00_);_(* \\(#,##0.00\\);_(* \"-\"??_);_(@_)",
+        44 => "_(\"$\"* #,##0.00_);_(\"$\"* \\(#,##0.00\\);_(\"$\"* \"-\"??_);_(@_)",
+        45 => "mm:ss",
+        46 => "[h]:mm:ss",
+        47 => "mm:ss.0",
+        48 => "##0.0E+0",
+        49 => "@",
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Classify a cell's number format from its ID and (possibly empty) code.
+pub(crate) fn classify_number_format(id: u32, code: &str) -> NumberFormatKind {
+    if !code.is_empty() {
+        return classify_format_code(code);
+    }
+    match builtin_format_code(id) {
+        Some(code) => classify_format_code(code),
+        None => cjk_builtin_kind(id).unwrap_or(NumberFormatKind::General),
+    }
+}
+
+/// Kind of a locale-specific CJK built-in (IDs 27-36 and 50-58).
+///
+/// 32 and 33 are `h"時"mm"分"` style times in every CJK locale. 34, 35, 55 and
+/// 56 are the `上午/下午` times of the Chinese locales; Japanese and Korean
+/// use some of them for dates, but a time-kind value of a day or more still
+/// reads as a datetime. Everything else in the two ranges is a date.
+fn cjk_builtin_kind(id: u32) -> Option<NumberFormatKind> {
+    match id {
+        32..=35 | 55 | 56 => Some(NumberFormatKind::Time),
+        27..=31 | 36 | 50..=54 | 57 | 58 => Some(NumberFormatKind::Date),
+        _ => None,
+    }
+}
+
+/// Classify a number format code. The positive (first) section decides the kind.
+pub(crate) fn classify_format_code(code: &str) -> NumberFormatKind {
+    let sections = split_sections(code);
+    let first = sections.first().map(String::as_str).unwrap_or("");
+    if first.trim().is_empty() || first.trim().eq_ignore_ascii_case("general") {
+        return NumberFormatKind::General;
+    }
+
+    let t = scan_section(first);
+    if t.elapsed {
+        NumberFormatKind::Duration
+    } else if t.date && t.time {
+        NumberFormatKind::DateTime
+    } else if t.date {
+        NumberFormatKind::Date
+    } else if t.time {
+        NumberFormatKind::Time
+    } else if t.text && !t.digits {
+        NumberFormatKind::Text
+    } else if t.percent {
+        NumberFormatKind::Percent
+    } else if t.currency {
+        NumberFormatKind::Currency
+    } else if t.general {
+        NumberFormatKind::General
+    } else {
+        NumberFormatKind::Number
+    }
+}
+
+/// Split a format code on `;`, ignoring separators inside quotes, brackets and
+/// after escapes.
+pub(crate) fn split_sections(code: &str) -> Vec<String> {
+    let mut sections = vec![String::new()];
+    let mut chars = code.chars();
+    let mut in_quote = false;
+    let mut in_bracket = false;
+    while let Some(c) = chars.next() {
+        let current = sections.last_mut().expect("at least one section");
+        match c {
+            '"' if !in_bracket => in_quote = !in_quote,
+            '[' if !in_quote => in_bracket = true,
+            ']' if !in_quote => in_bracket = false,
+            '\\' if !in_quote && !in_bracket => {
+                current.push(c);
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+                continue;
+            }
+            ';' if !in_quote && !in_bracket => {
+                sections.push(String::new());
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    sections
+}
+
+#[derive(Default)]
+struct SectionTokens {
+    date: bool,
+    time: bool,
+    elapsed: bool,
+    percent: bool,
+    currency: bool,
+    text: bool,
+    digits: bool,
+    general: bool,
+}
+
+fn scan_section(section: &str) -> SectionTokens {
+    let mut t = SectionTokens::default();
+    let chars: Vec<char> = section.chars().collect();
+    // Month-or-minute `m` runs are resolved after the scan, once we know
+    // whether hours or seconds are adjacent.
+    let mut m_runs: Vec<(usize, usize)> = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '"' => {
+                // Quoted literal: skip to the closing quote. A quoted currency
+                // symbol (`"$"#,##0`) still makes the code a currency format.
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    t.currency |= is_currency_symbol(chars[i]);
+                    i += 1;
+                }
+            }
+            '\\' | '_' | '*' => {
+                // Escaped literal, width-of-char spacing, or repeat fill.
+                i += 1;
+            }
+            '[' => {
+                let end = chars[i..].iter().position(|&ch| ch == ']').map(|p| i + p);
+                let inner: String = match end {
+                    Some(e) => chars[i + 1..e].iter().collect(),
+                    None => chars[i + 1..].iter().collect(),
+                };
+                scan_bracket(&inner, &mut t);
+                i = end.unwrap_or(chars.len());
+            }
+            'e' | 'E' if matches!(chars.get(i + 1), Some('+' | '-')) => {
+                // Scientific notation, not the era year.
+                t.digits = true;
+                i += 1;
+            }
+            'g' | 'G' if starts_with_ignore_case(&chars[i..], "general") => {
+                t.general = true;
+                i += "general".len() - 1;
+            }
+            'b' | 'B' => {
+                // `bb`/`bbbb` is the Buddhist year; `B1`/`B2` select a calendar.
+                if matches!(chars.get(i + 1), Some('b' | 'B')) {
+                    t.date = true;
+                    i += 1;
+                }
+            }
+            'y' | 'Y' | 'd' | 'D' | 'e' | 'E' | 'g' | 'G' => t.date = true,
+            'h' | 'H' | 's' | 'S' => t.time = true,
+            'm' | 'M' => {
+                let start = i;
+                while i + 1 < chars.len() && matches!(chars[i + 1], 'm' | 'M') {
+                    i += 1;
+                }
+                m_runs.push((start, i));
+            }
+            'a' | 'A' if starts_with_ignore_case(&chars[i..], "am/pm") => {
+                t.time = true;
+                i += "am/pm".len() - 1;
+            }
+            'a' | 'A' if starts_with_ignore_case(&chars[i..], "a/p") => {
+                t.time = true;
+                i += "a/p".len() - 1;
+            }
+            '0' | '#' | '?' => t.digits = true,
+            '%' => t.percent = true,
+            '@' => t.text = true,
+            c if is_currency_symbol(c) => t.currency = true,
+            _ => {}
+        }
+        i += 1;
+    }
+
+    for (start, end) in m_runs {
+        let run_len = end - start + 1;
+        if run_len <= 2 && adjacent_to_time_token(&chars, start, end) {
+            t.time = true;
+        } else {
+            t.date = true;
+        }
+    }
+
+    t
+}
+
+/// Bracketed tokens: `[h]`/`[mm]`/`[ss]` elapsed time, `[$€-407]` currency
+/// with locale, `[$-409]` locale only, and colors/conditions (ignored).
+fn scan_bracket(inner: &str, t: &mut SectionTokens) {
+    let lc = inner.to_ascii_lowercase();
+    if !lc.is_empty() && lc.chars().all(|c| matches!(c, 'h' | 'm' | 's')) {
+        let first = lc.chars().next().expect("non-empty");
+        if lc.chars().all(|c| c == first) {
+            t.elapsed = true;
+        }
+        return;
+    }
+    if let Some(locale) = inner.strip_prefix('$') {
+        let symbol = locale.split('-').next().unwrap_or("");
+        if !symbol.is_empty() {
+            t.currency = true;
+        }
+    }
+}
+
+/// Whether an `m`/`mm` run sits next to an hour or second token, ignoring
+/// separators and literals, which makes it minutes rather than months.
+fn adjacent_to_time_token(chars: &[char], start: usize, end: usize) -> bool {
+    let is_skippable = |c: char| !c.is_ascii_alphabetic() && c != '[' && c != ']';
+
+    let mut j = start;
+    while j > 0 {
+        j -= 1;
+        let c = chars[j];
+        if is_skippable(c) {
+            continue;
+        }
+        if matches!(c, 'h' | 'H') {
+            return true;
+        }
+        if c == ']' {
+            // `[h]:mm` — look inside the bracket.
+            return j > 0 && matches!(chars[j - 1], 'h' | 'H');
+        }
+        break;
+    }
+
+    let mut j = end + 1;
+    while j < chars.len() {
+        let c = chars[j];
+        if is_skippable(c) || c == '[' {
+            j += 1;
+            continue;
+        }
+        return matches!(c, 's' | 'S');
+    }
+    false
+}
+
+fn starts_with_ignore_case(chars: &[char], word: &str) -> bool {
+    let mut it = chars.iter();
+    word.chars()
+        .all(|w| it.next().is_some_and(|c| c.eq_ignore_ascii_case(&w)))
+}
+
+fn is_currency_symbol(c: char) -> bool {
+    matches!(c, '$' | '€' | '£' | '¥' | '₹' | '₩' | '₽' | '¤')
+}
+
+// ---------------------------------------------------------------------------
+// Rendering
+// ---------------------------------------------------------------------------
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const DAY_NAMES: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+/// Round half away from zero to `digits` decimals, as Excel does.
+///
+/// The scaled value is first cut to 15 significant digits so that binary
+/// noise (`1.005 * 100 == 100.49999999999999`) does not flip the result.
+pub(crate) fn round_half_away(value: f64, digits: i32) -> f64 {
+    let factor = 10f64.powi(digits);
+    let scaled: f64 = format!("{:.14e}", value * factor)
+        .parse()
+        .unwrap_or(value * factor);
+    scaled.round() / factor
+}
+
+/// Render a number the way the General format does, with up to 15
+/// significant digits.
+pub(crate) fn format_general(value: f64) -> String {
+    if value == 0.0 {
+        return "0".to_string();
+    }
+    if !value.is_finite() {
+        return "#NUM!".to_string();
+    }
+    let abs = value.abs();
+    if (1e-9..1e15).contains(&abs) {
+        let magnitude = abs.log10().floor() as i32;
+        let decimals = (14 - magnitude).clamp(0, 24) as usize;
+        let s = format!("{value:.decimals$}");
+        if s.contains('.') {
+            return s.trim_end_matches('0').trim_end_matches('.').to_string();
+        }
+        return s;
+    }
+    let s = format!("{value:.14E}");
+    let (mantissa, exponent) = s.split_once('E').unwrap_or((&s, "0"));
+    let mantissa = if mantissa.contains('.') {
+        mantissa.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        mantissa
+    };
+    let exponent: i32 = exponent.parse().unwrap_or(0);
+    let sign = if exponent < 0 { '-' } else { '+' };
+    format!("{mantissa}E{sign}{:02}", exponent.abs())
+}
+
+/// Render `value` with an Excel number format code, as `TEXT()` does.
+pub(crate) fn format_value(value: f64, code: &str, date1904: bool) -> String {
+    let sections = split_sections(code);
+    let (section, value, signed) = match sections.len() {
+        1 => (sections[0].as_str(), value, true),
+        2 if value < 0.0 => (sections[1].as_str(), -value, false),
+        2 => (sections[0].as_str(), value, true),
+        _ if value < 0.0 => (sections[1].as_str(), -value, false),
+        _ if value == 0.0 => (sections[2].as_str(), value, true),
+        _ => (sections[0].as_str(), value, true),
+    };
+
+    if section.trim().eq_ignore_ascii_case("general") {
+        return format_general(value);
+    }
+    let t = scan_section(section);
+    if t.date || t.time || t.elapsed {
+        return format_datetime(value, section, date1904);
+    }
+    let body = format_numeric(value.abs(), section);
+    let has_digits = body.chars().any(|c| c.is_ascii_digit() && c != '0');
+    if signed && value < 0.0 && has_digits {
+        format!("-{body}")
+    } else {
+        body
+    }
+}
+
+enum NumPiece {
+    Digit(char),
+    Point,
+    Lit(String),
+    Exp { plus: bool, digits: usize },
+}
+
+fn format_numeric(value: f64, section: &str) -> String {
+    let chars: Vec<char> = section.chars().collect();
+    let mut pieces: Vec<NumPiece> = Vec::new();
+    let mut thousands = false;
+    let mut scale_commas = 0;
+    let mut percents = 0;
+    let mut seen_point = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '"' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&ch| ch == '"')
+                    .map_or(chars.len(), |p| i + 1 + p);
+                pieces.push(NumPiece::Lit(chars[i + 1..end].iter().collect()));
+                i = end;
+            }
+            '\\' => {
+                if let Some(&next) = chars.get(i + 1) {
+                    pieces.push(NumPiece::Lit(next.to_string()));
+                }
+                i += 1;
+            }
+            '_' => {
+                pieces.push(NumPiece::Lit(" ".to_string()));
+                i += 1;
+            }
+            '*' => i += 1,
+            '[' => {
+                let end = chars[i..]
+                    .iter()
+                    .position(|&ch| ch == ']')
+                    .map_or(chars.len(), |p| i + p);
+                let inner: String = chars[i + 1..end].iter().collect();
+                if let Some(locale) = inner.strip_prefix('$') {
+                    let symbol = locale.split('-').next().unwrap_or("");
+                    pieces.push(NumPiece::Lit(symbol.to_string()));
+                }
+                i = end;
+            }
+            '0' | '#' | '?' => pieces.push(NumPiece::Digit(c)),
+            '.' if !seen_point => {
+                seen_point = true;
+                pieces.push(NumPiece::Point);
+            }
+            ',' => {
+                let after_digit = matches!(pieces.last(), Some(NumPiece::Digit(_)));
+                let before_digit = matches!(chars.get(i + 1), Some('0' | '#' | '?'));
+                if after_digit && before_digit && !seen_point {
+                    thousands = true;
+                } else if after_digit {
+                    scale_commas += 1;
+                } else {
+                    pieces.push(NumPiece::Lit(",".to_string()));
+                }
+            }
+            '%' => {
+                percents += 1;
+                pieces.push(NumPiece::Lit("%".to_string()));
+            }
+            'e' | 'E' if matches!(chars.get(i + 1), Some('+' | '-')) => {
+                let plus = chars[i + 1] == '+';
+                let mut digits = 0;
+                i += 2;
+                while matches!(chars.get(i), Some('0' | '#' | '?')) {
+                    digits += 1;
+                    i += 1;
+                }
+                pieces.push(NumPiece::Exp { plus, digits });
+                continue;
+            }
+            '@' => {}
+            _ => pieces.push(NumPiece::Lit(c.to_string())),
+        }
+        i += 1;
+    }
+
+    let mut v = value * 100f64.powi(percents) / 1000f64.powi(scale_commas);
+
+    let exp_at = pieces
+        .iter()
+        .position(|p| matches!(p, NumPiece::Exp { .. }))
+        .unwrap_or(pieces.len());
+    let point_at = pieces[..exp_at]
+        .iter()
+        .position(|p| matches!(p, NumPiece::Point))
+        .unwrap_or(exp_at);
+    let int_slots: Vec<usize> = (0..point_at)
+        .filter(|&k| matches!(pieces[k], NumPiece::Digit(_)))
+        .collect();
+    let dec_slots: Vec<usize> = (point_at..exp_at)
+        .filter(|&k| matches!(pieces[k], NumPiece::Digit(_)))
+        .collect();
+
+    let mut exponent = 0i32;
+    if exp_at < pieces.len() && v != 0.0 {
+        exponent = v.log10().floor() as i32;
+        // Engineering formats (`##0.0E+0`) keep the exponent a multiple of
+        // the number of integer placeholders.
+        let group = int_slots.len().max(1) as i32;
+        if group > 1 {
+            exponent -= exponent.rem_euclid(group);
+        }
+        v /= 10f64.powi(exponent);
+    }
+
+    let rounded = round_half_away(v, dec_slots.len() as i32);
+    let text = format!("{rounded:.prec$}", prec = dec_slots.len());
+    let (int_part, frac_part) = text.split_once('.').unwrap_or((&text, ""));
+    let int_digits: Vec<char> = if int_part == "0" {
+        Vec::new()
+    } else {
+        int_part.chars().collect()
+    };
+
+    let mut rendered: Vec<String> = pieces
+        .iter()
+        .map(|p| match p {
+            NumPiece::Lit(s) => s.clone(),
+            NumPiece::Point => ".".to_string(),
+            _ => String::new(),
+        })
+        .collect();
+
+    if thousands {
+        let zeros = int_slots
+            .iter()
+            .filter(|&&k| matches!(pieces[k], NumPiece::Digit('0')))
+            .count();
+        let mut digits: String = int_digits.iter().collect();
+        while digits.len() < zeros {
+            digits.insert(0, '0');
+        }
+        if let Some(&first) = int_slots.first() {
+            rendered[first] = group_thousands(&digits);
+        }
+    } else if int_slots.is_empty() {
+        // `.00` still shows the integer digits in front of the point.
+        if point_at < exp_at {
+            let digits: String = int_digits.iter().collect();
+            rendered[point_at].insert_str(0, &digits);
+        }
+    } else {
+        let mut remaining = int_digits.len();
+        for (n, &k) in int_slots.iter().enumerate().rev() {
+            if remaining > 0 {
+                remaining -= 1;
+                rendered[k] = int_digits[remaining].to_string();
+            } else if let NumPiece::Digit(ph) = pieces[k] {
+                rendered[k] = placeholder_fill(ph);
+            }
+            if n == 0 && remaining > 0 {
+                let lead: String = int_digits[..remaining].iter().collect();
+                rendered[k].insert_str(0, &lead);
+            }
+        }
+    }
+
+    let frac: Vec<char> = frac_part.chars().collect();
+    let mut trailing = true;
+    for (n, &k) in dec_slots.iter().enumerate().rev() {
+        let digit = frac.get(n).copied().unwrap_or('0');
+        let NumPiece::Digit(ph) = pieces[k] else {
+            continue;
+        };
+        if trailing && digit == '0' && ph != '0' {
+            rendered[k] = placeholder_fill(ph);
+        } else {
+            trailing = false;
+            rendered[k] = digit.to_string();
+        }
+    }
+
+    if let Some(NumPiece::Exp { plus, digits }) = pieces.get(exp_at) {
+        let sign = match (exponent < 0, plus) {
+            (true, _) => "-",
+            (false, true) => "+",
+            (false, false) => "",
+        };
+        rendered[exp_at] = format!("E{sign}{:0width$}", exponent.abs(), width = *digits);
+    }
+
+    rendered.concat()
+}
+
+fn placeholder_fill(ph: char) -> String {
+    match ph {
+        '0' => "0".to_string(),
+        '?' => " ".to_string(),
+        _ => String::new(),
+    }
+}
+
+fn group_thousands(digits: &str) -> String {
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (n, c) in digits.chars().enumerate() {
+        if n > 0 && (digits.len() - n) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+enum DatePart {
+    Lit(String),
+    Year(usize),
+    Month(usize),
+    Day(usize),
+    Hour(usize),
+    Minute(usize),
+    Second(usize),
+    Fraction(usize),
+    Elapsed(char, usize),
+    AmPm { full: bool, upper: bool },
+}
+
+fn format_datetime(serial: f64, section: &str, date1904: bool) -> String {
+    let chars: Vec<char> = section.chars().collect();
+    let mut parts: Vec<DatePart> = Vec::new();
+    let mut i = 0;
+
+    let run = |i: usize, target: char| -> usize {
+        chars[i..]
+            .iter()
+            .take_while(|c| c.eq_ignore_ascii_case(&target))
+            .count()
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '"' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&ch| ch == '"')
+                    .map_or(chars.len(), |p| i + 1 + p);
+                parts.push(DatePart::Lit(chars[i + 1..end].iter().collect()));
+                i = end + 1;
+            }
+            '\\' => {
+                if let Some(&next) = chars.get(i + 1) {
+                    parts.push(DatePart::Lit(next.to_string()));
+                }
+                i += 2;
+            }
+            '_' => {
+                parts.push(DatePart::Lit(" ".to_string()));
+                i += 2;
+            }
+            '*' => i += 2,
+            '[' => {
+                let end = chars[i..]
+                    .iter()
+                    .position(|&ch| ch == ']')
+                    .map_or(chars.len(), |p| i + p);
+                let inner: String = chars[i + 1..end].iter().collect::<String>().to_lowercase();
+                if let Some(first) = inner.chars().next() {
+                    if matches!(first, 'h' | 'm' | 's') && inner.chars().all(|ch| ch == first) {
+                        parts.push(DatePart::Elapsed(first, inner.len()));
+                    }
+                }
+                i = end + 1;
+            }
+            'y' | 'Y' | 'e' | 'E' => {
+                let n = run(i, c);
+                parts.push(DatePart::Year(if c.eq_ignore_ascii_case(&'e') {
+                    4
+                } else {
+                    n
+                }));
+                i += n;
+            }
+            'd' | 'D' => {
+                let n = run(i, 'd');
+                parts.push(DatePart::Day(n));
+                i += n;
+            }
+            'h' | 'H' => {
+                let n = run(i, 'h');
+                parts.push(DatePart::Hour(n));
+                i += n;
+            }
+            's' | 'S' => {
+                let n = run(i, 's');
+                parts.push(DatePart::Second(n));
+                i += n;
+            }
+            'm' | 'M' => {
+                let n = run(i, 'm');
+                if n <= 2 && adjacent_to_time_token(&chars, i, i + n - 1) {
+                    parts.push(DatePart::Minute(n));
+                } else {
+                    parts.push(DatePart::Month(n));
+                }
+                i += n;
+            }
+            'a' | 'A' if starts_with_ignore_case(&chars[i..], "am/pm") => {
+                parts.push(DatePart::AmPm {
+                    full: true,
+                    upper: c == 'A',
+                });
+                i += "am/pm".len();
+            }
+            'a' | 'A' if starts_with_ignore_case(&chars[i..], "a/p") => {
+                parts.push(DatePart::AmPm {
+                    full: false,
+                    upper: c == 'A',
+                });
+                i += "a/p".len();
+            }
+            '.' if matches!(
+                parts.last(),
+                Some(DatePart::Second(_)) | Some(DatePart::Elapsed('s', _))
+            ) && chars.get(i + 1) == Some(&'0') =>
+            {
+                let n = chars[i + 1..].iter().take_while(|&&ch| ch == '0').count();
+                parts.push(DatePart::Fraction(n));
+                i += n + 1;
+            }
+            _ => {
+                parts.push(DatePart::Lit(c.to_string()));
+                i += 1;
+            }
+        }
+    }
+
+    let fraction_digits = parts
+        .iter()
+        .find_map(|p| match p {
+            DatePart::Fraction(n) => Some(*n),
+            _ => None,
+        })
+        .unwrap_or(0);
+    // Without fractional seconds the display rounds to the nearest second.
+    let unit = 86_400.0 * 10f64.powi(fraction_digits as i32);
+    let serial = (serial * unit).round() / unit;
+    let Some(dt) = excel_serial_to_naive_datetime(serial, date1904) else {
+        return "#".repeat(section.len().max(1));
+    };
+    let twelve_hour = parts.iter().any(|p| matches!(p, DatePart::AmPm { .. }));
+    let total_seconds = serial * 86_400.0;
+
+    let mut out = String::new();
+    for p in &parts {
+        match p {
+            DatePart::Lit(s) => out.push_str(s),
+            DatePart::Year(n) if *n <= 2 => out.push_str(&format!("{:02}", dt.year() % 100)),
+            DatePart::Year(_) => out.push_str(&format!("{:04}", dt.year())),
+            DatePart::Month(n) => {
+                let name = MONTH_NAMES[dt.month0() as usize];
+                match n {
+                    1 => out.push_str(&dt.month().to_string()),
+                    2 => out.push_str(&format!("{:02}", dt.month())),
+                    3 => out.push_str(&name[..3]),
+                    5 => out.push_str(&name[..1]),
+                    _ => out.push_str(name),
+                }
+            }
+            DatePart::Day(n) => {
+                let name = DAY_NAMES[dt.weekday().num_days_from_monday() as usize];
+                match n {
+                    1 => out.push_str(&dt.day().to_string()),
+                    2 => out.push_str(&format!("{:02}", dt.day())),
+                    3 => out.push_str(&name[..3]),
+                    _ => out.push_str(name),
+                }
+            }
+            DatePart::Hour(n) => {
+                let h = if twelve_hour {
+                    match dt.hour() % 12 {
+                        0 => 12,
+                        h => h,
+                    }
+                } else {
+                    dt.hour()
+                };
+                if *n >= 2 {
+                    out.push_str(&format!("{h:02}"));
+                } else {
+                    out.push_str(&h.to_string());
+                }
+            }
+            DatePart::Minute(n) => pad_component(&mut out, dt.minute(), *n),
+            DatePart::Second(n) => pad_component(&mut out, dt.second(), *n),
+            DatePart::Fraction(n) => {
+                let frac = total_seconds - total_seconds.floor();
+                let digits = format!("{frac:.n$}", n = *n);
+                out.push_str(digits.trim_start_matches('0'));
+            }
+            DatePart::Elapsed(unit, n) => {
+                let value = match unit {
+                    'h' => (total_seconds / 3600.0).floor(),
+                    'm' => (total_seconds / 60.0).floor(),
+                    _ => total_seconds.floor(),
+                };
+                out.push_str(&format!("{:0width$}", value as i64, width = *n));
+            }
+            DatePart::AmPm { full, upper } => {
+                let pm = dt.hour() >= 12;
+                let s = match (full, pm) {
+                    (true, false) => "AM",
+                    (true, true) => "PM",
+                    (false, false) => "A",
+                    (false, true) => "P",
+                };
+                if *upper {
+                    out.push_str(s);
+                } else {
+                    out.push_str(&s.to_lowercase());
+                }
+            }
+        }
+    }
+    out
+}
+
+fn pad_component(out: &mut String, value: u32, width: usize) {
+    if width >= 2 {
+        out.push_str(&format!("{value:02}"));
+    } else {
+        out.push_str(&value.to_string());
+    }
+}
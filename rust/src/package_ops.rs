@@ -0,0 +1,1615 @@
+// Direct access to OOXML package parts that umya-spreadsheet does not model.
+// These helpers read or patch individual XML parts inside the .xlsx zip after
+// umya has done the heavy lifting, so only the attributes we care about are
+// touched.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs::{self, File};
+use std::hash::Hash;
+use std::io::{self, Cursor, Read, Write};
+use std::path::Path;
+
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::format_ops::{AlignmentExtras, BorderExtras, CellXfExtras, FontExtras, XfExtras};
+use crate::named_style_ops::LinkedCells;
+use crate::structural_ops::{Outline, Outlines};
+use crate::utils::{a1_to_row_col, argb_to_hex, u32_to_col_letter};
+use crate::view_ops::{SheetViewFlags, ViewFlags};
+
+const WORKBOOK_PART: &str = "xl/workbook.xml";
+const WORKBOOK_RELS_PART: &str = "xl/_rels/workbook.xml.rels";
+const CONTENT_TYPES_PART: &str = "[Content_Types].xml";
+const METADATA_PART: &str = "xl/metadata.xml";
+const SHARED_STRINGS_PART: &str = "xl/sharedStrings.xml";
+const STYLES_PART: &str = "xl/styles.xml";
+const THEME_PART: &str = "xl/theme/theme1.xml";
+
+/// The parts of a package, read once and patched in memory. Patched and added
+/// parts are written out with every other entry in a single pass by
+/// [`Package::save`].
+pub(crate) struct Package {
+    archive: ZipArchive<Cursor<Vec<u8>>>,
+    /// Text of the parts read or written so far.
+    parts: HashMap<String, String>,
+    /// Parts patched or added, to write on save.
+    changed: BTreeSet<String>,
+}
+
+impl Package {
+    pub(crate) fn new(bytes: Vec<u8>) -> io::Result<Self> {
+        Ok(Self {
+            archive: ZipArchive::new(Cursor::new(bytes)).map_err(io::Error::other)?,
+            parts: HashMap::new(),
+            changed: BTreeSet::new(),
+        })
+    }
+
+    pub(crate) fn open(path: &Path) -> io::Result<Self> {
+        Self::new(fs::read(path)?)
+    }
+
+    /// The text of a part, if the package has it.
+    pub(crate) fn part(&mut self, part: &str) -> io::Result<Option<String>> {
+        if let Some(xml) = self.parts.get(part) {
+            return Ok(Some(xml.clone()));
+        }
+        let mut entry = match self.archive.by_name(part) {
+            Ok(e) => e,
+            Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+            Err(e) => return Err(io::Error::other(e)),
+        };
+        let mut xml = String::new();
+        entry.read_to_string(&mut xml)?;
+        drop(entry);
+        self.parts.insert(part.to_string(), xml.clone());
+        Ok(Some(xml))
+    }
+
+    /// Rewrite one part. Does nothing if the part does not exist.
+    pub(crate) fn patch<F>(&mut self, part: &str, patch: F) -> io::Result<()>
+    where
+        F: FnOnce(&str) -> String,
+    {
+        if let Some(xml) = self.part(part)? {
+            self.put(part, patch(&xml));
+        }
+        Ok(())
+    }
+
+    /// Add a part, replacing it if it already exists.
+    pub(crate) fn put(&mut self, part: &str, content: String) {
+        self.parts.insert(part.to_string(), content);
+        self.changed.insert(part.to_string());
+    }
+
+    /// Write the package to `path`. It is written to a temporary file first
+    /// and renamed into place, so a failure leaves any file at `path` as it
+    /// was.
+    pub(crate) fn save(mut self, path: &Path) -> io::Result<()> {
+        let tmp_path = path.with_extension("xlsx.tmp");
+        match self.write(&tmp_path) {
+            Ok(()) => fs::rename(&tmp_path, path),
+            Err(e) => {
+                let _ = fs::remove_file(&tmp_path);
+                Err(e)
+            }
+        }
+    }
+
+    /// Copy every entry to `path`, unchanged entries as stored and changed
+    /// ones from their new text, followed by the added parts.
+    fn write(&mut self, path: &Path) -> io::Result<()> {
+        let mut writer = ZipWriter::new(File::create(path)?);
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        for i in 0..self.archive.len() {
+            let entry = self.archive.by_index(i).map_err(io::Error::other)?;
+            let name = entry.name().to_string();
+            if !self.changed.remove(&name) {
+                writer.raw_copy_file(entry).map_err(io::Error::other)?;
+                continue;
+            }
+            drop(entry);
+            writer
+                .start_file(name.as_str(), options)
+                .map_err(io::Error::other)?;
+            writer.write_all(self.parts[&name].as_bytes())?;
+        }
+        for name in std::mem::take(&mut self.changed) {
+            writer
+                .start_file(name.as_str(), options)
+                .map_err(io::Error::other)?;
+            writer.write_all(self.parts[&name].as_bytes())?;
+        }
+        writer.finish().map_err(io::Error::other)?;
+        Ok(())
+    }
+}
+
+/// Return the raw value of `attr` on the first `<tag ...>` element, if any.
+pub(crate) fn find_attr<'a>(xml: &'a str, tag: &str, attr: &str) -> Option<&'a str> {
+    let start = find_tag(xml, tag)?;
+    let end = start + xml[start..].find('>')?;
+    attr_value(&xml[start..end], attr)
+}
+
+/// Raw value of `attr` within a single element head (`<tag a="1" b="2"`).
+pub(crate) fn attr_value<'a>(head: &'a str, attr: &str) -> Option<&'a str> {
+    let needle = format!(" {attr}=\"");
+    let value_start = head.find(&needle)? + needle.len();
+    let value_len = head[value_start..].find('"')?;
+    Some(&head[value_start..value_start + value_len])
+}
+
+/// Byte offsets and heads of every `<tag ...>` element, in document order.
+pub(crate) fn find_tags<'a>(xml: &'a str, tag: &str) -> Vec<(usize, &'a str)> {
+    let mut out = Vec::new();
+    let mut from = 0;
+    while let Some(pos) = find_tag(&xml[from..], tag) {
+        let at = from + pos;
+        let Some(len) = xml[at..].find('>') else {
+            break;
+        };
+        out.push((at, &xml[at..at + len]));
+        from = at + len;
+    }
+    out
+}
+
+/// Byte offsets and full text of every `<tag ...>...</tag>` (or self-closing
+/// `<tag/>`) element, in document order. Elements must not nest in themselves.
+pub(crate) fn find_elements<'a>(xml: &'a str, tag: &str) -> Vec<(usize, &'a str)> {
+    let close = format!("</{tag}>");
+    let mut out = Vec::new();
+    let mut from = 0;
+    while let Some(pos) = find_tag(&xml[from..], tag) {
+        let at = from + pos;
+        let Some(head_len) = xml[at..].find('>') else {
+            break;
+        };
+        let end = if xml[..at + head_len].ends_with('/') {
+            at + head_len + 1
+        } else {
+            match xml[at..].find(&close) {
+                Some(len) => at + len + close.len(),
+                None => break,
+            }
+        };
+        out.push((at, &xml[at..end]));
+        from = end;
+    }
+    out
+}
+
+/// Text content of the first `<tag>` element, if it has any.
+fn element_text<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let (_, element) = find_elements(xml, tag).into_iter().next()?;
+    let start = element.find('>')? + 1;
+    element[start..].strip_suffix(&format!("</{tag}>"))
+}
+
+pub(crate) fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub(crate) fn unescape_xml(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Whether a flag element such as `<b/>` is present and not `val="0"`.
+pub(crate) fn flag(xml: &str, tag: &str) -> bool {
+    find_tags(xml, tag)
+        .first()
+        .is_some_and(|(_, h)| !matches!(attr_value(h, "val"), Some("0") | Some("false")))
+}
+
+/// Byte offset of the first `<tag` element opening (not a longer tag name).
+fn find_tag(xml: &str, tag: &str) -> Option<usize> {
+    let open = format!("<{tag}");
+    let mut from = 0;
+    while let Some(pos) = xml[from..].find(&open) {
+        let at = from + pos;
+        match xml[at + open.len()..].chars().next() {
+            Some(c) if c.is_whitespace() || c == '/' || c == '>' => return Some(at),
+            _ => from = at + open.len(),
+        }
+    }
+    None
+}
+
+/// Whether the package uses the 1904 date system.
+pub(crate) fn read_date1904(pkg: &mut Package) -> io::Result<bool> {
+    let Some(xml) = pkg.part(WORKBOOK_PART)? else {
+        return Ok(false);
+    };
+    Ok(matches!(
+        find_attr(&xml, "workbookPr", "date1904"),
+        Some("1") | Some("true")
+    ))
+}
+
+/// Mark a saved package as using the 1904 date system.
+pub(crate) fn write_date1904(pkg: &mut Package) -> io::Result<()> {
+    pkg.patch(WORKBOOK_PART, |xml| {
+        if let Some(&(at, pr)) = find_tags(xml, "workbookPr").first() {
+            return format!(
+                "{}{}{}",
+                &xml[..at],
+                set_attr(pr, "date1904", "1"),
+                &xml[at + pr.len()..]
+            );
+        }
+        // No <workbookPr> at all: it must precede <bookViews>/<sheets>.
+        let anchor = find_tag(xml, "bookViews").or_else(|| find_tag(xml, "sheets"));
+        match anchor {
+            Some(at) => format!("{}<workbookPr date1904=\"1\"/>{}", &xml[..at], &xml[at..]),
+            None => xml.to_string(),
+        }
+    })
+}
+
+/// Index of the first sheet shown in the tab bar (`workbookView/@firstSheet`).
+pub(crate) fn read_first_sheet(pkg: &mut Package) -> io::Result<u32> {
+    let Some(xml) = pkg.part(WORKBOOK_PART)? else {
+        return Ok(0);
+    };
+    Ok(find_attr(&xml, "workbookView", "firstSheet")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0))
+}
+
+/// Set the first sheet shown in the tab bar of a saved package.
+pub(crate) fn write_first_sheet(pkg: &mut Package, index: u32) -> io::Result<()> {
+    pkg.patch(WORKBOOK_PART, |xml| {
+        let Some(&(at, view)) = find_tags(xml, "workbookView").first() else {
+            return xml.to_string();
+        };
+        format!(
+            "{}{}{}",
+            &xml[..at],
+            set_attr(view, "firstSheet", &index.to_string()),
+            &xml[at + view.len()..]
+        )
+    })
+}
+
+/// Theme color scheme slots in SpreadsheetML `theme` index order.
+const THEME_SLOTS: [&str; 12] = [
+    "lt1", "dk1", "lt2", "dk2", "accent1", "accent2", "accent3", "accent4", "accent5", "accent6",
+    "hlink", "folHlink",
+];
+
+/// `RRGGBB` of each theme color, in `theme` index order. `None` if the package
+/// has no theme or its color scheme is incomplete.
+pub(crate) fn read_theme_colors(pkg: &mut Package) -> io::Result<Option<Vec<String>>> {
+    let Some(xml) = pkg.part(THEME_PART)? else {
+        return Ok(None);
+    };
+    Ok(THEME_SLOTS
+        .iter()
+        .map(|slot| {
+            let (_, element) = find_elements(&xml, &format!("a:{slot}"))
+                .into_iter()
+                .next()?;
+            find_attr(element, "a:srgbClr", "val")
+                .or_else(|| find_attr(element, "a:sysClr", "lastClr"))
+                .map(str::to_ascii_uppercase)
+        })
+        .collect())
+}
+
+/// `RRGGBB` of each entry of a custom legacy palette (`<indexedColors>`), if any.
+pub(crate) fn read_indexed_colors(pkg: &mut Package) -> io::Result<Option<Vec<String>>> {
+    let Some(xml) = pkg.part(STYLES_PART)? else {
+        return Ok(None);
+    };
+    let Some((_, block)) = find_elements(&xml, "indexedColors").into_iter().next() else {
+        return Ok(None);
+    };
+    let colors: Vec<String> = find_tags(block, "rgbColor")
+        .into_iter()
+        .filter_map(|(_, head)| attr_value(head, "rgb"))
+        .map(argb_to_hex)
+        .collect();
+    Ok((!colors.is_empty()).then_some(colors))
+}
+
+/// Worksheet names paired with their part paths, in workbook order.
+fn sheet_parts(pkg: &mut Package) -> io::Result<Vec<(String, String)>> {
+    let (Some(workbook), Some(rels)) = (pkg.part(WORKBOOK_PART)?, pkg.part(WORKBOOK_RELS_PART)?)
+    else {
+        return Ok(Vec::new());
+    };
+    let targets: HashMap<&str, &str> = find_tags(&rels, "Relationship")
+        .into_iter()
+        .filter_map(|(_, head)| Some((attr_value(head, "Id")?, attr_value(head, "Target")?)))
+        .collect();
+    Ok(find_tags(&workbook, "sheet")
+        .into_iter()
+        .filter_map(|(_, head)| {
+            let name = unescape_xml(attr_value(head, "name")?);
+            let target = targets.get(attr_value(head, "r:id")?)?;
+            let part = match target.strip_prefix('/') {
+                Some(absolute) => absolute.to_string(),
+                None => format!("xl/{target}"),
+            };
+            Some((name, part))
+        })
+        .collect())
+}
+
+// Dynamic arrays. Excel marks the anchor of a spilling formula with cell
+// metadata (`<c cm="1">`) that points at an `XLDAPR` entry in xl/metadata.xml.
+
+const DYNAMIC_ARRAY_METADATA: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+    "\n",
+    r#"<metadata xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" "#,
+    r#"xmlns:xda="http://schemas.microsoft.com/office/spreadsheetml/2017/dynamicarray">"#,
+    r#"<metadataTypes count="1"><metadataType name="XLDAPR" minSupportedVersion="120000" "#,
+    r#"copy="1" pasteAll="1" pasteValues="1" merge="1" splitFirst="1" rowColShift="1" "#,
+    r#"clearFormats="1" clearComments="1" assign="1" coerce="1" cellMeta="1"/></metadataTypes>"#,
+    r#"<futureMetadata name="XLDAPR" count="1"><bk><extLst>"#,
+    r#"<ext uri="{bdbb8cdc-fa1e-496e-a857-3c3f30c029c3}">"#,
+    r#"<xda:dynamicArrayProperties fDynamic="1" fCollapsed="0"/></ext></extLst></bk>"#,
+    r#"</futureMetadata><cellMetadata count="1"><bk><rc t="1" v="0"/></bk></cellMetadata>"#,
+    r#"</metadata>"#,
+);
+
+/// Cells carrying dynamic-array metadata, by sheet name and 1-based `(row, col)`.
+pub(crate) fn read_dynamic_arrays(
+    pkg: &mut Package,
+) -> io::Result<HashMap<String, HashSet<(u32, u32)>>> {
+    let mut out: HashMap<String, HashSet<(u32, u32)>> = HashMap::new();
+    for (name, part) in sheet_parts(pkg)? {
+        let Some(xml) = pkg.part(&part)? else {
+            continue;
+        };
+        for (_, head) in find_tags(&xml, "c") {
+            if attr_value(head, "cm").is_none() {
+                continue;
+            }
+            if let Some(Ok((row0, col0))) = attr_value(head, "r").map(a1_to_row_col) {
+                out.entry(name.clone())
+                    .or_default()
+                    .insert((row0 + 1, col0 + 1));
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Mark the given cells of a saved package as dynamic-array anchors.
+pub(crate) fn write_dynamic_arrays(
+    pkg: &mut Package,
+    cells: &HashMap<String, HashSet<(u32, u32)>>,
+) -> io::Result<()> {
+    if cells.values().all(HashSet::is_empty) {
+        return Ok(());
+    }
+    for (name, part) in sheet_parts(pkg)? {
+        let Some(marked) = cells.get(&name).filter(|m| !m.is_empty()) else {
+            continue;
+        };
+        let refs: HashSet<String> = marked
+            .iter()
+            .map(|&(row, col)| format!("{}{row}", u32_to_col_letter(col)))
+            .collect();
+        pkg.patch(&part, |xml| {
+            let mut out = String::with_capacity(xml.len() + refs.len() * 8);
+            let mut pos = 0;
+            for (at, head) in find_tags(xml, "c") {
+                let hit = attr_value(head, "r").is_some_and(|r| refs.contains(r));
+                if hit && attr_value(head, "cm").is_none() {
+                    let insert_at = at + "<c".len();
+                    out.push_str(&xml[pos..insert_at]);
+                    out.push_str(" cm=\"1\"");
+                    pos = insert_at;
+                }
+            }
+            out.push_str(&xml[pos..]);
+            out
+        })?;
+    }
+
+    if pkg.part(METADATA_PART)?.is_some() {
+        return Ok(());
+    }
+    pkg.put(METADATA_PART, DYNAMIC_ARRAY_METADATA.to_string());
+    pkg.patch(CONTENT_TYPES_PART, |xml| {
+        let over = concat!(
+            r#"<Override PartName="/xl/metadata.xml" "#,
+            r#"ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheetMetadata+xml"/>"#,
+        );
+        xml.replacen("</Types>", &format!("{over}</Types>"), 1)
+    })?;
+    pkg.patch(WORKBOOK_RELS_PART, |xml| {
+        let ids: HashSet<&str> = find_tags(xml, "Relationship")
+            .into_iter()
+            .filter_map(|(_, head)| attr_value(head, "Id"))
+            .collect();
+        let id = (1..)
+            .map(|n| format!("rId{n}"))
+            .find(|id| !ids.contains(id.as_str()))
+            .unwrap_or_default();
+        let rel = format!(
+            r#"<Relationship Id="{id}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/sheetMetadata" Target="metadata.xml"/>"#
+        );
+        xml.replacen("</Relationships>", &format!("{rel}</Relationships>"), 1)
+    })
+}
+
+// Rich text run alignment. umya keeps rich text runs but drops their
+// `<vertAlign>` (superscript/subscript), so it is read from and written to the
+// shared strings directly.
+
+/// `vertAlign` of each run of a string item, or `None` if no run is raised or
+/// lowered.
+fn run_alignments(item: &str) -> Option<Vec<String>> {
+    let aligns: Vec<String> = find_elements(item, "r")
+        .into_iter()
+        .map(|(_, run)| {
+            find_attr(run, "vertAlign", "val")
+                .unwrap_or("baseline")
+                .to_string()
+        })
+        .collect();
+    aligns.iter().any(|a| a != "baseline").then_some(aligns)
+}
+
+/// Shared-string cells of a worksheet part as 1-based `(row, col)` and string index.
+fn shared_string_cells(sheet_xml: &str) -> Vec<((u32, u32), usize)> {
+    find_elements(sheet_xml, "c")
+        .into_iter()
+        .filter_map(|(_, c)| {
+            let head = &c[..c.find('>')?];
+            if attr_value(head, "t") != Some("s") {
+                return None;
+            }
+            let (row0, col0) = a1_to_row_col(attr_value(head, "r")?).ok()?;
+            let idx = element_text(c, "v")?.trim().parse().ok()?;
+            Some(((row0 + 1, col0 + 1), idx))
+        })
+        .collect()
+}
+
+/// Run alignments of rich text cells with raised or lowered runs, by sheet
+/// name and 1-based `(row, col)`.
+pub(crate) fn read_run_alignments(
+    pkg: &mut Package,
+) -> io::Result<HashMap<String, HashMap<(u32, u32), Vec<String>>>> {
+    let mut out: HashMap<String, HashMap<(u32, u32), Vec<String>>> = HashMap::new();
+    let Some(shared) = pkg.part(SHARED_STRINGS_PART)? else {
+        return Ok(out);
+    };
+    let items: Vec<Option<Vec<String>>> = find_elements(&shared, "si")
+        .into_iter()
+        .map(|(_, si)| run_alignments(si))
+        .collect();
+    if items.iter().all(Option::is_none) {
+        return Ok(out);
+    }
+    for (name, part) in sheet_parts(pkg)? {
+        let Some(xml) = pkg.part(&part)? else {
+            continue;
+        };
+        for (cell, idx) in shared_string_cells(&xml) {
+            if let Some(Some(aligns)) = items.get(idx) {
+                out.entry(name.clone())
+                    .or_default()
+                    .insert(cell, aligns.clone());
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Add `<vertAlign>` to the runs of the given rich text cells of a saved package.
+pub(crate) fn write_run_alignments(
+    pkg: &mut Package,
+    cells: &HashMap<String, HashMap<(u32, u32), Vec<String>>>,
+) -> io::Result<()> {
+    let mut by_item: HashMap<usize, &[String]> = HashMap::new();
+    for (name, part) in sheet_parts(pkg)? {
+        let Some(aligned) = cells.get(&name).filter(|m| !m.is_empty()) else {
+            continue;
+        };
+        let Some(xml) = pkg.part(&part)? else {
+            continue;
+        };
+        for (cell, idx) in shared_string_cells(&xml) {
+            if let Some(aligns) = aligned.get(&cell) {
+                by_item.insert(idx, aligns);
+            }
+        }
+    }
+    if by_item.is_empty() {
+        return Ok(());
+    }
+
+    pkg.patch(SHARED_STRINGS_PART, |xml| {
+        let mut out = String::with_capacity(xml.len() + by_item.len() * 32);
+        let mut pos = 0;
+        for (i, (at, si)) in find_elements(xml, "si").into_iter().enumerate() {
+            let Some(aligns) = by_item.get(&i) else {
+                continue;
+            };
+            out.push_str(&xml[pos..at]);
+            out.push_str(&align_runs(si, aligns));
+            pos = at + si.len();
+        }
+        out.push_str(&xml[pos..]);
+        out
+    })
+}
+
+/// Insert `<vertAlign>` into the run properties of each raised or lowered run.
+fn align_runs(item: &str, aligns: &[String]) -> String {
+    let mut out = String::with_capacity(item.len() + aligns.len() * 32);
+    let mut pos = 0;
+    for ((at, run), align) in find_elements(item, "r").into_iter().zip(aligns) {
+        if align == "baseline" || find_tag(run, "vertAlign").is_some() {
+            continue;
+        }
+        let tag = format!(r#"<vertAlign val="{align}"/>"#);
+        let patched = if let Some(end) = run.find("</rPr>") {
+            format!("{}{tag}{}", &run[..end], &run[end..])
+        } else if let Some(empty) = run.find("<rPr/>") {
+            format!(
+                "{}<rPr>{tag}</rPr>{}",
+                &run[..empty],
+                &run[empty + "<rPr/>".len()..]
+            )
+        } else {
+            let body = run.find('>').map_or(run.len(), |i| i + 1);
+            format!("{}<rPr>{tag}</rPr>{}", &run[..body], &run[body..])
+        };
+        out.push_str(&item[pos..at]);
+        out.push_str(&patched);
+        pos = at + run.len();
+    }
+    out.push_str(&item[pos..]);
+    out
+}
+
+// Path gradients. umya models `<gradientFill>` as linear only (degree and
+// stops), so path gradients are matched to umya's fills by their stops.
+
+/// Identity of a color for matching style parts against umya's model.
+pub(crate) fn color_key(argb: &str, theme: u32, tint: f64, indexed: u32) -> String {
+    if indexed != 0 {
+        format!("indexed:{indexed}")
+    } else if theme != 0 || tint != 0.0 {
+        format!("theme:{theme}:{tint}")
+    } else {
+        argb.to_ascii_uppercase()
+    }
+}
+
+/// Identity of a gradient by its `(position, color key)` stops.
+pub(crate) fn gradient_key(stops: &[(f64, String)]) -> String {
+    stops
+        .iter()
+        .map(|(position, color)| format!("{position}@{color}"))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// Stop signature of a `<gradientFill>` element, as [`gradient_key`].
+fn xml_gradient_key(element: &str) -> String {
+    let stops: Vec<(f64, String)> = find_elements(element, "stop")
+        .into_iter()
+        .map(|(_, stop)| {
+            let head = &stop[..stop.find('>').unwrap_or(stop.len())];
+            let position = attr_value(head, "position")
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(0.0);
+            let color = match find_tags(stop, "color").first() {
+                Some((_, c)) => {
+                    let number = |attr| attr_value(c, attr).and_then(|v| v.parse().ok());
+                    color_key(
+                        attr_value(c, "rgb").unwrap_or(""),
+                        number("theme").unwrap_or(0),
+                        attr_value(c, "tint")
+                            .and_then(|v| v.parse().ok())
+                            .unwrap_or(0.0),
+                        number("indexed").unwrap_or(0),
+                    )
+                }
+                None => String::new(),
+            };
+            (position, color)
+        })
+        .collect();
+    gradient_key(&stops)
+}
+
+/// Geometry (`left`, `right`, `top`, `bottom`) of each path gradient in the
+/// styles part, by stop signature.
+pub(crate) fn read_gradient_paths(pkg: &mut Package) -> io::Result<HashMap<String, [f64; 4]>> {
+    let mut out = HashMap::new();
+    let Some(xml) = pkg.part(STYLES_PART)? else {
+        return Ok(out);
+    };
+    for (_, element) in find_elements(&xml, "gradientFill") {
+        let head = &element[..element.find('>').unwrap_or(element.len())];
+        if attr_value(head, "type") != Some("path") {
+            continue;
+        }
+        let side = |attr| {
+            attr_value(head, attr)
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0.0)
+        };
+        out.insert(
+            xml_gradient_key(element),
+            [side("left"), side("right"), side("top"), side("bottom")],
+        );
+    }
+    Ok(out)
+}
+
+/// Turn the gradient fills of a saved package whose stops match `paths` into
+/// path gradients.
+pub(crate) fn write_gradient_paths(
+    pkg: &mut Package,
+    paths: &HashMap<String, [f64; 4]>,
+) -> io::Result<()> {
+    pkg.patch(STYLES_PART, |xml| {
+        let mut out = String::with_capacity(xml.len());
+        let mut pos = 0;
+        for (at, element) in find_elements(xml, "gradientFill") {
+            let Some([left, right, top, bottom]) = paths.get(&xml_gradient_key(element)) else {
+                continue;
+            };
+            let Some(head_len) = element.find('>') else {
+                continue;
+            };
+            // Keep a self-closing head self-closing.
+            let close = if element[..head_len].ends_with('/') {
+                "/"
+            } else {
+                ""
+            };
+            out.push_str(&xml[pos..at]);
+            out.push_str(&format!(
+                r#"<gradientFill type="path" left="{left}" right="{right}" top="{top}" bottom="{bottom}"{close}"#
+            ));
+            pos = at + head_len;
+        }
+        out.push_str(&xml[pos..]);
+        out
+    })
+}
+
+// Named styles. umya writes only the "Normal" cell style, so the named styles
+// (`<cellStyles>` and their `<cellStyleXfs>` records) are read from and
+// written to the styles part directly, along with the `xfId` that ties each
+// cell format to its named style.
+
+/// Number format ids below this are built in and need no `<numFmt>` entry.
+const FIRST_CUSTOM_NUM_FMT: u32 = 164;
+
+/// A named style as stored in the styles part. Font, fill, border and
+/// alignment are kept as their XML elements.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct StyleRecord {
+    pub name: String,
+    pub builtin_id: Option<u32>,
+    /// Number format id and, for custom formats, its code.
+    pub num_fmt: Option<(u32, String)>,
+    pub font: Option<String>,
+    pub fill: Option<String>,
+    pub border: Option<String>,
+    pub alignment: Option<String>,
+}
+
+/// Full text of the first `<tag>` element, or `""`.
+fn section<'a>(xml: &'a str, tag: &str) -> &'a str {
+    find_elements(xml, tag)
+        .into_iter()
+        .next()
+        .map_or("", |(_, element)| element)
+}
+
+/// Full text of each `<child>` element of the first `<tag>` element.
+fn children<'a>(xml: &'a str, tag: &str, child: &str) -> Vec<&'a str> {
+    find_elements(section(xml, tag), child)
+        .into_iter()
+        .map(|(_, element)| element)
+        .collect()
+}
+
+/// Head of a full element (`<tag a="1"`), without the closing `>`.
+fn head(element: &str) -> &str {
+    &element[..element.find('>').unwrap_or(element.len())]
+}
+
+/// `head` with `attr` set to `value`, added after the tag name if missing.
+fn set_attr(head: &str, attr: &str, value: &str) -> String {
+    let needle = format!(" {attr}=\"");
+    if let Some(at) = head.find(&needle) {
+        let start = at + needle.len();
+        let len = head[start..].find('"').unwrap_or(0);
+        return format!("{}{value}{}", &head[..start], &head[start + len..]);
+    }
+    let name_end = head
+        .find(|c: char| c.is_whitespace() || c == '/')
+        .unwrap_or(head.len());
+    format!(
+        "{} {attr}=\"{value}\"{}",
+        &head[..name_end],
+        &head[name_end..]
+    )
+}
+
+/// Number format ids and codes of the custom formats in a styles part.
+fn custom_num_fmts(xml: &str) -> HashMap<u32, String> {
+    find_tags(section(xml, "numFmts"), "numFmt")
+        .into_iter()
+        .filter_map(|(_, h)| {
+            let id = attr_value(h, "numFmtId")?.parse().ok()?;
+            Some((id, unescape_xml(attr_value(h, "formatCode")?)))
+        })
+        .collect()
+}
+
+/// Parsed `attr` of a full element's head.
+fn index_attr(element: &str, attr: &str) -> Option<usize> {
+    attr_value(head(element), attr)?.parse().ok()
+}
+
+/// Named styles of the package, in `cellStyles` order.
+pub(crate) fn read_named_styles(pkg: &mut Package) -> io::Result<Vec<StyleRecord>> {
+    let Some(xml) = pkg.part(STYLES_PART)? else {
+        return Ok(Vec::new());
+    };
+    let num_fmts = custom_num_fmts(&xml);
+    let fonts = children(&xml, "fonts", "font");
+    let fills = children(&xml, "fills", "fill");
+    let borders = children(&xml, "borders", "border");
+    let style_xfs = children(&xml, "cellStyleXfs", "xf");
+
+    let mut records = Vec::new();
+    for (_, h) in find_tags(section(&xml, "cellStyles"), "cellStyle") {
+        let Some(name) = attr_value(h, "name").map(unescape_xml) else {
+            continue;
+        };
+        let xf = attr_value(h, "xfId")
+            .and_then(|v| v.parse::<usize>().ok())
+            .and_then(|i| style_xfs.get(i).copied())
+            .unwrap_or("");
+        let part = |attr: &str, list: &[&str]| {
+            index_attr(xf, attr)
+                .and_then(|i| list.get(i))
+                .map(|e| e.to_string())
+        };
+        let num_fmt = index_attr(xf, "numFmtId")
+            .map(|id| id as u32)
+            .filter(|&id| id != 0)
+            .map(|id| (id, num_fmts.get(&id).cloned().unwrap_or_default()));
+        records.push(StyleRecord {
+            name,
+            builtin_id: attr_value(h, "builtinId").and_then(|v| v.parse().ok()),
+            num_fmt,
+            font: part("fontId", &fonts),
+            fill: part("fillId", &fills),
+            border: part("borderId", &borders),
+            alignment: find_elements(xf, "alignment")
+                .first()
+                .map(|(_, e)| e.to_string()),
+        });
+    }
+    Ok(records)
+}
+
+/// Cells whose cell format (`s`) has a value in `by_xf`, by sheet name and
+/// 1-based `(row, col)`.
+fn cells_by_xf<T: Clone>(
+    pkg: &mut Package,
+    by_xf: &[Option<T>],
+) -> io::Result<HashMap<String, HashMap<(u32, u32), T>>> {
+    let mut out: HashMap<String, HashMap<(u32, u32), T>> = HashMap::new();
+    if by_xf.iter().all(Option::is_none) {
+        return Ok(out);
+    }
+    for (name, part) in sheet_parts(pkg)? {
+        let Some(sheet) = pkg.part(&part)? else {
+            continue;
+        };
+        for (_, h) in find_tags(&sheet, "c") {
+            let value = attr_value(h, "s")
+                .and_then(|s| s.parse::<usize>().ok())
+                .and_then(|s| by_xf.get(s))
+                .and_then(Option::as_ref);
+            let (Some(value), Some(Ok((row0, col0)))) =
+                (value, attr_value(h, "r").map(a1_to_row_col))
+            else {
+                continue;
+            };
+            out.entry(name.clone())
+                .or_default()
+                .insert((row0 + 1, col0 + 1), value.clone());
+        }
+    }
+    Ok(out)
+}
+
+/// Point each listed cell at a copy of its cell format made by `variant`,
+/// one copy per distinct format and value. Returns the copies, which belong
+/// at the end of `cellXfs`.
+fn split_cell_xfs<T: Clone + Eq + Hash>(
+    pkg: &mut Package,
+    cell_xfs: &[&str],
+    cells: &HashMap<String, HashMap<(u32, u32), T>>,
+    mut variant: impl FnMut(&str, &T) -> String,
+) -> io::Result<Vec<String>> {
+    let mut variants: HashMap<(usize, T), usize> = HashMap::new();
+    let mut added: Vec<String> = Vec::new();
+    for (name, part) in sheet_parts(pkg)? {
+        let Some(listed) = cells.get(&name).filter(|m| !m.is_empty()) else {
+            continue;
+        };
+        pkg.patch(&part, |xml| {
+            let mut out = String::with_capacity(xml.len());
+            let mut pos = 0;
+            for (at, h) in find_tags(xml, "c") {
+                let Some(Ok((row0, col0))) = attr_value(h, "r").map(a1_to_row_col) else {
+                    continue;
+                };
+                let Some(value) = listed.get(&(row0 + 1, col0 + 1)) else {
+                    continue;
+                };
+                let s: usize = attr_value(h, "s").and_then(|s| s.parse().ok()).unwrap_or(0);
+                let Some(base) = cell_xfs.get(s) else {
+                    continue;
+                };
+                let index = *variants.entry((s, value.clone())).or_insert_with(|| {
+                    added.push(variant(base, value));
+                    cell_xfs.len() + added.len() - 1
+                });
+                out.push_str(&xml[pos..at]);
+                out.push_str(&set_attr(h, "s", &index.to_string()));
+                pos = at + h.len();
+            }
+            out.push_str(&xml[pos..]);
+            out
+        })?;
+    }
+    Ok(added)
+}
+
+/// Cells using a named style other than the default, by sheet name and
+/// 1-based `(row, col)`.
+pub(crate) fn read_cell_styles(
+    pkg: &mut Package,
+) -> io::Result<HashMap<String, HashMap<(u32, u32), String>>> {
+    let Some(xml) = pkg.part(STYLES_PART)? else {
+        return Ok(HashMap::new());
+    };
+    let names: HashMap<usize, String> = find_tags(section(&xml, "cellStyles"), "cellStyle")
+        .into_iter()
+        .filter_map(|(_, h)| {
+            let xf_id = attr_value(h, "xfId")?.parse().ok()?;
+            Some((xf_id, unescape_xml(attr_value(h, "name")?)))
+        })
+        .collect();
+    let cell_xf_styles: Vec<Option<String>> = children(&xml, "cellXfs", "xf")
+        .into_iter()
+        .map(|xf| {
+            index_attr(xf, "xfId")
+                .filter(|&id| id != 0)
+                .and_then(|id| names.get(&id))
+                .cloned()
+        })
+        .collect();
+    cells_by_xf(pkg, &cell_xf_styles)
+}
+
+/// Replace the named styles of a saved package with `records` (in `xfId`
+/// order) and link the given cells to them. `cells` maps each sheet's 1-based
+/// `(row, col)` to an index into `records`.
+pub(crate) fn write_named_styles(
+    pkg: &mut Package,
+    records: &[StyleRecord],
+    cells: &LinkedCells,
+) -> io::Result<()> {
+    let Some(styles) = pkg.part(STYLES_PART)? else {
+        return Ok(());
+    };
+    let cell_xfs = children(&styles, "cellXfs", "xf");
+
+    // A cell format used both with and without a named style (or with two)
+    // is split into one copy per style.
+    let added = split_cell_xfs(pkg, &cell_xfs, cells, |base, xf_id| {
+        format!(
+            "{}{}",
+            set_attr(head(base), "xfId", &xf_id.to_string()),
+            &base[head(base).len()..]
+        )
+    })?;
+
+    pkg.patch(STYLES_PART, |xml| {
+        let mut xml = xml.to_string();
+        let mut xfs = String::new();
+        for record in records {
+            let num_fmt_id = match &record.num_fmt {
+                Some((id, code)) if *id >= FIRST_CUSTOM_NUM_FMT || !code.is_empty() => {
+                    let (patched, id) = ensure_num_fmt(&xml, code);
+                    xml = patched;
+                    id
+                }
+                Some((id, _)) => *id,
+                None => 0,
+            };
+            let mut part_id = |tag: &str, child: &str, element: &Option<String>| match element {
+                Some(e) => {
+                    let (patched, id) = append_child(&xml, tag, child, e);
+                    xml = patched;
+                    id
+                }
+                None => 0,
+            };
+            let font_id = part_id("fonts", "font", &record.font);
+            let fill_id = part_id("fills", "fill", &record.fill);
+            let border_id = part_id("borders", "border", &record.border);
+
+            xfs.push_str(&format!(
+                r#"<xf numFmtId="{num_fmt_id}" fontId="{font_id}" fillId="{fill_id}" borderId="{border_id}""#
+            ));
+            for (flag, on) in [
+                ("applyNumberFormat", record.num_fmt.is_some()),
+                ("applyFont", record.font.is_some()),
+                ("applyFill", record.fill.is_some()),
+                ("applyBorder", record.border.is_some()),
+                ("applyAlignment", record.alignment.is_some()),
+            ] {
+                if on {
+                    xfs.push_str(&format!(r#" {flag}="1""#));
+                }
+            }
+            match &record.alignment {
+                Some(alignment) => xfs.push_str(&format!(">{alignment}</xf>")),
+                None => xfs.push_str("/>"),
+            }
+        }
+        xml = put_section(
+            &xml,
+            "cellStyleXfs",
+            &format!(
+                r#"<cellStyleXfs count="{}">{xfs}</cellStyleXfs>"#,
+                records.len()
+            ),
+            &["cellXfs"],
+        );
+        for element in &added {
+            xml = append_child(&xml, "cellXfs", "xf", element).0;
+        }
+
+        let entries: String = records
+            .iter()
+            .enumerate()
+            .map(|(xf_id, record)| {
+                let builtin = record
+                    .builtin_id
+                    .map_or(String::new(), |id| format!(r#" builtinId="{id}""#));
+                format!(
+                    r#"<cellStyle name="{}" xfId="{xf_id}"{builtin}/>"#,
+                    escape_xml(&record.name)
+                )
+            })
+            .collect();
+        put_section(
+            &xml,
+            "cellStyles",
+            &format!(
+                r#"<cellStyles count="{}">{entries}</cellStyles>"#,
+                records.len()
+            ),
+            &["dxfs", "tableStyles", "colors", "extLst", "/styleSheet"],
+        )
+    })
+}
+
+/// Replace the first `<tag>` element with `element`, or insert it before the
+/// first of `before` found.
+fn put_section(xml: &str, tag: &str, element: &str, before: &[&str]) -> String {
+    if let Some((at, old)) = find_elements(xml, tag).into_iter().next() {
+        return format!("{}{element}{}", &xml[..at], &xml[at + old.len()..]);
+    }
+    let anchor = before.iter().find_map(|b| match b.strip_prefix('/') {
+        Some(close) => xml.find(&format!("</{close}>")),
+        None => find_tag(xml, b),
+    });
+    match anchor {
+        Some(at) => format!("{}{element}{}", &xml[..at], &xml[at..]),
+        None => xml.to_string(),
+    }
+}
+
+/// Append `element` to the first `<tag>` list, updating its `count`. Returns
+/// the patched XML and the new element's index.
+fn append_child(xml: &str, tag: &str, child: &str, element: &str) -> (String, usize) {
+    let Some((at, list)) = find_elements(xml, tag).into_iter().next() else {
+        return (xml.to_string(), 0);
+    };
+    let index = find_elements(list, child).len();
+    let count = (index + 1).to_string();
+    let list_head = head(list);
+    let patched = match list_head.strip_suffix('/') {
+        Some(open) => format!(
+            "{}>{element}</{tag}>",
+            set_attr(open.trim_end(), "count", &count)
+        ),
+        None => {
+            let close = format!("</{tag}>");
+            let body = &list[list_head.len()..list.len() - close.len()];
+            format!(
+                "{}{body}{element}{close}",
+                set_attr(list_head, "count", &count)
+            )
+        }
+    };
+    (
+        format!("{}{patched}{}", &xml[..at], &xml[at + list.len()..]),
+        index,
+    )
+}
+
+/// Id of the custom number format with `code`, adding it if missing.
+fn ensure_num_fmt(xml: &str, code: &str) -> (String, u32) {
+    let existing = custom_num_fmts(xml);
+    if let Some((&id, _)) = existing.iter().find(|(_, c)| c.as_str() == code) {
+        return (xml.to_string(), id);
+    }
+    let next = existing
+        .keys()
+        .map(|&id| id + 1)
+        .max()
+        .unwrap_or(0)
+        .max(FIRST_CUSTOM_NUM_FMT);
+    let element = format!(
+        r#"<numFmt numFmtId="{next}" formatCode="{}"/>"#,
+        escape_xml(code)
+    );
+    let xml = if find_tag(xml, "numFmts").is_some() {
+        append_child(xml, "numFmts", "numFmt", &element).0
+    } else {
+        put_section(
+            xml,
+            "numFmts",
+            &format!(r#"<numFmts count="1">{element}</numFmts>"#),
+            &["fonts"],
+        )
+    };
+    (xml, next)
+}
+
+// Cell format extras. umya does not model `vertAlign`, `outline`, `shadow`,
+// `condense` or `extend` on fonts, `shrinkToFit`, `justifyLastLine`,
+// `relativeIndent` or `readingOrder` on alignments, nor `outline` on
+// borders, so cells using them get their own copy of their cell format (and
+// font or border), patched in on save.
+
+/// Font extras of a `<font>` element.
+pub(crate) fn font_extras(font: &str) -> FontExtras {
+    FontExtras {
+        vert_align: find_attr(font, "vertAlign", "val")
+            .filter(|v| *v != "baseline")
+            .map(str::to_string),
+        outline: flag(font, "outline"),
+        shadow: flag(font, "shadow"),
+        condense: flag(font, "condense"),
+        extend: flag(font, "extend"),
+    }
+}
+
+/// `font` (a full `<font>` element without extras) with `extras` added.
+pub(crate) fn font_with_extras(font: &str, extras: &FontExtras) -> String {
+    let mut tags = String::new();
+    if let Some(align) = &extras.vert_align {
+        tags.push_str(&format!(r#"<vertAlign val="{align}"/>"#));
+    }
+    for (tag, on) in [
+        ("outline", extras.outline),
+        ("shadow", extras.shadow),
+        ("condense", extras.condense),
+        ("extend", extras.extend),
+    ] {
+        if on {
+            tags.push_str(&format!("<{tag}/>"));
+        }
+    }
+    match font.strip_suffix("</font>") {
+        Some(body) => format!("{body}{tags}</font>"),
+        None => format!("<font>{tags}</font>"),
+    }
+}
+
+/// Border extras of a `<border>` element.
+pub(crate) fn border_extras(border: &str) -> BorderExtras {
+    BorderExtras {
+        no_outline: matches!(
+            attr_value(head(border), "outline"),
+            Some("0") | Some("false")
+        ),
+    }
+}
+
+/// `border` (a full `<border>` element) with `extras` set.
+pub(crate) fn border_with_extras(border: &str, extras: &BorderExtras) -> String {
+    if !extras.no_outline {
+        return border.to_string();
+    }
+    format!(
+        "{}{}",
+        set_attr(head(border), "outline", "0"),
+        &border[head(border).len()..]
+    )
+}
+
+/// Alignment extras of an `<alignment>` element.
+pub(crate) fn alignment_extras(alignment: &str) -> AlignmentExtras {
+    let number = |attr| {
+        attr_value(head(alignment), attr)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0)
+    };
+    let on = |attr| matches!(attr_value(head(alignment), attr), Some("1") | Some("true"));
+    AlignmentExtras {
+        shrink_to_fit: on("shrinkToFit"),
+        justify_last_line: on("justifyLastLine"),
+        relative_indent: number("relativeIndent"),
+        reading_order: number("readingOrder") as u32,
+    }
+}
+
+/// `alignment` (an `<alignment>` element, if any) with `extras` set.
+pub(crate) fn alignment_with_extras(
+    alignment: Option<String>,
+    extras: &AlignmentExtras,
+) -> Option<String> {
+    if extras.is_empty() {
+        return alignment;
+    }
+    let element = alignment.unwrap_or_else(|| "<alignment/>".to_string());
+    let mut element_head = head(&element).to_string();
+    for (attr, value) in [
+        ("shrinkToFit", extras.shrink_to_fit.then(|| "1".to_string())),
+        (
+            "justifyLastLine",
+            extras.justify_last_line.then(|| "1".to_string()),
+        ),
+        (
+            "relativeIndent",
+            (extras.relative_indent != 0).then(|| extras.relative_indent.to_string()),
+        ),
+        (
+            "readingOrder",
+            (extras.reading_order != 0).then(|| extras.reading_order.to_string()),
+        ),
+    ] {
+        if let Some(value) = value {
+            element_head = set_attr(&element_head, attr, &value);
+        }
+    }
+    Some(format!(
+        "{element_head}{}",
+        &element[head(&element).len()..]
+    ))
+}
+
+/// `xf` with its `<alignment>` replaced by `alignment` (or added first).
+fn xf_with_alignment(xf: &str, alignment: &str) -> String {
+    let xf_head = set_attr(head(xf), "applyAlignment", "1");
+    if let Some(open) = xf_head.strip_suffix('/') {
+        return format!("{}>{alignment}</xf>", open.trim_end());
+    }
+    let body = &xf[head(xf).len() + 1..];
+    match find_elements(body, "alignment").into_iter().next() {
+        Some((at, old)) => format!(
+            "{xf_head}>{}{alignment}{}",
+            &body[..at],
+            &body[at + old.len()..]
+        ),
+        None => format!("{xf_head}>{alignment}{body}"),
+    }
+}
+
+/// Cells whose cell format has extras, by sheet name and 1-based `(row, col)`.
+pub(crate) fn read_cell_xf_extras(pkg: &mut Package) -> io::Result<CellXfExtras> {
+    let Some(xml) = pkg.part(STYLES_PART)? else {
+        return Ok(HashMap::new());
+    };
+    let fonts: Vec<FontExtras> = children(&xml, "fonts", "font")
+        .into_iter()
+        .map(font_extras)
+        .collect();
+    let borders: Vec<BorderExtras> = children(&xml, "borders", "border")
+        .into_iter()
+        .map(border_extras)
+        .collect();
+    let cell_xf_extras: Vec<Option<XfExtras>> = children(&xml, "cellXfs", "xf")
+        .into_iter()
+        .map(|xf| {
+            let extras = XfExtras {
+                font: index_attr(xf, "fontId")
+                    .and_then(|i| fonts.get(i))
+                    .cloned()
+                    .unwrap_or_default(),
+                alignment: find_elements(xf, "alignment")
+                    .first()
+                    .map(|(_, e)| alignment_extras(e))
+                    .unwrap_or_default(),
+                border: index_attr(xf, "borderId")
+                    .and_then(|i| borders.get(i))
+                    .cloned()
+                    .unwrap_or_default(),
+            };
+            (!extras.is_empty()).then_some(extras)
+        })
+        .collect();
+    cells_by_xf(pkg, &cell_xf_extras)
+}
+
+/// Index of `element` in a part list of `existing` entries followed by
+/// `added`, appending it to `added` if new.
+fn added_part(existing: usize, added: &mut Vec<String>, element: String) -> usize {
+    let at = match added.iter().position(|e| *e == element) {
+        Some(at) => at,
+        None => {
+            added.push(element);
+            added.len() - 1
+        }
+    };
+    existing + at
+}
+
+/// `xf` with `attr` pointing at part `id` and `apply` set.
+fn xf_with_part(xf: &str, attr: &str, id: usize, apply: &str) -> String {
+    let xf_head = set_attr(&set_attr(head(xf), attr, &id.to_string()), apply, "1");
+    format!("{xf_head}{}", &xf[head(xf).len()..])
+}
+
+/// Give the listed cells of a saved package cell formats with their extras.
+pub(crate) fn write_cell_xf_extras(pkg: &mut Package, cells: &CellXfExtras) -> io::Result<()> {
+    let Some(styles) = pkg.part(STYLES_PART)? else {
+        return Ok(());
+    };
+    let fonts = children(&styles, "fonts", "font");
+    let borders = children(&styles, "borders", "border");
+    let cell_xfs = children(&styles, "cellXfs", "xf");
+
+    let mut added_fonts: Vec<String> = Vec::new();
+    let mut added_borders: Vec<String> = Vec::new();
+    let added_xfs = split_cell_xfs(pkg, &cell_xfs, cells, |base, extras| {
+        let mut xf = base.to_string();
+        if !extras.font.is_empty() {
+            let font = index_attr(base, "fontId")
+                .and_then(|i| fonts.get(i).copied())
+                .unwrap_or("<font/>");
+            let id = added_part(
+                fonts.len(),
+                &mut added_fonts,
+                font_with_extras(font, &extras.font),
+            );
+            xf = xf_with_part(&xf, "fontId", id, "applyFont");
+        }
+        if !extras.border.is_empty() {
+            let border = index_attr(base, "borderId")
+                .and_then(|i| borders.get(i).copied())
+                .unwrap_or("<border/>");
+            let id = added_part(
+                borders.len(),
+                &mut added_borders,
+                border_with_extras(border, &extras.border),
+            );
+            xf = xf_with_part(&xf, "borderId", id, "applyBorder");
+        }
+        if !extras.alignment.is_empty() {
+            let current = find_elements(&xf, "alignment")
+                .first()
+                .map(|(_, e)| e.to_string());
+            if let Some(alignment) = alignment_with_extras(current, &extras.alignment) {
+                xf = xf_with_alignment(&xf, &alignment);
+            }
+        }
+        xf
+    })?;
+
+    pkg.patch(STYLES_PART, |xml| {
+        let mut xml = xml.to_string();
+        for font in &added_fonts {
+            xml = append_child(&xml, "fonts", "font", font).0;
+        }
+        for border in &added_borders {
+            xml = append_child(&xml, "borders", "border", border).0;
+        }
+        for xf in &added_xfs {
+            xml = append_child(&xml, "cellXfs", "xf", xf).0;
+        }
+        xml
+    })
+}
+
+// Outlines. umya drops the `outlineLevel` and `collapsed` attributes of rows
+// and columns and the sheet's `<outlinePr>`, so they are read from and written
+// to the worksheet parts directly. Rows and columns given a level are created
+// in umya, so their elements are there to patch.
+
+/// Whether an attribute is a false boolean (`"0"` or `"false"`).
+fn is_false(value: Option<&str>) -> bool {
+    matches!(value, Some("0") | Some("false"))
+}
+
+/// Whether an attribute is a true boolean (`"1"` or `"true"`).
+fn is_true(value: Option<&str>) -> bool {
+    matches!(value, Some("1") | Some("true"))
+}
+
+/// Outline grouping of each sheet that has any.
+pub(crate) fn read_outlines(pkg: &mut Package) -> io::Result<Outlines> {
+    let mut out = Outlines::new();
+    for (name, part) in sheet_parts(pkg)? {
+        let Some(xml) = pkg.part(&part)? else {
+            continue;
+        };
+        let mut outline = Outline::default();
+        for (_, head) in find_tags(&xml, "row") {
+            let Some(row) = attr_value(head, "r").and_then(|r| r.parse::<u32>().ok()) else {
+                continue;
+            };
+            let level = attr_value(head, "outlineLevel").and_then(|l| l.parse::<u8>().ok());
+            if let Some(level) = level.filter(|&l| l > 0) {
+                outline.rows.insert(row, level);
+            }
+            if is_true(attr_value(head, "collapsed")) {
+                outline.collapsed_rows.insert(row);
+            }
+        }
+        for (_, head) in find_tags(&xml, "col") {
+            let span = attr_value(head, "min")
+                .and_then(|v| v.parse::<u32>().ok())
+                .zip(attr_value(head, "max").and_then(|v| v.parse::<u32>().ok()));
+            let Some((min, max)) = span else {
+                continue;
+            };
+            let level = attr_value(head, "outlineLevel")
+                .and_then(|l| l.parse::<u8>().ok())
+                .filter(|&l| l > 0);
+            let collapsed = is_true(attr_value(head, "collapsed"));
+            for col in min..=max {
+                if let Some(level) = level {
+                    outline.cols.insert(col, level);
+                }
+                if collapsed {
+                    outline.collapsed_cols.insert(col);
+                }
+            }
+        }
+        if let Some((_, head)) = find_tags(&xml, "outlinePr").first() {
+            outline.summary_below = !is_false(attr_value(head, "summaryBelow"));
+            outline.summary_right = !is_false(attr_value(head, "summaryRight"));
+        }
+        if !outline.is_default() {
+            out.insert(name, outline);
+        }
+    }
+    Ok(out)
+}
+
+/// Write the outline grouping of each sheet into a saved package.
+pub(crate) fn write_outlines(pkg: &mut Package, outlines: &Outlines) -> io::Result<()> {
+    for (name, part) in sheet_parts(pkg)? {
+        let Some(outline) = outlines.get(&name).filter(|o| !o.is_default()) else {
+            continue;
+        };
+        pkg.patch(&part, |xml| outline_sheet(xml, outline))?;
+    }
+    Ok(())
+}
+
+/// `head` with the `outlineLevel` and `collapsed` of a row or column.
+fn outline_head(head: &str, level: Option<u8>, collapsed: bool) -> String {
+    let mut head = head.to_string();
+    if let Some(level) = level {
+        head = set_attr(&head, "outlineLevel", &level.to_string());
+    }
+    if collapsed {
+        head = set_attr(&head, "collapsed", "1");
+    }
+    head
+}
+
+/// A worksheet part with the row, column and sheet outline settings applied.
+fn outline_sheet(xml: &str, outline: &Outline) -> String {
+    let mut out = String::with_capacity(xml.len());
+    let mut pos = 0;
+    for (at, row_head) in find_tags(xml, "row") {
+        let Some(row) = attr_value(row_head, "r").and_then(|r| r.parse::<u32>().ok()) else {
+            continue;
+        };
+        let level = outline.rows.get(&row).copied();
+        let collapsed = outline.collapsed_rows.contains(&row);
+        if level.is_some() || collapsed {
+            out.push_str(&xml[pos..at]);
+            out.push_str(&outline_head(row_head, level, collapsed));
+            pos = at + row_head.len();
+        }
+    }
+    out.push_str(&xml[pos..]);
+    let xml = out;
+
+    // A `<col>` spanning several columns is split so each gets its own level.
+    let mut out = String::with_capacity(xml.len());
+    let mut pos = 0;
+    for (at, element) in find_elements(&xml, "col") {
+        let col_head = head(element);
+        let span = attr_value(col_head, "min")
+            .and_then(|v| v.parse::<u32>().ok())
+            .zip(attr_value(col_head, "max").and_then(|v| v.parse::<u32>().ok()));
+        let Some((min, max)) = span else {
+            continue;
+        };
+        let outlined = (min..=max)
+            .any(|c| outline.cols.contains_key(&c) || outline.collapsed_cols.contains(&c));
+        if !outlined {
+            continue;
+        }
+        out.push_str(&xml[pos..at]);
+        for col in min..=max {
+            let single = set_attr(
+                &set_attr(col_head, "min", &col.to_string()),
+                "max",
+                &col.to_string(),
+            );
+            let level = outline.cols.get(&col).copied();
+            let collapsed = outline.collapsed_cols.contains(&col);
+            out.push_str(&outline_head(&single, level, collapsed));
+            out.push_str(&element[col_head.len()..]);
+        }
+        pos = at + element.len();
+    }
+    out.push_str(&xml[pos..]);
+    let mut xml = out;
+
+    let row_level = outline.rows.values().copied().max().unwrap_or(0);
+    let col_level = outline.cols.values().copied().max().unwrap_or(0);
+    if row_level > 0 || col_level > 0 {
+        let existing = find_tags(&xml, "sheetFormatPr")
+            .first()
+            .map(|&(at, format_head)| (at, format_head.to_string()));
+        let mut format = existing.as_ref().map_or_else(
+            || r#"<sheetFormatPr defaultRowHeight="15"/"#.to_string(),
+            |(_, format_head)| format_head.clone(),
+        );
+        if row_level > 0 {
+            format = set_attr(&format, "outlineLevelRow", &row_level.to_string());
+        }
+        if col_level > 0 {
+            format = set_attr(&format, "outlineLevelCol", &col_level.to_string());
+        }
+        xml = match existing {
+            Some((at, old)) => format!("{}{format}{}", &xml[..at], &xml[at + old.len()..]),
+            None => put_section(
+                &xml,
+                "sheetFormatPr",
+                &format!("{format}>"),
+                &["cols", "sheetData"],
+            ),
+        };
+    }
+
+    if outline.summary_below && outline.summary_right {
+        return xml;
+    }
+    let outline_pr = format!(
+        r#"<outlinePr summaryBelow="{}" summaryRight="{}"/>"#,
+        u8::from(outline.summary_below),
+        u8::from(outline.summary_right)
+    );
+    match find_elements(&xml, "sheetPr").first() {
+        Some(&(at, sheet_pr)) => {
+            let sheet_pr_head = head(sheet_pr);
+            let patched = match sheet_pr_head.strip_suffix('/') {
+                Some(open) => format!("{}>{outline_pr}</sheetPr>", open.trim_end()),
+                None => put_section(
+                    sheet_pr,
+                    "outlinePr",
+                    &outline_pr,
+                    &["pageSetUpPr", "/sheetPr"],
+                ),
+            };
+            format!("{}{patched}{}", &xml[..at], &xml[at + sheet_pr.len()..])
+        }
+        None => put_section(
+            &xml,
+            "sheetPr",
+            &format!("<sheetPr>{outline_pr}</sheetPr>"),
+            &[
+                "dimension",
+                "sheetViews",
+                "sheetFormatPr",
+                "cols",
+                "sheetData",
+            ],
+        ),
+    }
+}
+
+// Sheet view flags. umya drops `showRowColHeaders`, `showZeros`,
+// `showFormulas` and `rightToLeft` on `<sheetView>`, so they are read from and
+// written to the first sheet view of each worksheet part directly.
+
+/// View flags of each sheet that changes any from its default.
+pub(crate) fn read_view_flags(pkg: &mut Package) -> io::Result<SheetViewFlags> {
+    let mut out = SheetViewFlags::new();
+    for (name, part) in sheet_parts(pkg)? {
+        let Some(xml) = pkg.part(&part)? else {
+            continue;
+        };
+        let Some(&(_, view_head)) = find_tags(&xml, "sheetView").first() else {
+            continue;
+        };
+        let flags = ViewFlags {
+            show_row_col_headers: !is_false(attr_value(view_head, "showRowColHeaders")),
+            show_zeros: !is_false(attr_value(view_head, "showZeros")),
+            show_formulas: is_true(attr_value(view_head, "showFormulas")),
+            right_to_left: is_true(attr_value(view_head, "rightToLeft")),
+        };
+        if !flags.is_default() {
+            out.insert(name, flags);
+        }
+    }
+    Ok(out)
+}
+
+/// Write the view flags of each sheet into a saved package.
+pub(crate) fn write_view_flags(pkg: &mut Package, flags: &SheetViewFlags) -> io::Result<()> {
+    for (name, part) in sheet_parts(pkg)? {
+        let Some(flags) = flags.get(&name).filter(|f| !f.is_default()) else {
+            continue;
+        };
+        pkg.patch(&part, |xml| view_flags_sheet(xml, flags))?;
+    }
+    Ok(())
+}
+
+/// A worksheet part with the view flags set on its first sheet view, which is
+/// added if it has none.
+fn view_flags_sheet(xml: &str, flags: &ViewFlags) -> String {
+    let existing = find_tags(xml, "sheetView")
+        .first()
+        .map(|&(at, view_head)| (at, view_head.to_string()));
+    let mut view = existing.as_ref().map_or_else(
+        || r#"<sheetView workbookViewId="0"/"#.to_string(),
+        |(_, view_head)| view_head.clone(),
+    );
+    for (attr, value, default) in [
+        ("showRowColHeaders", flags.show_row_col_headers, true),
+        ("showZeros", flags.show_zeros, true),
+        ("showFormulas", flags.show_formulas, false),
+        ("rightToLeft", flags.right_to_left, false),
+    ] {
+        if value != default {
+            view = set_attr(&view, attr, &u8::from(value).to_string());
+        }
+    }
+    match existing {
+        Some((at, old)) => format!("{}{view}{}", &xml[..at], &xml[at + old.len()..]),
+        None => put_section(
+            xml,
+            "sheetViews",
+            &format!("<sheetViews>{view}></sheetViews>"),
+            &["sheetFormatPr", "cols", "sheetData"],
+        ),
+    }
+}
@@ -0,0 +1,144 @@
+// Color palettes used to resolve theme and indexed color references.
+//
+// Styles may name a color by its slot in the workbook theme (optionally
+// lightened or darkened by a tint) or by its index in the legacy 64-color
+// palette instead of giving an RGB value. The palette holds both lookups, read
+// from the package when it has them and falling back to Excel's defaults.
+
+/// Default theme colors (Office 2007-2010 theme) in SpreadsheetML `theme`
+/// index order: lt1, dk1, lt2, dk2, accent1-6, hlink, folHlink.
+const DEFAULT_THEME: [&str; 12] = [
+    "FFFFFF", "000000", "EEECE1", "1F497D", "4F81BD", "C0504D", "9BBB59", "8064A2", "4BACC6",
+    "F79646", "0000FF", "800080",
+];
+
+/// Excel's default legacy palette (`indexedColors`).
+const DEFAULT_INDEXED: [&str; 64] = [
+    "000000", "FFFFFF", "FF0000", "00FF00", "0000FF", "FFFF00", "FF00FF", "00FFFF", // 0-7
+    "000000", "FFFFFF", "FF0000", "00FF00", "0000FF", "FFFF00", "FF00FF", "00FFFF", // 8-15
+    "800000", "008000", "000080", "808000", "800080", "008080", "C0C0C0", "808080", // 16-23
+    "9999FF", "993366", "FFFFCC", "CCFFFF", "660066", "FF8080", "0066CC", "CCCCFF", // 24-31
+    "000080", "FF00FF", "FFFF00", "00FFFF", "800080", "800000", "008080", "0000FF", // 32-39
+    "00CCFF", "CCFFFF", "CCFFCC", "FFFF99", "99CCFF", "FF99CC", "CC99FF", "FFCC99", // 40-47
+    "3366FF", "33CCCC", "99CC00", "FFCC00", "FF9900", "FF6600", "666699", "969696", // 48-55
+    "003366", "339966", "003300", "333300", "993300", "993366", "333399", "333333", // 56-63
+];
+
+/// Indexes 64 and 65 are the system foreground and background colors.
+const SYSTEM_FOREGROUND: u32 = 64;
+const SYSTEM_BACKGROUND: u32 = 65;
+
+#[derive(Clone, Debug)]
+pub(crate) struct Palette {
+    /// `RRGGBB` per theme index.
+    theme: Vec<String>,
+    /// `RRGGBB` per legacy palette index.
+    indexed: Vec<String>,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+impl Palette {
+    /// Build a palette from the colors found in a package, if any.
+    pub(crate) fn new(theme: Option<Vec<String>>, indexed: Option<Vec<String>>) -> Self {
+        let defaults = |colors: &[&str]| colors.iter().map(|c| c.to_string()).collect();
+        Self {
+            theme: theme.unwrap_or_else(|| defaults(&DEFAULT_THEME)),
+            indexed: indexed.unwrap_or_else(|| defaults(&DEFAULT_INDEXED)),
+        }
+    }
+
+    /// `RRGGBB` of a theme color with `tint` applied.
+    pub(crate) fn theme_color(&self, index: u32, tint: f64) -> Option<String> {
+        let base = self.theme.get(index as usize)?;
+        Some(apply_tint(base, tint))
+    }
+
+    /// `RRGGBB` of a legacy palette entry.
+    pub(crate) fn indexed_color(&self, index: u32) -> Option<String> {
+        match index {
+            SYSTEM_FOREGROUND => Some("000000".to_string()),
+            SYSTEM_BACKGROUND => Some("FFFFFF".to_string()),
+            i => self.indexed.get(i as usize).cloned(),
+        }
+    }
+}
+
+/// Lighten (`tint > 0`) or darken (`tint < 0`) an `RRGGBB` color the way
+/// Excel does: by scaling its HLS luminance.
+pub(crate) fn apply_tint(rgb: &str, tint: f64) -> String {
+    let channel = |i: usize| {
+        rgb.get(i..i + 2)
+            .and_then(|h| u8::from_str_radix(h, 16).ok())
+            .map_or(0.0, |v| f64::from(v) / 255.0)
+    };
+    if tint == 0.0 || rgb.len() != 6 {
+        return rgb.to_ascii_uppercase();
+    }
+    let (h, l, s) = rgb_to_hls(channel(0), channel(2), channel(4));
+    let l = if tint < 0.0 {
+        l * (1.0 + tint)
+    } else {
+        l * (1.0 - tint) + tint
+    };
+    let (r, g, b) = hls_to_rgb(h, l.clamp(0.0, 1.0), s);
+    let byte = |v: f64| (v * 255.0).round().clamp(0.0, 255.0) as u8;
+    format!("{:02X}{:02X}{:02X}", byte(r), byte(g), byte(b))
+}
+
+fn rgb_to_hls(r: f64, g: f64, b: f64) -> (f64, f64, f64) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    if max == min {
+        return (0.0, l, 0.0);
+    }
+    let d = max - min;
+    let s = if l > 0.5 {
+        d / (2.0 - max - min)
+    } else {
+        d / (max + min)
+    };
+    let h = if max == r {
+        (g - b) / d + if g < b { 6.0 } else { 0.0 }
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+    (h / 6.0, l, s)
+}
+
+fn hls_to_rgb(h: f64, l: f64, s: f64) -> (f64, f64, f64) {
+    if s == 0.0 {
+        return (l, l, l);
+    }
+    let q = if l < 0.5 {
+        l * (1.0 + s)
+    } else {
+        l + s - l * s
+    };
+    let p = 2.0 * l - q;
+    let hue = |mut t: f64| {
+        if t < 0.0 {
+            t += 1.0;
+        }
+        if t > 1.0 {
+            t -= 1.0;
+        }
+        if t < 1.0 / 6.0 {
+            p + (q - p) * 6.0 * t
+        } else if t < 0.5 {
+            q
+        } else if t < 2.0 / 3.0 {
+            p + (q - p) * (2.0 / 3.0 - t) * 6.0
+        } else {
+            p
+        }
+    };
+    (hue(h + 1.0 / 3.0), hue(h), hue(h - 1.0 / 3.0))
+}
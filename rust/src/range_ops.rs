@@ -0,0 +1,396 @@
+// Moving and copying blocks of cells. A move is Excel's cut and paste:
+// formulas keep pointing at the cells they referred to, and references into
+// the block follow it wherever they are in the workbook. A copy translates
+// the relative references of the copied formulas by the distance copied.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use umya_spreadsheet::structs::{Cell, CellFormula, CellFormulaValues, Comment, Style};
+use umya_spreadsheet::{Spreadsheet, Worksheet};
+
+use crate::cell_ops::{set_formula_obj, CellExtras, SheetFormulas};
+use crate::format_ops::{StyleExtras, XfExtras};
+use crate::formula_parser::{move_formula, translate_formula, MAX_COL, MAX_ROW};
+use crate::structural_ops::SavedFormulas;
+use crate::utils::{a1_range_to_bounds, u32_to_col_letter};
+
+/// What `copy_range` pastes.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Paste {
+    Values,
+    Formats,
+    All,
+}
+
+impl Paste {
+    fn parse(what: &str) -> PyResult<Self> {
+        match what {
+            "values" => Ok(Self::Values),
+            "formats" => Ok(Self::Formats),
+            "all" => Ok(Self::All),
+            other => Err(PyErr::new::<PyValueError, _>(format!(
+                "Invalid what: {other} (expected 'values', 'formats' or 'all')"
+            ))),
+        }
+    }
+
+    fn values(self) -> bool {
+        self != Self::Formats
+    }
+
+    fn formats(self) -> bool {
+        self != Self::Values
+    }
+}
+
+/// One cell of a block, recorded before anything is written.
+struct CellCopy {
+    /// `(rows, cols)` from the block's top-left cell.
+    offset: (u32, u32),
+    cell: Option<Cell>,
+    /// Shared formulas are recorded as the plain formula they expand to, so
+    /// the copy does not depend on the rest of their group.
+    formula: Option<CellFormula>,
+    xf_extras: Option<XfExtras>,
+    named_style: Option<String>,
+    dynamic: bool,
+    run_alignments: Option<Vec<String>>,
+}
+
+/// A block of cells with the merged ranges inside it and its comments.
+struct Block {
+    /// Rows and columns spanned.
+    size: (u32, u32),
+    cells: Vec<CellCopy>,
+    /// Merged ranges as `(row, col)` offsets of their corners.
+    merges: Vec<((u32, u32), (u32, u32))>,
+    comments: Vec<((u32, u32), Comment)>,
+}
+
+/// 1-based `(min_row, min_col, max_row, max_col)` of a range.
+fn range_bounds(range: &str) -> PyResult<(u32, u32, u32, u32)> {
+    let (r1, c1, r2, c2) =
+        a1_range_to_bounds(range).map_err(|msg| PyErr::new::<PyValueError, _>(msg))?;
+    Ok((r1 + 1, c1 + 1, r2 + 1, c2 + 1))
+}
+
+/// Top-left cell of a destination given as a cell or a range, checking that a
+/// block of `size` fits on the sheet from there.
+fn destination(dest: &str, (rows, cols): (u32, u32)) -> PyResult<(u32, u32)> {
+    let (row, col, _, _) = range_bounds(dest)?;
+    if row + rows - 1 > MAX_ROW || col + cols - 1 > MAX_COL {
+        return Err(PyErr::new::<PyValueError, _>(format!(
+            "Range does not fit on the sheet at {dest}"
+        )));
+    }
+    Ok((row, col))
+}
+
+/// Whether 1-based `(row, col)` lies in a `(min_row, min_col, max_row, max_col)`
+/// area.
+fn within((r1, c1, r2, c2): (u32, u32, u32, u32), (row, col): (u32, u32)) -> bool {
+    (r1..=r2).contains(&row) && (c1..=c2).contains(&col)
+}
+
+fn cell_name(row: u32, col: u32) -> String {
+    format!("{}{row}", u32_to_col_letter(col))
+}
+
+fn read_block(
+    ws: &Worksheet,
+    extras: &CellExtras,
+    styles: &StyleExtras,
+    sheet: &str,
+    (r1, c1, r2, c2): (u32, u32, u32, u32),
+) -> Block {
+    let dynamic = extras.dynamic_arrays.get(sheet);
+    let formulas = SheetFormulas::new(ws, dynamic);
+    let mut cells = Vec::new();
+    for row in r1..=r2 {
+        for col in c1..=c2 {
+            let cell = ws.get_cell((col, row));
+            let formula = cell.and_then(|cell| {
+                let f = cell.get_formula_obj()?;
+                if matches!(f.get_formula_type(), CellFormulaValues::Shared) {
+                    let mut plain = CellFormula::default();
+                    plain.set_text(formulas.formula_text(cell)?);
+                    Some(plain)
+                } else {
+                    Some(f.clone())
+                }
+            });
+            cells.push(CellCopy {
+                offset: (row - r1, col - c1),
+                cell: cell.cloned(),
+                formula,
+                xf_extras: styles.cell_extras(sheet, (row, col)).cloned(),
+                named_style: styles
+                    .named
+                    .cell_style(sheet, (row, col))
+                    .map(str::to_string),
+                dynamic: dynamic.is_some_and(|cells| cells.contains(&(row, col))),
+                run_alignments: extras
+                    .run_alignments
+                    .get(sheet)
+                    .and_then(|cells| cells.get(&(row, col)))
+                    .cloned(),
+            });
+        }
+    }
+
+    let inside = |row: u32, col: u32| (r1..=r2).contains(&row) && (c1..=c2).contains(&col);
+    let merges = ws
+        .get_merge_cells()
+        .iter()
+        .filter_map(|range| range_bounds(&range.get_range()).ok())
+        .filter(|&(a1, b1, a2, b2)| inside(a1, b1) && inside(a2, b2))
+        .map(|(a1, b1, a2, b2)| ((a1 - r1, b1 - c1), (a2 - r1, b2 - c1)))
+        .collect();
+    let comments = ws
+        .get_comments()
+        .iter()
+        .filter_map(|comment| {
+            let coord = comment.get_coordinate();
+            let (row, col) = (*coord.get_row_num(), *coord.get_col_num());
+            inside(row, col).then(|| ((row - r1, col - c1), comment.clone()))
+        })
+        .collect();
+
+    Block {
+        size: (r2 - r1 + 1, c2 - c1 + 1),
+        cells,
+        merges,
+        comments,
+    }
+}
+
+/// Remove a block's cells, their extras, comments and the merged ranges
+/// inside it.
+fn clear_block(
+    ws: &mut Worksheet,
+    extras: &mut CellExtras,
+    styles: &mut StyleExtras,
+    sheet: &str,
+    (r1, c1, r2, c2): (u32, u32, u32, u32),
+) {
+    let inside = |row: u32, col: u32| (r1..=r2).contains(&row) && (c1..=c2).contains(&col);
+    for row in r1..=r2 {
+        for col in c1..=c2 {
+            ws.remove_cell((col, row));
+            extras.clear(sheet, (row, col));
+            styles.set_cell_extras(sheet, (row, col), XfExtras::default());
+            styles.named.set_cell_style(sheet, (row, col), None);
+        }
+    }
+    ws.get_comments_mut().retain(|comment| {
+        let coord = comment.get_coordinate();
+        !inside(*coord.get_row_num(), *coord.get_col_num())
+    });
+    ws.get_merge_cells_mut().retain(|range| {
+        !range_bounds(&range.get_range())
+            .is_ok_and(|(a1, b1, a2, b2)| inside(a1, b1) && inside(a2, b2))
+    });
+}
+
+/// Write a block with its top-left cell at `(row, col)`.
+fn paste_block(
+    ws: &mut Worksheet,
+    extras: &mut CellExtras,
+    styles: &mut StyleExtras,
+    sheet: &str,
+    block: &Block,
+    (row, col): (u32, u32),
+    paste: Paste,
+) {
+    let (rows, cols) = block.size;
+    let (r2, c2) = (row + rows - 1, col + cols - 1);
+    let inside = |r: u32, c: u32| (row..=r2).contains(&r) && (col..=c2).contains(&c);
+
+    for copy in &block.cells {
+        let (r, c) = (row + copy.offset.0, col + copy.offset.1);
+        if paste == Paste::All {
+            ws.remove_cell((c, r));
+        }
+        match &copy.cell {
+            Some(src) => {
+                let dst = ws.get_cell_mut((c, r));
+                if paste.values() {
+                    *dst.get_cell_value_mut() = src.get_cell_value().clone();
+                }
+                if paste.formats() {
+                    dst.set_style(src.get_style().clone());
+                }
+                if paste == Paste::All {
+                    if let Some(link) = src.get_hyperlink() {
+                        dst.set_hyperlink(link.clone());
+                    }
+                }
+                if let (true, Some(f)) = (paste.values(), &copy.formula) {
+                    set_formula_obj(ws, (c, r), f.clone());
+                }
+            }
+            None if paste != Paste::All => {
+                if ws.get_cell((c, r)).is_some() {
+                    let dst = ws.get_cell_mut((c, r));
+                    if paste.values() {
+                        dst.set_blank();
+                    } else {
+                        dst.set_style(Style::default());
+                    }
+                }
+            }
+            None => {}
+        }
+
+        if paste.values() {
+            extras.clear(sheet, (r, c));
+            if copy.dynamic {
+                extras
+                    .dynamic_arrays
+                    .entry(sheet.to_string())
+                    .or_default()
+                    .insert((r, c));
+            }
+            if let Some(aligns) = &copy.run_alignments {
+                extras
+                    .run_alignments
+                    .entry(sheet.to_string())
+                    .or_default()
+                    .insert((r, c), aligns.clone());
+            }
+        }
+        if paste.formats() {
+            styles.set_cell_extras(sheet, (r, c), copy.xf_extras.clone().unwrap_or_default());
+            styles
+                .named
+                .set_cell_style(sheet, (r, c), copy.named_style.as_deref());
+        }
+    }
+
+    if paste.formats() {
+        ws.get_merge_cells_mut().retain(|range| {
+            !range_bounds(&range.get_range())
+                .is_ok_and(|(a1, b1, a2, b2)| inside(a1, b1) && inside(a2, b2))
+        });
+        for ((a1r, a1c), (a2r, a2c)) in &block.merges {
+            let range = format!(
+                "{}:{}",
+                cell_name(row + a1r, col + a1c),
+                cell_name(row + a2r, col + a2c)
+            );
+            ws.add_merge_cells(range);
+        }
+    }
+    if paste == Paste::All {
+        ws.get_comments_mut().retain(|comment| {
+            let coord = comment.get_coordinate();
+            !inside(*coord.get_row_num(), *coord.get_col_num())
+        });
+        for ((dr, dc), src) in &block.comments {
+            let mut comment = Comment::default();
+            comment.new_comment(cell_name(row + dr, col + dc));
+            comment.set_author(src.get_author());
+            *comment.get_text_mut() = src.get_text().clone();
+            ws.add_comments(comment);
+        }
+    }
+}
+
+/// Move the cells of `src` so that its top-left cell lands on `dest`, with
+/// their values, formatting, merged ranges, comments and hyperlinks. The
+/// cells left behind are cleared and the ones landed on are replaced.
+pub(crate) fn move_range(
+    book: &mut Spreadsheet,
+    extras: &mut CellExtras,
+    styles: &mut StyleExtras,
+    sheet: &str,
+    src: &str,
+    dest: &str,
+) -> PyResult<()> {
+    if book.get_sheet_by_name(sheet).is_none() {
+        return Err(PyErr::new::<PyValueError, _>(format!(
+            "Unknown sheet: {sheet}"
+        )));
+    }
+    let area = range_bounds(src)?;
+    let (row, col) = destination(dest, (area.2 - area.0 + 1, area.3 - area.1 + 1))?;
+    let (rows, cols) = (
+        i64::from(row) - i64::from(area.0),
+        i64::from(col) - i64::from(area.1),
+    );
+    if (rows, cols) == (0, 0) {
+        return Ok(());
+    }
+
+    // Adjust every formula before moving anything, so one that cannot be read
+    // leaves the sheet as it was.
+    let saved = SavedFormulas::new(book)
+        .adjusted(|text, host| move_formula(text, host, sheet, area, rows, cols))?;
+    let landed = (row, col, row + area.2 - area.0, col + area.3 - area.1);
+    let ws = book
+        .get_sheet_by_name_mut(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+    let block = read_block(ws, extras, styles, sheet, area);
+    clear_block(ws, extras, styles, sheet, area);
+    paste_block(ws, extras, styles, sheet, &block, (row, col), Paste::All);
+
+    // Formulas in the moved cells go with them; those landed on are gone.
+    saved.restore(book, |host, (r, c)| {
+        if host != sheet {
+            Some((r, c))
+        } else if within(area, (r, c)) {
+            Some(((i64::from(r) + rows) as u32, (i64::from(c) + cols) as u32))
+        } else if within(landed, (r, c)) {
+            None
+        } else {
+            Some((r, c))
+        }
+    })
+}
+
+/// Copy the cells of a `(sheet, range)` to a `(sheet, dest)`, `dest` being a
+/// cell or a range whose top-left cell is used. `what` is `"values"`
+/// (values and formulas), `"formats"` (styles and merged ranges) or `"all"`
+/// (both, plus comments and hyperlinks). Relative references in copied
+/// formulas move by the distance copied.
+pub(crate) fn copy_range(
+    book: &mut Spreadsheet,
+    extras: &mut CellExtras,
+    styles: &mut StyleExtras,
+    (src_sheet, src): (&str, &str),
+    (dst_sheet, dest): (&str, &str),
+    what: &str,
+) -> PyResult<()> {
+    let paste = Paste::parse(what)?;
+    let ws = book
+        .get_sheet_by_name(src_sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {src_sheet}")))?;
+    let area = range_bounds(src)?;
+    let mut block = read_block(ws, extras, styles, src_sheet, area);
+    let (row, col) = destination(dest, block.size)?;
+
+    let (rows, cols) = (
+        i64::from(row) - i64::from(area.0),
+        i64::from(col) - i64::from(area.1),
+    );
+    let translate = |text: &str| {
+        translate_formula(text, rows, cols).map_err(|e| {
+            PyErr::new::<PyValueError, _>(format!("Cannot adjust formula {text}: {e}"))
+        })
+    };
+    for f in block.cells.iter_mut().filter_map(|c| c.formula.as_mut()) {
+        let text = translate(f.get_text())?;
+        f.set_text(text);
+        if !f.get_reference().is_empty() {
+            let reference = translate(f.get_reference())?;
+            f.set_reference(reference);
+        }
+    }
+
+    let ws = book
+        .get_sheet_by_name_mut(dst_sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {dst_sheet}")))?;
+    paste_block(ws, extras, styles, dst_sheet, &block, (row, col), paste);
+    Ok(())
+}
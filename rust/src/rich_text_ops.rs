@@ -0,0 +1,163 @@
+// Rich text (multi-run) cell values.
+//
+// A rich text payload is `{"type": "rich_text", "value": <plain text>, "runs":
+// [...]}` where each run is `{"text": str, "font": {...}}`. Runs without a
+// `font` use the cell's font. Font keys are `name`, `size`, `bold`, `italic`,
+// `underline`, `strike`, `color` and `vert_align` (`"superscript"` or
+// `"subscript"`). umya does not model `vertAlign`, so run alignments travel
+// separately and are patched into the package on save.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+use umya_spreadsheet::structs::{Font, RichText, TextElement};
+
+use crate::utils::{argb_to_hex, hex_to_argb};
+
+const BASELINE: &str = "baseline";
+
+/// Build the payload for a rich text value. `aligns` holds the `vertAlign` of
+/// each run, if any run is raised or lowered.
+pub(crate) fn rich_text_payload(
+    py: Python<'_>,
+    rt: &RichText,
+    aligns: Option<&Vec<String>>,
+) -> PyResult<Py<PyAny>> {
+    let runs = PyList::empty(py);
+    for (i, element) in rt.get_rich_text_elements().iter().enumerate() {
+        let run = PyDict::new(py);
+        run.set_item("text", element.get_text())?;
+
+        let align = aligns
+            .and_then(|a| a.get(i))
+            .map(String::as_str)
+            .filter(|a| *a != BASELINE);
+        let props = element.get_run_properties();
+        if props.is_some() || align.is_some() {
+            let font = PyDict::new(py);
+            if let Some(f) = props {
+                font_items(&font, f)?;
+            }
+            if let Some(a) = align {
+                font.set_item("vert_align", a)?;
+            }
+            run.set_item("font", font)?;
+        }
+        runs.append(run)?;
+    }
+
+    let d = PyDict::new(py);
+    d.set_item("type", "rich_text")?;
+    d.set_item("value", rt.get_text())?;
+    d.set_item("runs", runs)?;
+    Ok(d.into_any().unbind())
+}
+
+fn font_items(d: &Bound<'_, PyDict>, font: &Font) -> PyResult<()> {
+    let name = font.get_name();
+    if !name.is_empty() {
+        d.set_item("name", name.to_string())?;
+    }
+    let size = *font.get_size();
+    if size > 0.0 {
+        d.set_item("size", size)?;
+    }
+    if *font.get_bold() {
+        d.set_item("bold", true)?;
+    }
+    if *font.get_italic() {
+        d.set_item("italic", true)?;
+    }
+    let ul = font.get_underline();
+    if !ul.is_empty() && ul != "none" {
+        d.set_item("underline", ul.to_string())?;
+    }
+    if *font.get_strikethrough() {
+        d.set_item("strike", true)?;
+    }
+    let argb = font.get_color().get_argb();
+    if !argb.is_empty() {
+        d.set_item("color", argb_to_hex(argb))?;
+    }
+    Ok(())
+}
+
+/// Parse the `runs` of a rich text payload. Run fonts start from `base` (the
+/// cell's font), as Excel does when part of a cell is restyled.
+///
+/// Returns the rich text and the `vertAlign` of each run.
+pub(crate) fn parse_rich_text(
+    dict: &Bound<'_, PyDict>,
+    base: Option<&Font>,
+) -> PyResult<(RichText, Vec<String>)> {
+    let runs = dict
+        .get_item("runs")?
+        .ok_or_else(|| PyErr::new::<PyValueError, _>("rich_text payload missing 'runs'"))?;
+
+    let mut rt = RichText::default();
+    let mut aligns = Vec::new();
+    for run in runs.try_iter()? {
+        let run = run?;
+        let run = run
+            .cast::<PyDict>()
+            .map_err(|_| PyErr::new::<PyValueError, _>("rich text run must be a dict"))?;
+        let text = match run.get_item("text")? {
+            Some(v) => v.extract::<String>()?,
+            None => String::new(),
+        };
+
+        let mut element = TextElement::default();
+        element.set_text(text);
+        let mut align = BASELINE.to_string();
+        if let Some(font) = run.get_item("font")?.filter(|f| !f.is_none()) {
+            let font = font
+                .cast::<PyDict>()
+                .map_err(|_| PyErr::new::<PyValueError, _>("run font must be a dict"))?;
+            element.set_run_properties(parse_font(font, base)?);
+            if let Some(v) = font.get_item("vert_align")?.filter(|v| !v.is_none()) {
+                align = v.extract::<String>()?;
+                if !matches!(align.as_str(), BASELINE | "superscript" | "subscript") {
+                    return Err(PyErr::new::<PyValueError, _>(format!(
+                        "Invalid vert_align: {align}"
+                    )));
+                }
+            }
+        }
+        rt.add_rich_text_elements(element);
+        aligns.push(align);
+    }
+    Ok((rt, aligns))
+}
+
+fn parse_font(d: &Bound<'_, PyDict>, base: Option<&Font>) -> PyResult<Font> {
+    let mut font = base.cloned().unwrap_or_default();
+    if let Some(v) = d.get_item("name")?.filter(|v| !v.is_none()) {
+        font.set_name(v.extract::<String>()?);
+    }
+    if let Some(v) = d.get_item("size")?.filter(|v| !v.is_none()) {
+        font.set_size(v.extract::<f64>()?);
+    }
+    if let Some(v) = d.get_item("bold")?.filter(|v| !v.is_none()) {
+        font.set_bold(v.extract::<bool>()?);
+    }
+    if let Some(v) = d.get_item("italic")?.filter(|v| !v.is_none()) {
+        font.set_italic(v.extract::<bool>()?);
+    }
+    if let Some(v) = d.get_item("underline")?.filter(|v| !v.is_none()) {
+        font.set_underline(v.extract::<String>()?);
+    }
+    if let Some(v) = d.get_item("strike")?.filter(|v| !v.is_none()) {
+        font.set_strikethrough(v.extract::<bool>()?);
+    }
+    if let Some(v) = d.get_item("color")?.filter(|v| !v.is_none()) {
+        font.get_color_mut()
+            .set_argb(hex_to_argb(&v.extract::<String>()?));
+    }
+    Ok(font)
+}
+
+/// Whether any run is raised or lowered.
+pub(crate) fn has_alignment(aligns: &[String]) -> bool {
+    aligns.iter().any(|a| a != BASELINE)
+}
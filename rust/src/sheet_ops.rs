@@ -0,0 +1,418 @@
+// Sheet management: renaming, reordering and copying sheets, within a
+// workbook or into another one, and their visibility, tab colors and
+// selection. The state kept alongside the workbook by sheet name (cell and
+// style extras, outlines, view flags) follows each sheet.
+
+use std::collections::{HashMap, HashSet};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use umya_spreadsheet::structs::{Color, SheetView};
+use umya_spreadsheet::{Spreadsheet, Worksheet};
+
+use crate::cell_ops::CellExtras;
+use crate::format_ops::{color_items, write_color_reference, StyleExtras, XfExtras};
+use crate::formula_parser::rename_sheet_refs;
+use crate::structural_ops::{Outline, Outlines, SavedFormulas};
+use crate::utils::hex_to_argb;
+use crate::view_ops::{SheetViewFlags, ViewFlags};
+
+/// Characters Excel does not allow in sheet names.
+const INVALID_CHARS: [char; 7] = ['[', ']', ':', '*', '?', '/', '\\'];
+
+fn sheet_index(book: &Spreadsheet, sheet: &str) -> PyResult<usize> {
+    book.get_sheet_collection()
+        .iter()
+        .position(|ws| ws.get_name() == sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))
+}
+
+/// Check that `name` is a valid sheet name that no sheet but the one at
+/// `except` uses. Sheet names compare case-insensitively.
+fn check_sheet_name(book: &Spreadsheet, name: &str, except: Option<usize>) -> PyResult<()> {
+    if name.is_empty() || name.chars().count() > 31 {
+        return Err(PyErr::new::<PyValueError, _>(
+            "Sheet name must be 1 to 31 characters",
+        ));
+    }
+    if name.contains(INVALID_CHARS) || name.starts_with('\'') || name.ends_with('\'') {
+        return Err(PyErr::new::<PyValueError, _>(format!(
+            "Invalid sheet name: {name}"
+        )));
+    }
+    let lower = name.to_lowercase();
+    let taken = book
+        .get_sheet_collection()
+        .iter()
+        .enumerate()
+        .any(|(i, ws)| Some(i) != except && ws.get_name().to_lowercase() == lower);
+    if taken {
+        return Err(PyErr::new::<PyValueError, _>(format!(
+            "Sheet already exists: {name}"
+        )));
+    }
+    Ok(())
+}
+
+/// Rename a sheet, pointing formulas, defined names, data validation and
+/// conditional format formulas and internal hyperlinks at the new name.
+pub(crate) fn rename_sheet(
+    book: &mut Spreadsheet,
+    extras: &mut CellExtras,
+    styles: &mut StyleExtras,
+    outlines: &mut Outlines,
+    view_flags: &mut SheetViewFlags,
+    old: &str,
+    new: &str,
+) -> PyResult<()> {
+    let index = sheet_index(book, old)?;
+    if new == old {
+        return Ok(());
+    }
+    check_sheet_name(book, new, Some(index))?;
+
+    // Work out every change before making any, so a formula or link that
+    // cannot be read leaves the workbook as it was.
+    let saved = SavedFormulas::new(book).adjusted(|text, _| rename_sheet_refs(text, old, new))?;
+    let mut links = Vec::new();
+    for (si, ws) in book.get_sheet_collection().iter().enumerate() {
+        for cell in ws.get_cell_collection() {
+            let Some(link) = cell.get_hyperlink().filter(|l| *l.get_location()) else {
+                continue;
+            };
+            let url = rename_sheet_refs(link.get_url(), old, new).map_err(|e| {
+                let url = link.get_url();
+                PyErr::new::<PyValueError, _>(format!("Cannot adjust hyperlink {url}: {e}"))
+            })?;
+            if url != link.get_url() {
+                let coord = cell.get_coordinate();
+                let mut link = link.clone();
+                link.set_url(url);
+                links.push((si, (*coord.get_col_num(), *coord.get_row_num()), link));
+            }
+        }
+    }
+    saved.restore(book, |_, cell| Some(cell))?;
+    let sheets = book.get_sheet_collection_mut();
+    for (si, at, link) in links {
+        sheets[si].get_cell_mut(at).set_hyperlink(link);
+    }
+    book.get_sheet_collection_mut()[index].set_name(new);
+
+    extras.rename_sheet(old, new);
+    styles.rename_sheet(old, new);
+    if let Some(outline) = outlines.remove(old) {
+        outlines.insert(new.to_string(), outline);
+    }
+    if let Some(flags) = view_flags.remove(old) {
+        view_flags.insert(new.to_string(), flags);
+    }
+    Ok(())
+}
+
+/// Move a sheet to 0-based position `to` in the tab order.
+pub(crate) fn move_sheet(book: &mut Spreadsheet, sheet: &str, to: i64) -> PyResult<()> {
+    let from = sheet_index(book, sheet)?;
+    let sheets = book.get_sheet_collection_mut();
+    let Some(to) = usize::try_from(to).ok().filter(|&to| to < sheets.len()) else {
+        return Err(PyErr::new::<PyValueError, _>(format!(
+            "Sheet position must be between 0 and {}",
+            sheets.len() - 1
+        )));
+    };
+    if from < to {
+        sheets[from..=to].rotate_left(1);
+    } else {
+        sheets[to..=from].rotate_right(1);
+    }
+    Ok(())
+}
+
+/// A copy of a sheet with the state kept alongside it, ready to be added to
+/// a workbook under a new name.
+pub(crate) struct SheetCopy {
+    ws: Worksheet,
+    dynamic_arrays: Option<HashSet<(u32, u32)>>,
+    run_alignments: Option<HashMap<(u32, u32), Vec<String>>>,
+    cell_xfs: Option<HashMap<(u32, u32), XfExtras>>,
+    named_cells: HashMap<(u32, u32), String>,
+    outline: Option<Outline>,
+    view_flags: Option<ViewFlags>,
+}
+
+/// Copy a sheet and its state, to add with [`add_sheet_copy`].
+pub(crate) fn sheet_copy(
+    book: &Spreadsheet,
+    extras: &CellExtras,
+    styles: &StyleExtras,
+    outlines: &Outlines,
+    view_flags: &SheetViewFlags,
+    sheet: &str,
+) -> PyResult<SheetCopy> {
+    let ws = book
+        .get_sheet_by_name(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+    Ok(SheetCopy {
+        ws: ws.clone(),
+        dynamic_arrays: extras.dynamic_arrays.get(sheet).cloned(),
+        run_alignments: extras.run_alignments.get(sheet).cloned(),
+        cell_xfs: styles.cell_xfs.get(sheet).cloned(),
+        named_cells: styles.named.sheet_cells(sheet),
+        outline: outlines.get(sheet).cloned(),
+        view_flags: view_flags.get(sheet).cloned(),
+    })
+}
+
+/// Add a copied sheet as the last sheet of a workbook, named `name`.
+///
+/// The copy's sheet-level defined names point at the copy. Its formulas are
+/// kept as written, so in another workbook references to other sheets
+/// resolve against that workbook's sheets; cells linked to a named style the
+/// workbook does not define keep their formatting but lose the link.
+pub(crate) fn add_sheet_copy(
+    book: &mut Spreadsheet,
+    extras: &mut CellExtras,
+    styles: &mut StyleExtras,
+    outlines: &mut Outlines,
+    view_flags: &mut SheetViewFlags,
+    copy: SheetCopy,
+    name: &str,
+) -> PyResult<()> {
+    check_sheet_name(book, name, None)?;
+    let mut ws = copy.ws;
+    let source = ws.get_name().to_string();
+    for dn in ws.get_defined_names_mut() {
+        let address = dn.get_address();
+        let renamed = rename_sheet_refs(&address, &source, name).map_err(|e| {
+            PyErr::new::<PyValueError, _>(format!("Cannot adjust formula {address}: {e}"))
+        })?;
+        dn.set_address(renamed);
+    }
+    ws.set_name(name);
+    // Sheet ids must be unique within the workbook.
+    let next_id = book
+        .get_sheet_collection()
+        .iter()
+        .filter_map(|sheet| sheet.get_sheet_id().parse::<u32>().ok())
+        .max()
+        .unwrap_or(0)
+        + 1;
+    ws.set_sheet_id(next_id.to_string());
+    book.add_sheet(ws)
+        .map_err(|e| PyErr::new::<PyValueError, _>(format!("{e}")))?;
+
+    if let Some(cells) = copy.dynamic_arrays {
+        extras.dynamic_arrays.insert(name.to_string(), cells);
+    }
+    if let Some(cells) = copy.run_alignments {
+        extras.run_alignments.insert(name.to_string(), cells);
+    }
+    if let Some(cells) = copy.cell_xfs {
+        styles.cell_xfs.insert(name.to_string(), cells);
+    }
+    styles.named.link_sheet_cells(name, copy.named_cells);
+    if let Some(outline) = copy.outline {
+        outlines.insert(name.to_string(), outline);
+    }
+    if let Some(flags) = copy.view_flags {
+        view_flags.insert(name.to_string(), flags);
+    }
+    Ok(())
+}
+
+// Visibility, tab color and selection. Excel needs at least one visible sheet,
+// and the active sheet must be visible and among the selected ones.
+
+const SHEET_STATES: [&str; 3] = ["visible", "hidden", "veryHidden"];
+
+fn is_visible(ws: &Worksheet) -> bool {
+    matches!(ws.get_sheet_state(), "" | "visible")
+}
+
+fn is_selected(ws: &Worksheet) -> bool {
+    ws.get_sheet_views()
+        .get_sheet_view_list()
+        .first()
+        .is_some_and(|sv| *sv.get_tab_selected())
+}
+
+fn set_selected(ws: &mut Worksheet, selected: bool) {
+    let views = ws.get_sheet_views_mut().get_sheet_view_list_mut();
+    if views.is_empty() {
+        if !selected {
+            return;
+        }
+        views.push(SheetView::default());
+    }
+    views[0].set_tab_selected(selected);
+}
+
+/// A sheet's `state` (`"visible"`, `"hidden"` or `"veryHidden"`), its tab
+/// color as `tab_color` (plus `tab_color_theme`/`_tint`/`_indexed` for color
+/// references) and whether it is `selected`.
+pub(crate) fn read_sheet_properties(
+    book: &Spreadsheet,
+    styles: &StyleExtras,
+    py: Python<'_>,
+    sheet: &str,
+) -> PyResult<Py<PyAny>> {
+    let ws = book
+        .get_sheet_by_name(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+    let d = PyDict::new(py);
+    let state = match ws.get_sheet_state() {
+        "" => "visible",
+        state => state,
+    };
+    d.set_item("state", state)?;
+    if let Some(color) = ws.get_tab_color() {
+        color_items(&d, "tab_color", color, &styles.palette, false)?;
+    }
+    d.set_item("selected", is_selected(ws))?;
+    Ok(d.into_any().unbind())
+}
+
+/// Update a sheet's `state` and/or tab color from a dict. `tab_color` is
+/// `RRGGBB`, or `None` to remove it; `tab_color_theme` (with `_tint`) or
+/// `tab_color_indexed` set it by reference.
+pub(crate) fn set_sheet_properties(
+    book: &mut Spreadsheet,
+    sheet: &str,
+    props: &Bound<'_, PyAny>,
+) -> PyResult<()> {
+    let index = sheet_index(book, sheet)?;
+    let dict = props
+        .cast::<PyDict>()
+        .map_err(|_| PyErr::new::<PyValueError, _>("props must be a dict"))?;
+
+    if let Some(state) = dict.get_item("state")? {
+        let state = state.extract::<String>()?;
+        if !SHEET_STATES.contains(&state.as_str()) {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "Invalid sheet state: {state}"
+            )));
+        }
+        if state != "visible" {
+            hide_sheet(book, index)?;
+        }
+        book.get_sheet_collection_mut()[index].set_sheet_state(state);
+    }
+
+    let ws = &mut book.get_sheet_collection_mut()[index];
+    let mut color = Color::default();
+    if write_color_reference(&mut color, dict, "tab_color")? {
+        ws.set_tab_color(color);
+    } else if let Some(hex) = dict.get_item("tab_color")? {
+        if hex.is_none() {
+            ws.remove_tab_color();
+        } else {
+            color.set_argb(hex_to_argb(&hex.extract::<String>()?));
+            ws.set_tab_color(color);
+        }
+    }
+    Ok(())
+}
+
+/// Unselect the sheet at `index` before it is hidden, handing the active tab
+/// to the first other visible sheet if it had it.
+fn hide_sheet(book: &mut Spreadsheet, index: usize) -> PyResult<()> {
+    let sheets = book.get_sheet_collection_mut();
+    let Some(fallback) = (0..sheets.len()).find(|&i| i != index && is_visible(&sheets[i])) else {
+        return Err(PyErr::new::<PyValueError, _>(
+            "A workbook must keep at least one visible sheet",
+        ));
+    };
+    set_selected(&mut sheets[index], false);
+    if !sheets.iter().any(is_selected) {
+        set_selected(&mut sheets[fallback], true);
+    }
+    if *book.get_workbook_view().get_active_tab() as usize == index {
+        book.get_workbook_view_mut().set_active_tab(fallback as u32);
+    }
+    Ok(())
+}
+
+/// The workbook view: the `active` sheet index, the `first_sheet` shown in the
+/// tab bar and the names of the `selected` (grouped) sheets.
+pub(crate) fn read_workbook_view(
+    book: &Spreadsheet,
+    first_sheet: u32,
+    py: Python<'_>,
+) -> PyResult<Py<PyAny>> {
+    let d = PyDict::new(py);
+    d.set_item("active", *book.get_workbook_view().get_active_tab())?;
+    d.set_item("first_sheet", first_sheet)?;
+    let selected: Vec<&str> = book
+        .get_sheet_collection()
+        .iter()
+        .filter(|ws| is_selected(ws))
+        .map(|ws| ws.get_name())
+        .collect();
+    d.set_item("selected", selected)?;
+    Ok(d.into_any().unbind())
+}
+
+fn check_visible(book: &Spreadsheet, index: usize) -> PyResult<()> {
+    let Some(ws) = book.get_sheet_collection().get(index) else {
+        return Err(PyErr::new::<PyValueError, _>(format!(
+            "No sheet at index {index}"
+        )));
+    };
+    if !is_visible(ws) {
+        return Err(PyErr::new::<PyValueError, _>(format!(
+            "Sheet {} is hidden",
+            ws.get_name()
+        )));
+    }
+    Ok(())
+}
+
+/// Make the sheet at `index` the one the workbook opens on, and the only
+/// selected sheet.
+pub(crate) fn set_active_sheet(book: &mut Spreadsheet, index: usize) -> PyResult<()> {
+    check_visible(book, index)?;
+    for (i, ws) in book.get_sheet_collection_mut().iter_mut().enumerate() {
+        set_selected(ws, i == index);
+    }
+    book.get_workbook_view_mut().set_active_tab(index as u32);
+    Ok(())
+}
+
+/// Select (group) the named sheets. If the active sheet is not among them the
+/// first becomes active.
+pub(crate) fn set_selected_sheets(book: &mut Spreadsheet, names: Vec<String>) -> PyResult<()> {
+    if names.is_empty() {
+        return Err(PyErr::new::<PyValueError, _>(
+            "At least one sheet must be selected",
+        ));
+    }
+    let indexes = names
+        .iter()
+        .map(|name| sheet_index(book, name))
+        .collect::<PyResult<Vec<usize>>>()?;
+    for &index in &indexes {
+        check_visible(book, index)?;
+    }
+    for (i, ws) in book.get_sheet_collection_mut().iter_mut().enumerate() {
+        set_selected(ws, indexes.contains(&i));
+    }
+    let active = *book.get_workbook_view().get_active_tab() as usize;
+    if !indexes.contains(&active) {
+        book.get_workbook_view_mut()
+            .set_active_tab(indexes[0] as u32);
+    }
+    Ok(())
+}
+
+/// Check the first sheet shown in the tab bar is a sheet of the workbook.
+pub(crate) fn check_first_sheet(book: &Spreadsheet, index: u32) -> PyResult<()> {
+    let count = book.get_sheet_collection().len();
+    if index as usize >= count {
+        return Err(PyErr::new::<PyValueError, _>(format!(
+            "No sheet at index {index}"
+        )));
+    }
+    Ok(())
+}
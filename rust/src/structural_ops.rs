@@ -1,11 +1,19 @@
+use std::collections::{HashMap, HashSet};
+
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
 
-use umya_spreadsheet::structs::{Coordinate, Pane, PaneStateValues, PaneValues, SheetView};
-use umya_spreadsheet::Spreadsheet;
+use umya_spreadsheet::structs::{
+    Cell, CellFormula, Coordinate, Formula, Pane, PaneStateValues, PaneValues, SheetView, Style,
+};
+use umya_spreadsheet::{Spreadsheet, Worksheet};
 
-use crate::utils::{a1_to_row_col, col_letter_to_u32};
+use crate::cell_ops::{set_formula_obj, CellExtras};
+use crate::format_ops::{apply_format, format_items, StyleExtras};
+use crate::formula_parser::{shift_formula, LineShift, MAX_COL, MAX_ROW};
+use crate::utils::{a1_range_to_bounds, a1_to_row_col, col_letter_to_u32};
+use crate::view_ops::place_pane;
 
 pub(crate) fn read_row_height(book: &Spreadsheet, sheet: &str, row: u32) -> PyResult<Option<f64>> {
     let ws = book
@@ -78,6 +86,934 @@ pub(crate) fn set_column_width(
     Ok(())
 }
 
+pub(crate) fn read_row_hidden(book: &Spreadsheet, sheet: &str, row: u32) -> PyResult<bool> {
+    let ws = book
+        .get_sheet_by_name(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+
+    if row == 0 {
+        return Err(PyErr::new::<PyValueError, _>("row must be >= 1"));
+    }
+
+    Ok(ws
+        .get_row_dimension(&row)
+        .is_some_and(|rd| *rd.get_hidden()))
+}
+
+pub(crate) fn set_row_hidden(
+    book: &mut Spreadsheet,
+    sheet: &str,
+    row: u32,
+    hidden: bool,
+) -> PyResult<()> {
+    let ws = book
+        .get_sheet_by_name_mut(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+
+    if row == 0 {
+        return Err(PyErr::new::<PyValueError, _>("row must be >= 1"));
+    }
+
+    ws.get_row_dimension_mut(&row).set_hidden(hidden);
+    Ok(())
+}
+
+pub(crate) fn read_column_hidden(book: &Spreadsheet, sheet: &str, col_str: &str) -> PyResult<bool> {
+    let ws = book
+        .get_sheet_by_name(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+
+    let col_idx = col_letter_to_u32(col_str).map_err(|e| PyErr::new::<PyValueError, _>(e))?;
+    Ok(ws
+        .get_column_dimension_by_number(&col_idx)
+        .is_some_and(|cd| *cd.get_hidden()))
+}
+
+pub(crate) fn set_column_hidden(
+    book: &mut Spreadsheet,
+    sheet: &str,
+    col_str: &str,
+    hidden: bool,
+) -> PyResult<()> {
+    let ws = book
+        .get_sheet_by_name_mut(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+
+    let col_idx = col_letter_to_u32(col_str).map_err(|e| PyErr::new::<PyValueError, _>(e))?;
+    ws.get_column_dimension_by_number_mut(&col_idx)
+        .set_hidden(hidden);
+    Ok(())
+}
+
+// Outline grouping. umya drops the outline level and `collapsed` flag of rows
+// and columns and the sheet's outline properties, so they are kept alongside
+// the workbook and patched into the package on save.
+
+/// Outline grouping of a sheet.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Outline {
+    /// Outline level (1-7) of grouped rows and columns, by 1-based index.
+    pub rows: HashMap<u32, u8>,
+    pub cols: HashMap<u32, u8>,
+    /// Rows and columns flagged `collapsed`: the summary line of a collapsed
+    /// group.
+    pub collapsed_rows: HashSet<u32>,
+    pub collapsed_cols: HashSet<u32>,
+    /// Summary rows sit below their details (otherwise above) and summary
+    /// columns to their right (otherwise left).
+    pub summary_below: bool,
+    pub summary_right: bool,
+}
+
+impl Default for Outline {
+    fn default() -> Self {
+        Self {
+            rows: HashMap::new(),
+            cols: HashMap::new(),
+            collapsed_rows: HashSet::new(),
+            collapsed_cols: HashSet::new(),
+            summary_below: true,
+            summary_right: true,
+        }
+    }
+}
+
+impl Outline {
+    pub(crate) fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Move levels and flags along with their rows or columns.
+    pub(crate) fn shift(&mut self, shift: LineShift) {
+        let (levels, collapsed) = if shift.rows {
+            (&mut self.rows, &mut self.collapsed_rows)
+        } else {
+            (&mut self.cols, &mut self.collapsed_cols)
+        };
+        *levels = levels
+            .drain()
+            .filter_map(|(line, level)| Some((shift.line(line)?, level)))
+            .collect();
+        *collapsed = collapsed
+            .drain()
+            .filter_map(|line| shift.line(line))
+            .collect();
+    }
+
+    fn set_level(&mut self, rows: bool, line: u32, level: u8) {
+        let levels = if rows { &mut self.rows } else { &mut self.cols };
+        if level == 0 {
+            levels.remove(&line);
+        } else {
+            levels.insert(line, level);
+        }
+    }
+
+    fn set_collapsed(&mut self, rows: bool, line: u32, collapsed: bool) {
+        let flags = if rows {
+            &mut self.collapsed_rows
+        } else {
+            &mut self.collapsed_cols
+        };
+        if collapsed {
+            flags.insert(line);
+        } else {
+            flags.remove(&line);
+        }
+    }
+}
+
+/// Outline grouping by sheet name.
+pub(crate) type Outlines = HashMap<String, Outline>;
+
+fn check_level(level: u8) -> PyResult<()> {
+    if level > 7 {
+        return Err(PyErr::new::<PyValueError, _>(
+            "outline level must be between 0 and 7",
+        ));
+    }
+    Ok(())
+}
+
+/// 1-based bounds of a column span given as letters.
+fn column_span(start: &str, end: &str) -> PyResult<(u32, u32)> {
+    let start = col_letter_to_u32(start).map_err(|e| PyErr::new::<PyValueError, _>(e))?;
+    let end = col_letter_to_u32(end).map_err(|e| PyErr::new::<PyValueError, _>(e))?;
+    if end < start {
+        return Err(PyErr::new::<PyValueError, _>(
+            "end column must not be before start column",
+        ));
+    }
+    Ok((start, end))
+}
+
+fn row_span(start: u32, end: u32) -> PyResult<(u32, u32)> {
+    if start == 0 {
+        return Err(PyErr::new::<PyValueError, _>("row must be >= 1"));
+    }
+    if end < start {
+        return Err(PyErr::new::<PyValueError, _>(
+            "end row must not be before start row",
+        ));
+    }
+    Ok((start, end))
+}
+
+/// Outline level and `collapsed` flag of a row.
+pub(crate) fn read_row_outline(
+    book: &Spreadsheet,
+    outlines: &Outlines,
+    sheet: &str,
+    row: u32,
+) -> PyResult<(u8, bool)> {
+    book.get_sheet_by_name(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+    let outline = outlines.get(sheet);
+    Ok((
+        outline.and_then(|o| o.rows.get(&row)).copied().unwrap_or(0),
+        outline.is_some_and(|o| o.collapsed_rows.contains(&row)),
+    ))
+}
+
+/// Set the outline level (0 to ungroup) of rows `start..=end`.
+pub(crate) fn set_row_outline_level(
+    book: &mut Spreadsheet,
+    outlines: &mut Outlines,
+    sheet: &str,
+    (start, end): (u32, u32),
+    level: u8,
+) -> PyResult<()> {
+    let ws = book
+        .get_sheet_by_name_mut(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+    let (start, end) = row_span(start, end)?;
+    check_level(level)?;
+
+    let outline = outlines.entry(sheet.to_string()).or_default();
+    for row in start..=end {
+        // The row must exist in the package for its level to be written.
+        ws.get_row_dimension_mut(&row);
+        outline.set_level(true, row, level);
+    }
+    Ok(())
+}
+
+pub(crate) fn set_row_collapsed(
+    book: &mut Spreadsheet,
+    outlines: &mut Outlines,
+    sheet: &str,
+    row: u32,
+    collapsed: bool,
+) -> PyResult<()> {
+    let ws = book
+        .get_sheet_by_name_mut(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+    row_span(row, row)?;
+    ws.get_row_dimension_mut(&row);
+    outlines
+        .entry(sheet.to_string())
+        .or_default()
+        .set_collapsed(true, row, collapsed);
+    Ok(())
+}
+
+/// Group rows `start..=end` at `level`, as Excel's Group command. With
+/// `hidden` the group is collapsed: its rows are hidden and its summary row
+/// (below or above, as the sheet's outline properties say) is flagged.
+pub(crate) fn group_rows(
+    book: &mut Spreadsheet,
+    outlines: &mut Outlines,
+    sheet: &str,
+    span: (u32, u32),
+    level: u8,
+    hidden: bool,
+) -> PyResult<()> {
+    set_row_outline_level(book, outlines, sheet, span, level)?;
+    let ws = book
+        .get_sheet_by_name_mut(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+    let (start, end) = span;
+    for row in start..=end {
+        ws.get_row_dimension_mut(&row).set_hidden(hidden);
+    }
+
+    let outline = outlines.entry(sheet.to_string()).or_default();
+    let summary = if outline.summary_below {
+        Some(end + 1).filter(|&r| r <= MAX_ROW)
+    } else {
+        start.checked_sub(1).filter(|&r| r >= 1)
+    };
+    if let Some(row) = summary {
+        ws.get_row_dimension_mut(&row);
+        outline.set_collapsed(true, row, hidden);
+    }
+    Ok(())
+}
+
+/// Outline level and `collapsed` flag of a column.
+pub(crate) fn read_column_outline(
+    book: &Spreadsheet,
+    outlines: &Outlines,
+    sheet: &str,
+    col_str: &str,
+) -> PyResult<(u8, bool)> {
+    book.get_sheet_by_name(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+    let col = col_letter_to_u32(col_str).map_err(|e| PyErr::new::<PyValueError, _>(e))?;
+    let outline = outlines.get(sheet);
+    Ok((
+        outline.and_then(|o| o.cols.get(&col)).copied().unwrap_or(0),
+        outline.is_some_and(|o| o.collapsed_cols.contains(&col)),
+    ))
+}
+
+/// Set the outline level (0 to ungroup) of columns `start..=end` (letters).
+pub(crate) fn set_column_outline_level(
+    book: &mut Spreadsheet,
+    outlines: &mut Outlines,
+    sheet: &str,
+    (start, end): (&str, &str),
+    level: u8,
+) -> PyResult<()> {
+    let ws = book
+        .get_sheet_by_name_mut(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+    let (start, end) = column_span(start, end)?;
+    check_level(level)?;
+
+    let outline = outlines.entry(sheet.to_string()).or_default();
+    for col in start..=end {
+        ws.get_column_dimension_by_number_mut(&col);
+        outline.set_level(false, col, level);
+    }
+    Ok(())
+}
+
+pub(crate) fn set_column_collapsed(
+    book: &mut Spreadsheet,
+    outlines: &mut Outlines,
+    sheet: &str,
+    col_str: &str,
+    collapsed: bool,
+) -> PyResult<()> {
+    let ws = book
+        .get_sheet_by_name_mut(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+    let col = col_letter_to_u32(col_str).map_err(|e| PyErr::new::<PyValueError, _>(e))?;
+    ws.get_column_dimension_by_number_mut(&col);
+    outlines
+        .entry(sheet.to_string())
+        .or_default()
+        .set_collapsed(false, col, collapsed);
+    Ok(())
+}
+
+/// Group columns `start..=end` (letters) at `level`; see [`group_rows`].
+pub(crate) fn group_columns(
+    book: &mut Spreadsheet,
+    outlines: &mut Outlines,
+    sheet: &str,
+    span: (&str, &str),
+    level: u8,
+    hidden: bool,
+) -> PyResult<()> {
+    set_column_outline_level(book, outlines, sheet, span, level)?;
+    let ws = book
+        .get_sheet_by_name_mut(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+    let (start, end) = column_span(span.0, span.1)?;
+    for col in start..=end {
+        ws.get_column_dimension_by_number_mut(&col)
+            .set_hidden(hidden);
+    }
+
+    let outline = outlines.entry(sheet.to_string()).or_default();
+    let summary = if outline.summary_right {
+        Some(end + 1).filter(|&c| c <= MAX_COL)
+    } else {
+        start.checked_sub(1).filter(|&c| c >= 1)
+    };
+    if let Some(col) = summary {
+        ws.get_column_dimension_by_number_mut(&col);
+        outline.set_collapsed(false, col, hidden);
+    }
+    Ok(())
+}
+
+/// The sheet's outline properties: `summary_below` and `summary_right`.
+pub(crate) fn read_outline_properties(
+    book: &Spreadsheet,
+    outlines: &Outlines,
+    py: Python<'_>,
+    sheet: &str,
+) -> PyResult<Py<PyAny>> {
+    book.get_sheet_by_name(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+    let default = Outline::default();
+    let outline = outlines.get(sheet).unwrap_or(&default);
+    let d = PyDict::new(py);
+    d.set_item("summary_below", outline.summary_below)?;
+    d.set_item("summary_right", outline.summary_right)?;
+    Ok(d.into_any().unbind())
+}
+
+/// Update the sheet's outline properties from a dict with `summary_below`
+/// and/or `summary_right`.
+pub(crate) fn set_outline_properties(
+    book: &Spreadsheet,
+    outlines: &mut Outlines,
+    sheet: &str,
+    props: &Bound<'_, PyAny>,
+) -> PyResult<()> {
+    book.get_sheet_by_name(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+    let dict = props
+        .cast::<PyDict>()
+        .map_err(|_| PyErr::new::<PyValueError, _>("props must be a dict"))?;
+    let outline = outlines.entry(sheet.to_string()).or_default();
+    if let Some(v) = dict.get_item("summary_below")? {
+        outline.summary_below = v.extract()?;
+    }
+    if let Some(v) = dict.get_item("summary_right")? {
+        outline.summary_right = v.extract()?;
+    }
+    Ok(())
+}
+
+// Row and column formatting. A formatted row (`customFormat`) or column is
+// what an empty cell in it shows, and what a new cell in it starts from.
+
+/// Whether a style sets any formatting.
+fn has_format(style: &Style) -> bool {
+    style.get_font().is_some()
+        || style.get_fill().is_some()
+        || style.get_borders().is_some()
+        || style.get_alignment().is_some()
+        || style.get_number_format().is_some()
+}
+
+/// Formatting an empty cell at `(col, row)` shows: its row's if the row is
+/// formatted, otherwise its column's.
+pub(crate) fn row_col_style(ws: &Worksheet, (col, row): (u32, u32)) -> Option<&Style> {
+    ws.get_row_dimension(&row)
+        .map(|rd| rd.get_style())
+        .filter(|style| has_format(style))
+        .or_else(|| {
+            ws.get_column_dimension_by_number(&col)
+                .map(|cd| cd.get_style())
+                .filter(|style| has_format(style))
+        })
+}
+
+/// The cell at `(col, row)`, created with its row's or column's formatting if
+/// it does not exist yet.
+pub(crate) fn cell_with_row_col_style(ws: &mut Worksheet, coord: (u32, u32)) -> &mut Cell {
+    if ws.get_cell(coord).is_none() {
+        if let Some(style) = row_col_style(ws, coord).cloned() {
+            ws.get_cell_mut(coord).set_style(style);
+        }
+    }
+    ws.get_cell_mut(coord)
+}
+
+pub(crate) fn read_row_format(
+    book: &Spreadsheet,
+    styles: &StyleExtras,
+    py: Python<'_>,
+    sheet: &str,
+    row: u32,
+) -> PyResult<Py<PyAny>> {
+    let ws = book
+        .get_sheet_by_name(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+
+    if row == 0 {
+        return Err(PyErr::new::<PyValueError, _>("row must be >= 1"));
+    }
+
+    let d = PyDict::new(py);
+    if let Some(rd) = ws.get_row_dimension(&row) {
+        format_items(py, &d, rd.get_style(), styles)?;
+    }
+    Ok(d.into_any().unbind())
+}
+
+/// Format a whole row. Cells already in the row are formatted too, as in Excel.
+pub(crate) fn write_row_format(
+    book: &mut Spreadsheet,
+    styles: &mut StyleExtras,
+    sheet: &str,
+    row: u32,
+    format_dict: &Bound<'_, PyAny>,
+) -> PyResult<()> {
+    let ws = book
+        .get_sheet_by_name_mut(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+
+    if row == 0 {
+        return Err(PyErr::new::<PyValueError, _>("row must be >= 1"));
+    }
+
+    let dict = format_dict
+        .cast::<PyDict>()
+        .map_err(|_| PyErr::new::<PyValueError, _>("format_dict must be a dict"))?;
+
+    apply_format(
+        ws.get_row_dimension_mut(&row).get_style_mut(),
+        &mut styles.gradient_paths,
+        dict,
+    )?;
+    let cols: Vec<u32> = ws
+        .get_cell_collection()
+        .iter()
+        .map(|c| c.get_coordinate())
+        .filter(|c| *c.get_row_num() == row)
+        .map(|c| *c.get_col_num())
+        .collect();
+    for col in cols {
+        apply_format(
+            ws.get_style_mut((col, row)),
+            &mut styles.gradient_paths,
+            dict,
+        )?;
+        styles.update_cell_extras(sheet, (row, col), |extras| extras.update(dict))?;
+    }
+    Ok(())
+}
+
+pub(crate) fn read_column_format(
+    book: &Spreadsheet,
+    styles: &StyleExtras,
+    py: Python<'_>,
+    sheet: &str,
+    col_str: &str,
+) -> PyResult<Py<PyAny>> {
+    let ws = book
+        .get_sheet_by_name(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+
+    let col_idx = col_letter_to_u32(col_str).map_err(|e| PyErr::new::<PyValueError, _>(e))?;
+    let d = PyDict::new(py);
+    if let Some(cd) = ws.get_column_dimension_by_number(&col_idx) {
+        format_items(py, &d, cd.get_style(), styles)?;
+    }
+    Ok(d.into_any().unbind())
+}
+
+/// Format a whole column. Cells already in the column are formatted too.
+pub(crate) fn write_column_format(
+    book: &mut Spreadsheet,
+    styles: &mut StyleExtras,
+    sheet: &str,
+    col_str: &str,
+    format_dict: &Bound<'_, PyAny>,
+) -> PyResult<()> {
+    let ws = book
+        .get_sheet_by_name_mut(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+
+    let col_idx = col_letter_to_u32(col_str).map_err(|e| PyErr::new::<PyValueError, _>(e))?;
+    let dict = format_dict
+        .cast::<PyDict>()
+        .map_err(|_| PyErr::new::<PyValueError, _>("format_dict must be a dict"))?;
+
+    apply_format(
+        ws.get_column_dimension_by_number_mut(&col_idx)
+            .get_style_mut(),
+        &mut styles.gradient_paths,
+        dict,
+    )?;
+    let rows: Vec<u32> = ws
+        .get_cell_collection()
+        .iter()
+        .map(|c| c.get_coordinate())
+        .filter(|c| *c.get_col_num() == col_idx)
+        .map(|c| *c.get_row_num())
+        .collect();
+    for row in rows {
+        apply_format(
+            ws.get_style_mut((col_idx, row)),
+            &mut styles.gradient_paths,
+            dict,
+        )?;
+        styles.update_cell_extras(sheet, (row, col_idx), |extras| extras.update(dict))?;
+    }
+    Ok(())
+}
+
+// Inserting and deleting rows and columns. umya moves the cells and what is
+// anchored to them (dimensions, merged ranges, hyperlinks, comments, data
+// validations, conditional formats, images). Formulas across the workbook are
+// then rewritten from their text before the move, so that references to the
+// sheet follow their cells and deleted ones become `#REF!` as in Excel.
+
+/// Insert `amount` empty rows before row `idx`.
+pub(crate) fn insert_rows(
+    book: &mut Spreadsheet,
+    extras: &mut CellExtras,
+    styles: &mut StyleExtras,
+    outlines: &mut Outlines,
+    sheet: &str,
+    idx: u32,
+    amount: u32,
+) -> PyResult<()> {
+    let shift = LineShift {
+        rows: true,
+        at: idx,
+        by: i64::from(amount),
+    };
+    shift_lines(book, extras, styles, outlines, sheet, shift)
+}
+
+/// Delete `amount` rows starting at row `idx`; the rows below move up.
+pub(crate) fn delete_rows(
+    book: &mut Spreadsheet,
+    extras: &mut CellExtras,
+    styles: &mut StyleExtras,
+    outlines: &mut Outlines,
+    sheet: &str,
+    idx: u32,
+    amount: u32,
+) -> PyResult<()> {
+    let shift = LineShift {
+        rows: true,
+        at: idx,
+        by: -i64::from(amount),
+    };
+    shift_lines(book, extras, styles, outlines, sheet, shift)
+}
+
+/// Insert `amount` empty columns before column `idx` (1-based).
+pub(crate) fn insert_cols(
+    book: &mut Spreadsheet,
+    extras: &mut CellExtras,
+    styles: &mut StyleExtras,
+    outlines: &mut Outlines,
+    sheet: &str,
+    idx: u32,
+    amount: u32,
+) -> PyResult<()> {
+    let shift = LineShift {
+        rows: false,
+        at: idx,
+        by: i64::from(amount),
+    };
+    shift_lines(book, extras, styles, outlines, sheet, shift)
+}
+
+/// Delete `amount` columns starting at column `idx` (1-based); the columns to
+/// the right move left.
+pub(crate) fn delete_cols(
+    book: &mut Spreadsheet,
+    extras: &mut CellExtras,
+    styles: &mut StyleExtras,
+    outlines: &mut Outlines,
+    sheet: &str,
+    idx: u32,
+    amount: u32,
+) -> PyResult<()> {
+    let shift = LineShift {
+        rows: false,
+        at: idx,
+        by: -i64::from(amount),
+    };
+    shift_lines(book, extras, styles, outlines, sheet, shift)
+}
+
+/// Formula texts of a workbook, recorded so they can be written back adjusted
+/// after cells move.
+pub(crate) struct SavedFormulas {
+    /// Sheet name, 1-based `(row, col)` and formula of every formula cell.
+    cells: Vec<(String, (u32, u32), CellFormula)>,
+    /// Workbook-level defined names.
+    names: Vec<String>,
+    /// Per sheet: sheet-level defined names, data validation formulas and
+    /// conditional format rule formulas, in document order.
+    sheets: Vec<SavedSheet>,
+}
+
+struct SavedSheet {
+    host: String,
+    names: Vec<String>,
+    validations: Vec<(String, String)>,
+    rules: Vec<Vec<Option<String>>>,
+}
+
+impl SavedFormulas {
+    pub(crate) fn new(book: &Spreadsheet) -> Self {
+        let mut cells = Vec::new();
+        let mut sheets = Vec::new();
+        for ws in book.get_sheet_collection() {
+            for cell in ws.get_cell_collection() {
+                if let Some(f) = cell.get_formula_obj() {
+                    let coord = cell.get_coordinate();
+                    let at = (*coord.get_row_num(), *coord.get_col_num());
+                    cells.push((ws.get_name().to_string(), at, f.clone()));
+                }
+            }
+            sheets.push(SavedSheet {
+                host: ws.get_name().to_string(),
+                names: ws
+                    .get_defined_names()
+                    .iter()
+                    .map(|dn| dn.get_address())
+                    .collect(),
+                validations: ws
+                    .get_data_validations()
+                    .map(|dvs| {
+                        dvs.get_data_validation_list()
+                            .iter()
+                            .map(|dv| {
+                                (dv.get_formula1().to_string(), dv.get_formula2().to_string())
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                rules: ws
+                    .get_conditional_formatting_collection()
+                    .iter()
+                    .map(|cf| {
+                        cf.get_conditional_collection()
+                            .iter()
+                            .map(|rule| rule.get_formula().map(|f| f.get_address_str()))
+                            .collect()
+                    })
+                    .collect(),
+            });
+        }
+        let names = book
+            .get_defined_names()
+            .iter()
+            .map(|dn| dn.get_address())
+            .collect();
+        Self {
+            cells,
+            names,
+            sheets,
+        }
+    }
+
+    /// Pass every formula text through `adjust` along with the sheet it
+    /// belongs to. Fails on the first formula that cannot be adjusted, before
+    /// anything in the workbook has been touched.
+    pub(crate) fn adjusted(
+        mut self,
+        adjust: impl Fn(&str, Option<&str>) -> Result<String, String>,
+    ) -> PyResult<Self> {
+        let adjust = |text: &str, host: Option<&str>| {
+            adjust(text, host).map_err(|e| {
+                PyErr::new::<PyValueError, _>(format!("Cannot adjust formula {text}: {e}"))
+            })
+        };
+        for (host, _, f) in &mut self.cells {
+            if !f.get_text().is_empty() {
+                let text = adjust(f.get_text(), Some(host.as_str()))?;
+                f.set_text(text);
+            }
+            if !f.get_reference().is_empty() {
+                let reference = adjust(f.get_reference(), Some(host.as_str()))?;
+                f.set_reference(reference);
+            }
+        }
+        for address in &mut self.names {
+            *address = adjust(address, None)?;
+        }
+        for saved in &mut self.sheets {
+            let host = Some(saved.host.as_str());
+            for address in &mut saved.names {
+                *address = adjust(address, host)?;
+            }
+            for (f1, f2) in &mut saved.validations {
+                *f1 = adjust(f1, host)?;
+                *f2 = adjust(f2, host)?;
+            }
+            for text in saved.rules.iter_mut().flatten().flatten() {
+                *text = adjust(text, host)?;
+            }
+        }
+        Ok(self)
+    }
+
+    /// Remove the data validations and conditional formats of `sheet` whose
+    /// ranges are all deleted by `shift`, from the workbook and from the
+    /// saved formulas alike, so the two stay in step.
+    pub(crate) fn drop_deleted_areas(
+        &mut self,
+        book: &mut Spreadsheet,
+        sheet: &str,
+        shift: LineShift,
+    ) {
+        let kept = |sqref: &str| {
+            sqref.split_whitespace().any(|range| {
+                a1_range_to_bounds(range).map_or(true, |(r1, c1, r2, c2)| {
+                    shift.keeps_area((r1 + 1, c1 + 1, r2 + 1, c2 + 1))
+                })
+            })
+        };
+        let (Some(ws), Some(saved)) = (
+            book.get_sheet_by_name_mut(sheet),
+            self.sheets.iter_mut().find(|s| s.host == sheet),
+        ) else {
+            return;
+        };
+        if let Some(dvs) = ws.get_data_validations_mut() {
+            let list = dvs.get_data_validation_list_mut();
+            let keep: Vec<bool> = list
+                .iter()
+                .map(|dv| kept(&dv.get_sequence_of_references().get_sqref()))
+                .collect();
+            list.retain(flagged(&keep));
+            saved.validations.retain(flagged(&keep));
+        }
+        let cfs = ws.get_conditional_formatting_collection_mut();
+        let keep: Vec<bool> = cfs
+            .iter()
+            .map(|cf| kept(&cf.get_sequence_of_references().get_sqref()))
+            .collect();
+        cfs.retain(flagged(&keep));
+        saved.rules.retain(flagged(&keep));
+    }
+
+    /// Write the adjusted formulas back, each cell's to where `place` puts it
+    /// (if anywhere). Fails if the workbook no longer has the defined names,
+    /// data validations or conditional format rules the formulas were saved
+    /// from.
+    pub(crate) fn restore(
+        self,
+        book: &mut Spreadsheet,
+        place: impl Fn(&str, (u32, u32)) -> Option<(u32, u32)>,
+    ) -> PyResult<()> {
+        let changed = |what: &str, host: Option<&str>| {
+            let whose = host.map_or("the workbook".to_string(), |h| format!("sheet {h}"));
+            PyErr::new::<PyValueError, _>(format!(
+                "The {what} of {whose} changed while adjusting formulas"
+            ))
+        };
+        for (host, cell, f) in self.cells {
+            let cell = place(&host, cell);
+            let (Some((row, col)), Some(ws)) = (cell, book.get_sheet_by_name_mut(&host)) else {
+                continue;
+            };
+            set_formula_obj(ws, (col, row), f);
+        }
+
+        let names = book.get_defined_names_mut();
+        if names.len() != self.names.len() {
+            return Err(changed("defined names", None));
+        }
+        for (dn, address) in names.iter_mut().zip(self.names) {
+            dn.set_address(address);
+        }
+
+        for saved in self.sheets {
+            let host = Some(saved.host.as_str());
+            let Some(ws) = book.get_sheet_by_name_mut(&saved.host) else {
+                return Err(changed("sheets", None));
+            };
+            let names = ws.get_defined_names_mut();
+            if names.len() != saved.names.len() {
+                return Err(changed("defined names", host));
+            }
+            for (dn, address) in names.iter_mut().zip(saved.names) {
+                dn.set_address(address);
+            }
+            let validations = ws
+                .get_data_validations_mut()
+                .map(|dvs| dvs.get_data_validation_list_mut());
+            match validations {
+                Some(list) if list.len() == saved.validations.len() => {
+                    for (dv, (f1, f2)) in list.iter_mut().zip(saved.validations) {
+                        dv.set_formula1(f1);
+                        dv.set_formula2(f2);
+                    }
+                }
+                None if saved.validations.is_empty() => {}
+                _ => return Err(changed("data validations", host)),
+            }
+            let cfs = ws.get_conditional_formatting_collection_mut();
+            if cfs.len() != saved.rules.len() {
+                return Err(changed("conditional formats", host));
+            }
+            for (cf, formulas) in cfs.iter_mut().zip(saved.rules) {
+                let mut rules = cf.get_conditional_collection().to_vec();
+                if rules.len() != formulas.len() {
+                    return Err(changed("conditional format rules", host));
+                }
+                for (rule, text) in rules.iter_mut().zip(formulas) {
+                    if let Some(text) = text {
+                        let mut formula = Formula::default();
+                        formula.set_string_value(text);
+                        rule.set_formula(formula);
+                    }
+                }
+                cf.set_conditional_collection(rules);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A `retain` predicate keeping the items whose flag in `keep` is set.
+fn flagged<T>(keep: &[bool]) -> impl FnMut(&T) -> bool + '_ {
+    let mut flags = keep.iter();
+    move |_| flags.next().copied().unwrap_or(true)
+}
+
+fn shift_lines(
+    book: &mut Spreadsheet,
+    extras: &mut CellExtras,
+    styles: &mut StyleExtras,
+    outlines: &mut Outlines,
+    sheet: &str,
+    shift: LineShift,
+) -> PyResult<()> {
+    let ws = book
+        .get_sheet_by_name(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+    let (what, max, used) = if shift.rows {
+        ("Row", MAX_ROW, ws.get_highest_row())
+    } else {
+        ("Column", MAX_COL, ws.get_highest_column())
+    };
+    if !(1..=max).contains(&shift.at) {
+        return Err(PyErr::new::<PyValueError, _>(format!(
+            "{what} index must be between 1 and {max}"
+        )));
+    }
+    if shift.by == 0 {
+        return Ok(());
+    }
+    if shift.by > 0 && used >= shift.at && i64::from(used) + shift.by > i64::from(max) {
+        return Err(PyErr::new::<PyValueError, _>(
+            "Cannot insert: cells would be pushed off the sheet",
+        ));
+    }
+
+    let mut saved =
+        SavedFormulas::new(book).adjusted(|text, host| shift_formula(text, host, sheet, shift))?;
+    saved.drop_deleted_areas(book, sheet, shift);
+    let (at, n) = (shift.at, shift.by.unsigned_abs() as u32);
+    match (shift.rows, shift.by > 0) {
+        (true, true) => book.insert_new_row(sheet, &at, &n),
+        (true, false) => book.remove_row(sheet, &at, &n),
+        (false, true) => book.insert_new_column_by_index(sheet, &at, &n),
+        (false, false) => book.remove_column_by_index(sheet, &at, &n),
+    }
+    saved.restore(book, |host, cell| {
+        if host == sheet {
+            shift.cell(cell)
+        } else {
+            Some(cell)
+        }
+    })?;
+    extras.shift(sheet, shift);
+    styles.shift_cells(sheet, shift);
+    if let Some(outline) = outlines.get_mut(sheet) {
+        outline.shift(shift);
+    }
+    Ok(())
+}
+
 pub(crate) fn merge_cells(book: &mut Spreadsheet, sheet: &str, range_str: &str) -> PyResult<()> {
     let ws = book
         .get_sheet_by_name_mut(sheet)
@@ -170,6 +1106,17 @@ pub(crate) fn read_freeze_panes_settings(
     Ok(d.into_any().unbind())
 }
 
+/// Remove a sheet's frozen or split pane, keeping its other view settings.
+fn clear_pane(ws: &mut Worksheet) {
+    if let Some(sv) = ws
+        .get_sheet_views_mut()
+        .get_sheet_view_list_mut()
+        .first_mut()
+    {
+        place_pane(sv, None);
+    }
+}
+
 pub(crate) fn set_freeze_panes(
     book: &mut Spreadsheet,
     sheet: &str,
@@ -180,7 +1127,7 @@ pub(crate) fn set_freeze_panes(
         .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
 
     let Some(a1) = a1.map(str::trim).filter(|s| !s.is_empty()) else {
-        ws.get_sheet_views_mut().get_sheet_view_list_mut().clear();
+        clear_pane(ws);
         return Ok(());
     };
 
@@ -193,7 +1140,7 @@ pub(crate) fn set_freeze_panes(
 
     // A1 (or equivalents) means "no freeze".
     if x_split == 0.0 && y_split == 0.0 {
-        ws.get_sheet_views_mut().get_sheet_view_list_mut().clear();
+        clear_pane(ws);
         return Ok(());
     }
 
@@ -221,7 +1168,7 @@ pub(crate) fn set_freeze_panes(
     if sv_list.is_empty() {
         sv_list.push(SheetView::default());
     }
-    sv_list[0].set_pane(pane);
+    place_pane(&mut sv_list[0], Some(pane));
     Ok(())
 }
 
@@ -298,6 +1245,6 @@ pub(crate) fn set_freeze_panes_settings(
     if sv_list.is_empty() {
         sv_list.push(SheetView::default());
     }
-    sv_list[0].set_pane(pane);
+    place_pane(&mut sv_list[0], Some(pane));
     Ok(())
 }
@@ -37,6 +37,32 @@ pub fn a1_to_row_col(a1: &str) -> Result<(u32, u32), String> {
     Ok((row_1 - 1, col - 1))
 }
 
+/// Convert an A1-style range (e.g. "A1:C10", or a single cell "B3") to
+/// 0-based inclusive bounds `(min_row0, min_col0, max_row0, max_col0)`.
+///
+/// Corners may be given in any order; the bounds are normalized.
+pub fn a1_range_to_bounds(range: &str) -> Result<(u32, u32, u32, u32), String> {
+    let trimmed = range.trim();
+    let (start, end) = trimmed.split_once(':').unwrap_or((trimmed, trimmed));
+    let (r1, c1) = a1_to_row_col(start.trim()).map_err(|_| format!("Invalid range: {range}"))?;
+    let (r2, c2) = a1_to_row_col(end.trim()).map_err(|_| format!("Invalid range: {range}"))?;
+    Ok((r1.min(r2), c1.min(c2), r1.max(r2), c1.max(c2)))
+}
+
+/// Split a reference made of one or more areas separated by spaces or commas
+/// (e.g. "A1:A5 C1:C5") into the bounds of each, as [`a1_range_to_bounds`].
+pub fn a1_areas_to_bounds(areas: &str) -> Result<Vec<(u32, u32, u32, u32)>, String> {
+    let bounds = areas
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|area| !area.is_empty())
+        .map(a1_range_to_bounds)
+        .collect::<Result<Vec<_>, _>>()?;
+    if bounds.is_empty() {
+        return Err(format!("Invalid range: {areas}"));
+    }
+    Ok(bounds)
+}
+
 /// Convert a column letter (e.g. "A", "AA") into a 1-based column index.
 pub fn col_letter_to_u32(col_str: &str) -> Result<u32, String> {
     let mut col: u32 = 0;
@@ -103,30 +129,60 @@ pub fn hex_to_argb(hex: &str) -> String {
 // Date helpers
 // ---------------------------------------------------------------------------
 
-pub fn looks_like_date_format(code: &str) -> bool {
-    // Heuristic: date formats typically include year + day tokens.
-    let lc = code.to_ascii_lowercase();
-    lc.contains('y') && lc.contains('d')
+/// Day zero of the workbook's date system.
+///
+/// The 1900 system counts from 1899-12-30 (so that serial 61 is 1900-03-01
+/// despite the phantom 1900-02-29); the 1904 system used by older Mac Excel
+/// counts from 1904-01-01 and has no leap-year quirk.
+fn date_epoch(date1904: bool) -> Option<NaiveDateTime> {
+    let d = if date1904 {
+        NaiveDate::from_ymd_opt(1904, 1, 1)?
+    } else {
+        NaiveDate::from_ymd_opt(1899, 12, 30)?
+    };
+    Some(d.and_time(NaiveTime::MIN))
 }
 
-pub fn excel_serial_to_naive_datetime(serial: f64) -> Option<NaiveDateTime> {
-    // Excel 1900 date system, with the standard 1900 leap-year bug adjustment.
-    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30)?.and_time(NaiveTime::MIN);
+pub fn excel_serial_to_naive_datetime(serial: f64, date1904: bool) -> Option<NaiveDateTime> {
+    let epoch = date_epoch(date1904)?;
     let mut f = serial;
-    if f < 60.0 {
+    if !date1904 && f < 60.0 {
+        // Excel 1900 date system, with the standard 1900 leap-year bug adjustment.
         f += 1.0;
     }
     let total_ms = (f * 86_400_000.0).round() as i64;
     epoch.checked_add_signed(Duration::milliseconds(total_ms))
 }
 
-pub fn naive_datetime_to_excel_serial(dt: NaiveDateTime) -> Option<f64> {
-    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30)?.and_time(NaiveTime::MIN);
+pub fn naive_datetime_to_excel_serial(dt: NaiveDateTime, date1904: bool) -> Option<f64> {
+    let epoch = date_epoch(date1904)?;
     let delta = dt - epoch;
     let total_ms = delta.num_milliseconds();
     Some(total_ms as f64 / 86_400_000.0)
 }
 
+/// Convert the fractional part of a serial into a time of day.
+pub fn excel_serial_to_naive_time(serial: f64) -> Option<NaiveTime> {
+    let day_ms = ((serial - serial.floor()) * 86_400_000.0).round() as u32;
+    // Rounding can push 23:59:59.9995 to a full day; wrap back to midnight.
+    let day_ms = day_ms % 86_400_000;
+    NaiveTime::from_num_seconds_from_midnight_opt(day_ms / 1000, (day_ms % 1000) * 1_000_000)
+}
+
+pub fn naive_time_to_excel_serial(t: NaiveTime) -> f64 {
+    let delta = t - NaiveTime::MIN;
+    delta.num_milliseconds() as f64 / 86_400_000.0
+}
+
+/// Durations are stored as a plain day count, independent of the epoch.
+pub fn excel_serial_to_duration(serial: f64) -> Duration {
+    Duration::milliseconds((serial * 86_400_000.0).round() as i64)
+}
+
+pub fn duration_to_excel_serial(d: Duration) -> f64 {
+    d.num_milliseconds() as f64 / 86_400_000.0
+}
+
 pub fn parse_iso_date(s: &str) -> Option<NaiveDate> {
     NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()
 }
@@ -138,6 +194,33 @@ pub fn parse_iso_datetime(s: &str) -> Option<NaiveDateTime> {
         .or_else(|| NaiveDateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M:%S%.f").ok())
 }
 
+pub fn parse_iso_time(s: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(s, "%H:%M:%S")
+        .ok()
+        .or_else(|| NaiveTime::parse_from_str(s, "%H:%M:%S%.f").ok())
+}
+
+// ---------------------------------------------------------------------------
+// Value helpers
+// ---------------------------------------------------------------------------
+
+/// Error values Excel stores in `t="e"` cells.
+pub const ERROR_TOKENS: [&str; 9] = [
+    "#NULL!",
+    "#DIV/0!",
+    "#VALUE!",
+    "#REF!",
+    "#NAME?",
+    "#NUM!",
+    "#N/A",
+    "#GETTING_DATA",
+    "#SPILL!",
+];
+
+pub fn is_error_token(s: &str) -> bool {
+    ERROR_TOKENS.contains(&s)
+}
+
 // ---------------------------------------------------------------------------
 // Py helpers
 // ---------------------------------------------------------------------------
@@ -0,0 +1,360 @@
+// Sheet view settings: zoom, view mode, what is shown, the top-left visible
+// cell, the active cell and the selection. Only the first sheet view (the one
+// for the first workbook view) is read and written. umya drops the
+// `showRowColHeaders`, `showZeros`, `showFormulas` and `rightToLeft` flags, so
+// they are kept by sheet name alongside the workbook and patched in on save.
+
+use std::collections::HashMap;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use umya_spreadsheet::structs::{
+    Coordinate, Pane, PaneValues, Selection, SheetView, SheetViewValues,
+};
+use umya_spreadsheet::{Spreadsheet, Worksheet};
+
+use crate::utils::{a1_range_to_bounds, a1_to_row_col};
+
+/// Sheet view flags umya does not model.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ViewFlags {
+    pub(crate) show_row_col_headers: bool,
+    pub(crate) show_zeros: bool,
+    pub(crate) show_formulas: bool,
+    pub(crate) right_to_left: bool,
+}
+
+impl Default for ViewFlags {
+    fn default() -> Self {
+        Self {
+            show_row_col_headers: true,
+            show_zeros: true,
+            show_formulas: false,
+            right_to_left: false,
+        }
+    }
+}
+
+impl ViewFlags {
+    pub(crate) fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// View flags by sheet name.
+pub(crate) type SheetViewFlags = HashMap<String, ViewFlags>;
+
+const VIEW_MODES: [&str; 3] = ["normal", "pageLayout", "pageBreakPreview"];
+
+/// Zoom keys, in the order [`zooms`] returns them. `zoom_scale` is the zoom
+/// of the current view; the others are remembered for each view.
+const ZOOM_KEYS: [&str; 4] = [
+    "zoom_scale",
+    "zoom_scale_normal",
+    "zoom_scale_page_layout_view",
+    "zoom_scale_sheet_layout_view",
+];
+
+fn view_mode(view: &SheetViewValues) -> &'static str {
+    match view {
+        SheetViewValues::Normal => "normal",
+        SheetViewValues::PageLayout => "pageLayout",
+        SheetViewValues::PageBreakPreview => "pageBreakPreview",
+    }
+}
+
+fn zooms(sv: &SheetView) -> [u32; 4] {
+    [
+        *sv.get_zoom_scale(),
+        *sv.get_zoom_scale_normal(),
+        *sv.get_zoom_scale_page_layout_view(),
+        *sv.get_zoom_scale_sheet_layout_view(),
+    ]
+}
+
+fn set_zoom(sv: &mut SheetView, key: &str, value: u32) {
+    match key {
+        "zoom_scale" => sv.set_zoom_scale(value),
+        "zoom_scale_normal" => sv.set_zoom_scale_normal(value),
+        "zoom_scale_page_layout_view" => sv.set_zoom_scale_page_layout_view(value),
+        _ => sv.set_zoom_scale_sheet_layout_view(value),
+    };
+}
+
+/// The pane selections belong to: the active pane of a frozen or split view,
+/// or the only pane otherwise.
+fn selection_pane(sv: &SheetView) -> PaneValues {
+    sv.get_pane()
+        .map_or(PaneValues::TopLeft, |pane| pane.get_active_pane().clone())
+}
+
+/// The selection of the active pane, or the first one.
+fn active_selection(sv: &SheetView) -> Option<&Selection> {
+    let pane = selection_pane(sv);
+    let selections = sv.get_selection();
+    selections
+        .iter()
+        .find(|s| *s.get_pane() == pane)
+        .or_else(|| selections.first())
+}
+
+/// A sheet view with `pane` in place of its own (or none), keeping every
+/// other setting. Selections move to the new active pane.
+pub(crate) fn place_pane(sv: &mut SheetView, pane: Option<Pane>) {
+    let mut view = SheetView::default();
+    view.set_tab_selected(*sv.get_tab_selected());
+    view.set_workbook_view_id(*sv.get_workbook_view_id());
+    view.set_view(sv.get_view().clone());
+    view.set_show_grid_lines(*sv.get_show_grid_lines());
+    // Zooms left unset stay unset rather than being written as 0.
+    for (key, zoom) in ZOOM_KEYS.into_iter().zip(zooms(sv)) {
+        if zoom > 0 {
+            set_zoom(&mut view, key, zoom);
+        }
+    }
+    if !sv.get_top_left_cell().is_empty() {
+        view.set_top_left_cell(sv.get_top_left_cell());
+    }
+    if let Some(pane) = pane {
+        view.set_pane(pane);
+    }
+    let target = selection_pane(&view);
+    if let Some(selection) = active_selection(sv) {
+        let mut selection = selection.clone();
+        selection.set_pane(target);
+        view.set_selection(selection);
+    }
+    *sv = view;
+}
+
+/// The first sheet view of a sheet, created if it has none.
+fn first_view(ws: &mut Worksheet) -> &mut SheetView {
+    let views = ws.get_sheet_views_mut().get_sheet_view_list_mut();
+    if views.is_empty() {
+        views.push(SheetView::default());
+    }
+    &mut views[0]
+}
+
+/// A sheet's view settings: `view`, the four zooms, `show_grid_lines`,
+/// `show_row_col_headers`, `show_zeros`, `show_formulas`, `right_to_left`,
+/// `top_left_cell`, `active_cell` and `selection` (a list of ranges).
+pub(crate) fn read_sheet_view(
+    book: &Spreadsheet,
+    flags: &SheetViewFlags,
+    py: Python<'_>,
+    sheet: &str,
+) -> PyResult<Py<PyAny>> {
+    let ws = book
+        .get_sheet_by_name(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+    let d = PyDict::new(py);
+    let default_view = SheetView::default();
+    let sv = ws
+        .get_sheets_views()
+        .get_sheet_view_list()
+        .first()
+        .unwrap_or(&default_view);
+
+    d.set_item("view", view_mode(sv.get_view()))?;
+    for (key, zoom) in ZOOM_KEYS.into_iter().zip(zooms(sv)) {
+        match zoom {
+            // An unset current zoom is 100%; the per-view ones stay unset.
+            0 if key == "zoom_scale" => d.set_item(key, 100)?,
+            0 => d.set_item(key, py.None())?,
+            zoom => d.set_item(key, zoom)?,
+        }
+    }
+    d.set_item("show_grid_lines", *sv.get_show_grid_lines())?;
+    let default_flags = ViewFlags::default();
+    let extra = flags.get(sheet).unwrap_or(&default_flags);
+    d.set_item("show_row_col_headers", extra.show_row_col_headers)?;
+    d.set_item("show_zeros", extra.show_zeros)?;
+    d.set_item("show_formulas", extra.show_formulas)?;
+    d.set_item("right_to_left", extra.right_to_left)?;
+
+    let top_left = sv.get_top_left_cell();
+    d.set_item(
+        "top_left_cell",
+        if top_left.is_empty() { "A1" } else { top_left },
+    )?;
+    let (active, ranges) = match active_selection(sv) {
+        Some(selection) => {
+            let active = selection
+                .get_active_cell()
+                .map_or_else(|| "A1".to_string(), |c| c.to_string());
+            let sqref = selection.get_sequence_of_references().get_sqref();
+            let ranges: Vec<String> = sqref.split_whitespace().map(str::to_string).collect();
+            (active, ranges)
+        }
+        None => ("A1".to_string(), Vec::new()),
+    };
+    let ranges = if ranges.is_empty() {
+        vec![active.clone()]
+    } else {
+        ranges
+    };
+    d.set_item("active_cell", active)?;
+    d.set_item("selection", ranges)?;
+    Ok(d.into_any().unbind())
+}
+
+fn check_cell(a1: &str) -> PyResult<String> {
+    let a1 = a1.trim().to_ascii_uppercase();
+    a1_to_row_col(&a1).map_err(|e| PyErr::new::<PyValueError, _>(e))?;
+    Ok(a1)
+}
+
+fn check_range(range: &str) -> PyResult<String> {
+    let range = range.trim().to_ascii_uppercase();
+    if range.contains(':') {
+        a1_range_to_bounds(&range).map_err(|e| PyErr::new::<PyValueError, _>(e))?;
+        Ok(range)
+    } else {
+        check_cell(&range)
+    }
+}
+
+/// Update a sheet's view settings from a dict with any of the keys
+/// [`read_sheet_view`] returns. Zooms run from 10 to 400 (`None` unsets a
+/// per-view zoom). `selection` is a range or list of ranges; without an
+/// `active_cell` the first range's top-left cell becomes active, and an
+/// `active_cell` alone selects just that cell.
+pub(crate) fn set_sheet_view(
+    book: &mut Spreadsheet,
+    flags: &mut SheetViewFlags,
+    sheet: &str,
+    props: &Bound<'_, PyAny>,
+) -> PyResult<()> {
+    let dict = props
+        .cast::<PyDict>()
+        .map_err(|_| PyErr::new::<PyValueError, _>("sheet view must be a dict"))?;
+    let ws = book
+        .get_sheet_by_name_mut(sheet)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("Unknown sheet: {sheet}")))?;
+
+    // Validate everything before changing anything.
+    let view = match dict.get_item("view")? {
+        Some(v) => {
+            let mode: String = v.extract()?;
+            Some(match mode.as_str() {
+                "normal" => SheetViewValues::Normal,
+                "pageLayout" => SheetViewValues::PageLayout,
+                "pageBreakPreview" => SheetViewValues::PageBreakPreview,
+                _ => {
+                    return Err(PyErr::new::<PyValueError, _>(format!(
+                        "view must be one of {}",
+                        VIEW_MODES.join(", ")
+                    )))
+                }
+            })
+        }
+        None => None,
+    };
+    let mut zoom_updates = Vec::new();
+    for key in ZOOM_KEYS {
+        let Some(v) = dict.get_item(key)? else {
+            continue;
+        };
+        let zoom: Option<u32> = v.extract()?;
+        if zoom.is_none() && key == "zoom_scale" {
+            return Err(PyErr::new::<PyValueError, _>("zoom_scale cannot be None"));
+        }
+        if zoom.is_some_and(|z| !(10..=400).contains(&z)) {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "{key} must be between 10 and 400"
+            )));
+        }
+        zoom_updates.push((key, zoom.unwrap_or(0)));
+    }
+    let top_left = dict
+        .get_item("top_left_cell")?
+        .map(|v| v.extract::<String>())
+        .transpose()?
+        .map(|a1| check_cell(&a1))
+        .transpose()?;
+    let active = dict
+        .get_item("active_cell")?
+        .map(|v| v.extract::<String>())
+        .transpose()?
+        .map(|a1| check_cell(&a1))
+        .transpose()?;
+    let ranges = match dict.get_item("selection")? {
+        Some(v) => {
+            let ranges: Vec<String> = match v.extract::<String>() {
+                Ok(sqref) => sqref.split_whitespace().map(str::to_string).collect(),
+                Err(_) => v.extract()?,
+            };
+            let ranges = ranges
+                .iter()
+                .map(|r| check_range(r))
+                .collect::<PyResult<Vec<String>>>()?;
+            if ranges.is_empty() {
+                return Err(PyErr::new::<PyValueError, _>(
+                    "selection must include at least one range",
+                ));
+            }
+            Some(ranges)
+        }
+        None => None,
+    };
+    let mut bools = Vec::new();
+    for key in [
+        "show_grid_lines",
+        "show_row_col_headers",
+        "show_zeros",
+        "show_formulas",
+        "right_to_left",
+    ] {
+        if let Some(v) = dict.get_item(key)? {
+            bools.push((key, v.extract::<bool>()?));
+        }
+    }
+
+    let sv = first_view(ws);
+    if let Some(view) = view {
+        sv.set_view(view);
+    }
+    for (key, zoom) in zoom_updates {
+        set_zoom(sv, key, zoom);
+    }
+    if let Some(a1) = top_left {
+        sv.set_top_left_cell(a1);
+    }
+    if active.is_some() || ranges.is_some() {
+        let ranges = ranges.unwrap_or_else(|| active.iter().cloned().collect());
+        let active = match active {
+            Some(a1) => a1,
+            None => ranges[0].split(':').next().unwrap_or("A1").to_string(),
+        };
+        let mut cell = Coordinate::default();
+        cell.set_coordinate(&active);
+        let mut selection = Selection::default();
+        selection.set_pane(selection_pane(sv));
+        selection.set_active_cell(cell);
+        selection
+            .get_sequence_of_references_mut()
+            .set_sqref(ranges.join(" "));
+        sv.get_selection_mut().clear();
+        sv.set_selection(selection);
+    }
+
+    let extra = flags.entry(sheet.to_string()).or_default();
+    for (key, value) in bools {
+        match key {
+            "show_grid_lines" => {
+                sv.set_show_grid_lines(value);
+            }
+            "show_row_col_headers" => extra.show_row_col_headers = value,
+            "show_zeros" => extra.show_zeros = value,
+            "show_formulas" => extra.show_formulas = value,
+            _ => extra.right_to_left = value,
+        }
+    }
+    if extra.is_default() {
+        flags.remove(sheet);
+    }
+    Ok(())
+}
@@ -1,13 +1,23 @@
 use pyo3::exceptions::{PyIOError, PyValueError};
 use pyo3::prelude::*;
 
+use std::collections::HashMap;
+use std::io::{self, Cursor};
 use std::path::Path;
 
 use umya_spreadsheet::{new_file, reader, writer, Spreadsheet};
 
+use crate::cell_ops::{CellExtras, ReadOptions};
+use crate::format_ops::StyleExtras;
+use crate::named_style_ops::NamedStyles;
+use crate::package_ops::Package;
+use crate::palette::Palette;
+use crate::structural_ops::Outlines;
+use crate::view_ops::SheetViewFlags;
 use crate::{
-    cell_ops, comment_ops, conditional_format_ops, data_validation_ops, format_ops, hyperlink_ops,
-    image_ops, structural_ops, worksheet,
+    autofit_ops, calc_ops, cell_ops, comment_ops, conditional_format_ops, data_validation_ops,
+    format_ops, hyperlink_ops, image_ops, named_style_ops, package_ops, range_ops, sheet_ops,
+    structural_ops, view_ops, worksheet,
 };
 
 /// Low-level Rust workbook handle wrapping umya-spreadsheet.
@@ -17,6 +27,70 @@ use crate::{
 #[pyclass(unsendable)]
 pub struct RustWorkbook {
     book: Spreadsheet,
+    /// Serials count from 1904-01-01 instead of 1899-12-30 (`workbookPr/@date1904`).
+    date1904: bool,
+    /// First sheet shown in the tab bar (`workbookView/@firstSheet`), which umya drops.
+    first_sheet: u32,
+    /// Formula cells read as their cached results.
+    data_only: bool,
+    /// Rich text cells read as runs instead of plain strings.
+    rich_text: bool,
+    /// Dynamic-array flags and rich text run alignments, which umya drops.
+    extras: CellExtras,
+    /// Color palette, path gradients and named styles, which umya does not model.
+    styles: StyleExtras,
+    /// Row and column outline grouping, which umya drops.
+    outlines: Outlines,
+    /// Headings, zeros, formulas and right-to-left view flags, which umya drops.
+    view_flags: SheetViewFlags,
+}
+
+impl RustWorkbook {
+    fn read_options(&self) -> ReadOptions<'_> {
+        ReadOptions {
+            date1904: self.date1904,
+            data_only: self.data_only,
+            rich_text: self.rich_text,
+            extras: &self.extras,
+        }
+    }
+
+    /// Patch what umya drops into its saved package.
+    fn patch_package(&self, pkg: &mut Package) -> io::Result<()> {
+        if self.date1904 {
+            package_ops::write_date1904(pkg)?;
+        }
+        // Sheets removed since it was set may leave it past the last one.
+        let last_sheet = self.book.get_sheet_collection().len().saturating_sub(1) as u32;
+        let first_sheet = self.first_sheet.min(last_sheet);
+        if first_sheet > 0 {
+            package_ops::write_first_sheet(pkg, first_sheet)?;
+        }
+        let extras = self.extras.live(&self.book);
+        if !extras.dynamic_arrays.is_empty() {
+            package_ops::write_dynamic_arrays(pkg, &extras.dynamic_arrays)?;
+        }
+        if !extras.run_alignments.is_empty() {
+            package_ops::write_run_alignments(pkg, &extras.run_alignments)?;
+        }
+        if self.styles.named.is_custom() {
+            let (records, cells) = self.styles.named.records();
+            package_ops::write_named_styles(pkg, &records, &cells)?;
+        }
+        if !self.styles.cell_xfs.is_empty() {
+            package_ops::write_cell_xf_extras(pkg, &self.styles.cell_xfs)?;
+        }
+        if !self.styles.gradient_paths.is_empty() {
+            package_ops::write_gradient_paths(pkg, &self.styles.gradient_paths)?;
+        }
+        if self.outlines.values().any(|o| !o.is_default()) {
+            package_ops::write_outlines(pkg, &self.outlines)?;
+        }
+        if !self.view_flags.is_empty() {
+            package_ops::write_view_flags(pkg, &self.view_flags)?;
+        }
+        Ok(())
+    }
 }
 
 #[pymethods]
@@ -34,15 +108,79 @@ impl RustWorkbook {
                 let _ = book.remove_sheet(0);
             }
         }
-        Self { book }
+        Self {
+            book,
+            date1904: false,
+            first_sheet: 0,
+            data_only: false,
+            // Rich text in a new workbook was written deliberately; hand it back as such.
+            rich_text: true,
+            extras: CellExtras::default(),
+            styles: StyleExtras::default(),
+            outlines: Outlines::new(),
+            view_flags: SheetViewFlags::new(),
+        }
     }
 
     #[staticmethod]
-    pub fn open(path: &str) -> PyResult<Self> {
+    #[pyo3(signature = (path, data_only = false, rich_text = false))]
+    pub fn open(path: &str, data_only: bool, rich_text: bool) -> PyResult<Self> {
         let p = Path::new(path);
         let book = reader::xlsx::read(p)
             .map_err(|e| PyErr::new::<PyIOError, _>(format!("Failed to open: {e}")))?;
-        Ok(Self { book })
+        let mut pkg = Package::open(p)
+            .map_err(|e| PyErr::new::<PyIOError, _>(format!("Failed to open: {e}")))?;
+        let date1904 = package_ops::read_date1904(&mut pkg)
+            .map_err(|e| PyErr::new::<PyIOError, _>(format!("Failed to open: {e}")))?;
+        let first_sheet = package_ops::read_first_sheet(&mut pkg)
+            .map_err(|e| PyErr::new::<PyIOError, _>(format!("Failed to open: {e}")))?;
+        let extras = CellExtras {
+            dynamic_arrays: package_ops::read_dynamic_arrays(&mut pkg)
+                .map_err(|e| PyErr::new::<PyIOError, _>(format!("Failed to open: {e}")))?,
+            run_alignments: package_ops::read_run_alignments(&mut pkg)
+                .map_err(|e| PyErr::new::<PyIOError, _>(format!("Failed to open: {e}")))?,
+        };
+        let styles = StyleExtras {
+            palette: Palette::new(
+                package_ops::read_theme_colors(&mut pkg)
+                    .map_err(|e| PyErr::new::<PyIOError, _>(format!("Failed to open: {e}")))?,
+                package_ops::read_indexed_colors(&mut pkg)
+                    .map_err(|e| PyErr::new::<PyIOError, _>(format!("Failed to open: {e}")))?,
+            ),
+            gradient_paths: package_ops::read_gradient_paths(&mut pkg)
+                .map_err(|e| PyErr::new::<PyIOError, _>(format!("Failed to open: {e}")))?,
+            named: NamedStyles::new(
+                package_ops::read_named_styles(&mut pkg)
+                    .map_err(|e| PyErr::new::<PyIOError, _>(format!("Failed to open: {e}")))?,
+                package_ops::read_cell_styles(&mut pkg)
+                    .map_err(|e| PyErr::new::<PyIOError, _>(format!("Failed to open: {e}")))?,
+            ),
+            cell_xfs: package_ops::read_cell_xf_extras(&mut pkg)
+                .map_err(|e| PyErr::new::<PyIOError, _>(format!("Failed to open: {e}")))?,
+        };
+        let outlines = package_ops::read_outlines(&mut pkg)
+            .map_err(|e| PyErr::new::<PyIOError, _>(format!("Failed to open: {e}")))?;
+        let view_flags = package_ops::read_view_flags(&mut pkg)
+            .map_err(|e| PyErr::new::<PyIOError, _>(format!("Failed to open: {e}")))?;
+        Ok(Self {
+            book,
+            date1904,
+            first_sheet,
+            data_only,
+            rich_text,
+            extras,
+            styles,
+            outlines,
+            view_flags,
+        })
+    }
+
+    pub fn get_date1904(&self) -> bool {
+        self.date1904
+    }
+
+    pub fn set_date1904(&mut self, value: bool) {
+        self.date1904 = value;
     }
 
     pub fn sheet_names(&self) -> Vec<String> {
@@ -68,15 +206,116 @@ impl RustWorkbook {
         self.book
             .remove_sheet_by_name(name)
             .map_err(|e| PyErr::new::<PyValueError, _>(format!("{e}")))?;
+        self.extras.remove_sheet(name);
+        self.styles.remove_sheet(name);
+        self.outlines.remove(name);
+        self.view_flags.remove(name);
         Ok(())
     }
 
+    /// Rename a sheet, rewriting references to it across the workbook.
+    pub fn rename_sheet(&mut self, old: &str, new: &str) -> PyResult<()> {
+        sheet_ops::rename_sheet(
+            &mut self.book,
+            &mut self.extras,
+            &mut self.styles,
+            &mut self.outlines,
+            &mut self.view_flags,
+            old,
+            new,
+        )
+    }
+
+    /// Move a sheet to 0-based position `index` in the tab order.
+    pub fn move_sheet(&mut self, name: &str, index: i64) -> PyResult<()> {
+        sheet_ops::move_sheet(&mut self.book, name, index)
+    }
+
+    /// Add a copy of sheet `name` as the last sheet, named `new_name`.
+    pub fn copy_sheet(&mut self, name: &str, new_name: &str) -> PyResult<()> {
+        let copy = sheet_ops::sheet_copy(
+            &self.book,
+            &self.extras,
+            &self.styles,
+            &self.outlines,
+            &self.view_flags,
+            name,
+        )?;
+        sheet_ops::add_sheet_copy(
+            &mut self.book,
+            &mut self.extras,
+            &mut self.styles,
+            &mut self.outlines,
+            &mut self.view_flags,
+            copy,
+            new_name,
+        )
+    }
+
+    /// Add a copy of sheet `name` as the last sheet of another workbook.
+    pub fn copy_sheet_to(
+        &self,
+        name: &str,
+        target: &Bound<'_, RustWorkbook>,
+        new_name: &str,
+    ) -> PyResult<()> {
+        let mut target = target.try_borrow_mut().map_err(|_| {
+            PyErr::new::<PyValueError, _>("Use copy_sheet to copy within a workbook")
+        })?;
+        let target = &mut *target;
+        let copy = sheet_ops::sheet_copy(
+            &self.book,
+            &self.extras,
+            &self.styles,
+            &self.outlines,
+            &self.view_flags,
+            name,
+        )?;
+        sheet_ops::add_sheet_copy(
+            &mut target.book,
+            &mut target.extras,
+            &mut target.styles,
+            &mut target.outlines,
+            &mut target.view_flags,
+            copy,
+            new_name,
+        )
+    }
+
+    /// State (visible, hidden or veryHidden), tab color and selection of a sheet.
+    pub fn read_sheet_properties(&self, py: Python<'_>, sheet: &str) -> PyResult<Py<PyAny>> {
+        sheet_ops::read_sheet_properties(&self.book, &self.styles, py, sheet)
+    }
+
+    pub fn set_sheet_properties(&mut self, sheet: &str, props: &Bound<'_, PyAny>) -> PyResult<()> {
+        sheet_ops::set_sheet_properties(&mut self.book, sheet, props)
+    }
+
+    /// Active sheet, first sheet in the tab bar and selected sheets.
+    pub fn read_workbook_view(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        sheet_ops::read_workbook_view(&self.book, self.first_sheet, py)
+    }
+
+    pub fn set_active_sheet(&mut self, index: usize) -> PyResult<()> {
+        sheet_ops::set_active_sheet(&mut self.book, index)
+    }
+
+    pub fn set_first_sheet(&mut self, index: u32) -> PyResult<()> {
+        sheet_ops::check_first_sheet(&self.book, index)?;
+        self.first_sheet = index;
+        Ok(())
+    }
+
+    pub fn set_selected_sheets(&mut self, names: Vec<String>) -> PyResult<()> {
+        sheet_ops::set_selected_sheets(&mut self.book, names)
+    }
+
     // =========================================================================
     // Phase 1: Core cell R/W
     // =========================================================================
 
     pub fn read_cell_value(&self, py: Python<'_>, sheet: &str, a1: &str) -> PyResult<Py<PyAny>> {
-        cell_ops::read_cell_value(&self.book, py, sheet, a1)
+        cell_ops::read_cell_value(&self.book, self.read_options(), py, sheet, a1)
     }
 
     pub fn write_cell_value(
@@ -85,7 +324,44 @@ impl RustWorkbook {
         a1: &str,
         payload: &Bound<'_, PyAny>,
     ) -> PyResult<()> {
-        cell_ops::write_cell_value(&mut self.book, sheet, a1, payload)
+        cell_ops::write_cell_value(
+            &mut self.book,
+            self.date1904,
+            &mut self.extras,
+            sheet,
+            a1,
+            payload,
+        )
+    }
+
+    pub fn read_range_values(
+        &self,
+        py: Python<'_>,
+        sheet: &str,
+        range: &str,
+    ) -> PyResult<Py<PyAny>> {
+        cell_ops::read_range_values(&self.book, self.read_options(), py, sheet, range)
+    }
+
+    pub fn write_range_values(
+        &mut self,
+        sheet: &str,
+        top_left: &str,
+        rows: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        cell_ops::write_range_values(
+            &mut self.book,
+            self.date1904,
+            &mut self.extras,
+            sheet,
+            top_left,
+            rows,
+        )
+    }
+
+    /// Array formula anchors on a sheet, mapped to the range they fill.
+    pub fn array_formula_ranges(&self, sheet: &str) -> PyResult<HashMap<String, String>> {
+        cell_ops::array_formula_ranges(&self.book, sheet)
     }
 
     pub fn sheet_max_row(&self, sheet: &str) -> PyResult<u32> {
@@ -96,12 +372,22 @@ impl RustWorkbook {
         worksheet::sheet_max_column(&self.book, sheet)
     }
 
+    // =========================================================================
+    // Calculation
+    // =========================================================================
+
+    /// Evaluate all formulas and store their results as cached values.
+    /// Returns the cells skipped because of circular references.
+    pub fn calculate(&mut self) -> Vec<String> {
+        calc_ops::calculate(&mut self.book, self.date1904)
+    }
+
     // =========================================================================
     // Phase 2: Formatting
     // =========================================================================
 
     pub fn read_cell_format(&self, py: Python<'_>, sheet: &str, a1: &str) -> PyResult<Py<PyAny>> {
-        format_ops::read_cell_format(&self.book, py, sheet, a1)
+        format_ops::read_cell_format(&self.book, &self.styles, py, sheet, a1)
     }
 
     pub fn write_cell_format(
@@ -110,11 +396,11 @@ impl RustWorkbook {
         a1: &str,
         format_dict: &Bound<'_, PyAny>,
     ) -> PyResult<()> {
-        format_ops::write_cell_format(&mut self.book, sheet, a1, format_dict)
+        format_ops::write_cell_format(&mut self.book, &mut self.styles, sheet, a1, format_dict)
     }
 
     pub fn read_cell_border(&self, py: Python<'_>, sheet: &str, a1: &str) -> PyResult<Py<PyAny>> {
-        format_ops::read_cell_border(&self.book, py, sheet, a1)
+        format_ops::read_cell_border(&self.book, &self.styles, py, sheet, a1)
     }
 
     pub fn write_cell_border(
@@ -123,7 +409,66 @@ impl RustWorkbook {
         a1: &str,
         border_dict: &Bound<'_, PyAny>,
     ) -> PyResult<()> {
-        format_ops::write_cell_border(&mut self.book, sheet, a1, border_dict)
+        format_ops::write_cell_border(&mut self.book, &mut self.styles, sheet, a1, border_dict)
+    }
+
+    /// Apply a format dict to every cell of a range or multi-area reference.
+    pub fn write_range_format(
+        &mut self,
+        sheet: &str,
+        range: &str,
+        format_dict: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        format_ops::write_range_format(&mut self.book, &mut self.styles, sheet, range, format_dict)
+    }
+
+    /// Apply a border dict to a range; with `outline`, only its outer box and
+    /// inner grid lines.
+    #[pyo3(signature = (sheet, range, border_dict, outline = false))]
+    pub fn write_range_border(
+        &mut self,
+        sheet: &str,
+        range: &str,
+        border_dict: &Bound<'_, PyAny>,
+        outline: bool,
+    ) -> PyResult<()> {
+        format_ops::write_range_border(
+            &mut self.book,
+            &mut self.styles,
+            sheet,
+            range,
+            border_dict,
+            outline,
+        )
+    }
+
+    // =========================================================================
+    // Named styles
+    // =========================================================================
+
+    /// Names of the workbook's named styles, "Normal" first.
+    pub fn named_styles(&self) -> Vec<String> {
+        named_style_ops::named_styles(&self.styles)
+    }
+
+    #[pyo3(signature = (name, format_dict, border_dict = None))]
+    pub fn define_named_style(
+        &mut self,
+        name: &str,
+        format_dict: &Bound<'_, PyAny>,
+        border_dict: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<()> {
+        named_style_ops::define_named_style(
+            &mut self.book,
+            &mut self.styles,
+            name,
+            format_dict,
+            border_dict,
+        )
+    }
+
+    pub fn apply_named_style(&mut self, sheet: &str, range: &str, name: &str) -> PyResult<()> {
+        named_style_ops::apply_named_style(&mut self.book, &mut self.styles, sheet, range, name)
     }
 
     // =========================================================================
@@ -146,6 +491,273 @@ impl RustWorkbook {
         structural_ops::set_column_width(&mut self.book, sheet, col_str, width)
     }
 
+    /// Fit column widths (in characters) to the text their cells display.
+    pub fn autofit_columns(
+        &mut self,
+        sheet: &str,
+        columns: Option<Vec<String>>,
+        min: f64,
+        max: f64,
+    ) -> PyResult<()> {
+        autofit_ops::autofit_columns(&mut self.book, self.date1904, sheet, columns, (min, max))
+    }
+
+    /// Fit row heights (in points) to their lines of text, wrapped where the
+    /// cell wraps.
+    pub fn autofit_rows(
+        &mut self,
+        sheet: &str,
+        rows: Option<Vec<u32>>,
+        min: f64,
+        max: f64,
+    ) -> PyResult<()> {
+        autofit_ops::autofit_rows(&mut self.book, self.date1904, sheet, rows, (min, max))
+    }
+
+    pub fn read_row_hidden(&self, sheet: &str, row: u32) -> PyResult<bool> {
+        structural_ops::read_row_hidden(&self.book, sheet, row)
+    }
+
+    pub fn set_row_hidden(&mut self, sheet: &str, row: u32, hidden: bool) -> PyResult<()> {
+        structural_ops::set_row_hidden(&mut self.book, sheet, row, hidden)
+    }
+
+    pub fn read_column_hidden(&self, sheet: &str, col_str: &str) -> PyResult<bool> {
+        structural_ops::read_column_hidden(&self.book, sheet, col_str)
+    }
+
+    pub fn set_column_hidden(&mut self, sheet: &str, col_str: &str, hidden: bool) -> PyResult<()> {
+        structural_ops::set_column_hidden(&mut self.book, sheet, col_str, hidden)
+    }
+
+    /// Outline level and `collapsed` flag of a row.
+    pub fn read_row_outline(&self, sheet: &str, row: u32) -> PyResult<(u8, bool)> {
+        structural_ops::read_row_outline(&self.book, &self.outlines, sheet, row)
+    }
+
+    pub fn set_row_outline_level(
+        &mut self,
+        sheet: &str,
+        start: u32,
+        end: u32,
+        level: u8,
+    ) -> PyResult<()> {
+        structural_ops::set_row_outline_level(
+            &mut self.book,
+            &mut self.outlines,
+            sheet,
+            (start, end),
+            level,
+        )
+    }
+
+    pub fn set_row_collapsed(&mut self, sheet: &str, row: u32, collapsed: bool) -> PyResult<()> {
+        structural_ops::set_row_collapsed(&mut self.book, &mut self.outlines, sheet, row, collapsed)
+    }
+
+    /// Group rows `start..=end`, hiding them and collapsing the group if `hidden`.
+    pub fn group_rows(
+        &mut self,
+        sheet: &str,
+        start: u32,
+        end: u32,
+        level: u8,
+        hidden: bool,
+    ) -> PyResult<()> {
+        structural_ops::group_rows(
+            &mut self.book,
+            &mut self.outlines,
+            sheet,
+            (start, end),
+            level,
+            hidden,
+        )
+    }
+
+    pub fn read_column_outline(&self, sheet: &str, col_str: &str) -> PyResult<(u8, bool)> {
+        structural_ops::read_column_outline(&self.book, &self.outlines, sheet, col_str)
+    }
+
+    pub fn set_column_outline_level(
+        &mut self,
+        sheet: &str,
+        start: &str,
+        end: &str,
+        level: u8,
+    ) -> PyResult<()> {
+        structural_ops::set_column_outline_level(
+            &mut self.book,
+            &mut self.outlines,
+            sheet,
+            (start, end),
+            level,
+        )
+    }
+
+    pub fn set_column_collapsed(
+        &mut self,
+        sheet: &str,
+        col_str: &str,
+        collapsed: bool,
+    ) -> PyResult<()> {
+        structural_ops::set_column_collapsed(
+            &mut self.book,
+            &mut self.outlines,
+            sheet,
+            col_str,
+            collapsed,
+        )
+    }
+
+    pub fn group_columns(
+        &mut self,
+        sheet: &str,
+        start: &str,
+        end: &str,
+        level: u8,
+        hidden: bool,
+    ) -> PyResult<()> {
+        structural_ops::group_columns(
+            &mut self.book,
+            &mut self.outlines,
+            sheet,
+            (start, end),
+            level,
+            hidden,
+        )
+    }
+
+    pub fn read_outline_properties(&self, py: Python<'_>, sheet: &str) -> PyResult<Py<PyAny>> {
+        structural_ops::read_outline_properties(&self.book, &self.outlines, py, sheet)
+    }
+
+    pub fn set_outline_properties(
+        &mut self,
+        sheet: &str,
+        props: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        structural_ops::set_outline_properties(&self.book, &mut self.outlines, sheet, props)
+    }
+
+    pub fn read_row_format(&self, py: Python<'_>, sheet: &str, row: u32) -> PyResult<Py<PyAny>> {
+        structural_ops::read_row_format(&self.book, &self.styles, py, sheet, row)
+    }
+
+    pub fn write_row_format(
+        &mut self,
+        sheet: &str,
+        row: u32,
+        format_dict: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        structural_ops::write_row_format(&mut self.book, &mut self.styles, sheet, row, format_dict)
+    }
+
+    pub fn read_column_format(
+        &self,
+        py: Python<'_>,
+        sheet: &str,
+        col_str: &str,
+    ) -> PyResult<Py<PyAny>> {
+        structural_ops::read_column_format(&self.book, &self.styles, py, sheet, col_str)
+    }
+
+    pub fn write_column_format(
+        &mut self,
+        sheet: &str,
+        col_str: &str,
+        format_dict: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        structural_ops::write_column_format(
+            &mut self.book,
+            &mut self.styles,
+            sheet,
+            col_str,
+            format_dict,
+        )
+    }
+
+    /// Insert `amount` rows before row `idx`, adjusting references to them.
+    pub fn insert_rows(&mut self, sheet: &str, idx: u32, amount: u32) -> PyResult<()> {
+        structural_ops::insert_rows(
+            &mut self.book,
+            &mut self.extras,
+            &mut self.styles,
+            &mut self.outlines,
+            sheet,
+            idx,
+            amount,
+        )
+    }
+
+    pub fn delete_rows(&mut self, sheet: &str, idx: u32, amount: u32) -> PyResult<()> {
+        structural_ops::delete_rows(
+            &mut self.book,
+            &mut self.extras,
+            &mut self.styles,
+            &mut self.outlines,
+            sheet,
+            idx,
+            amount,
+        )
+    }
+
+    /// Insert `amount` columns before 1-based column `idx`.
+    pub fn insert_cols(&mut self, sheet: &str, idx: u32, amount: u32) -> PyResult<()> {
+        structural_ops::insert_cols(
+            &mut self.book,
+            &mut self.extras,
+            &mut self.styles,
+            &mut self.outlines,
+            sheet,
+            idx,
+            amount,
+        )
+    }
+
+    pub fn delete_cols(&mut self, sheet: &str, idx: u32, amount: u32) -> PyResult<()> {
+        structural_ops::delete_cols(
+            &mut self.book,
+            &mut self.extras,
+            &mut self.styles,
+            &mut self.outlines,
+            sheet,
+            idx,
+            amount,
+        )
+    }
+
+    /// Move a range so its top-left cell lands on `dest`, as cut and paste.
+    pub fn move_range(&mut self, sheet: &str, src: &str, dest: &str) -> PyResult<()> {
+        range_ops::move_range(
+            &mut self.book,
+            &mut self.extras,
+            &mut self.styles,
+            sheet,
+            src,
+            dest,
+        )
+    }
+
+    /// Copy a range to `dest`, possibly on another sheet. `what` is
+    /// `"values"`, `"formats"` or `"all"`.
+    pub fn copy_range(
+        &mut self,
+        src_sheet: &str,
+        src: &str,
+        dst_sheet: &str,
+        dest: &str,
+        what: &str,
+    ) -> PyResult<()> {
+        range_ops::copy_range(
+            &mut self.book,
+            &mut self.extras,
+            &mut self.styles,
+            (src_sheet, src),
+            (dst_sheet, dest),
+            what,
+        )
+    }
+
     pub fn merge_cells(&mut self, sheet: &str, range_str: &str) -> PyResult<()> {
         structural_ops::merge_cells(&mut self.book, sheet, range_str)
     }
@@ -178,6 +790,15 @@ impl RustWorkbook {
         structural_ops::set_freeze_panes_settings(&mut self.book, sheet, settings)
     }
 
+    /// Zoom, view mode, shown elements, active cell and selection of a sheet.
+    pub fn read_sheet_view(&self, py: Python<'_>, sheet: &str) -> PyResult<Py<PyAny>> {
+        view_ops::read_sheet_view(&self.book, &self.view_flags, py, sheet)
+    }
+
+    pub fn set_sheet_view(&mut self, sheet: &str, props: &Bound<'_, PyAny>) -> PyResult<()> {
+        view_ops::set_sheet_view(&mut self.book, &mut self.view_flags, sheet, props)
+    }
+
     // =========================================================================
     // Tier 2: Hyperlinks
     // =========================================================================
@@ -266,8 +887,16 @@ impl RustWorkbook {
     }
 
     pub fn save(&self, path: &str) -> PyResult<()> {
-        let p = Path::new(path);
-        writer::xlsx::write(&self.book, p)
+        // umya's output is patched in memory and written out once, so a failed
+        // save leaves any file already at `path` untouched.
+        let mut buffer = Cursor::new(Vec::new());
+        writer::xlsx::write_writer(&self.book, &mut buffer)
+            .map_err(|e| PyErr::new::<PyIOError, _>(format!("Failed to save: {e}")))?;
+        Package::new(buffer.into_inner())
+            .and_then(|mut pkg| {
+                self.patch_package(&mut pkg)?;
+                pkg.save(Path::new(path))
+            })
             .map_err(|e| PyErr::new::<PyIOError, _>(format!("Failed to save: {e}")))
     }
 }
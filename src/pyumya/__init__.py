@@ -4,17 +4,37 @@ Powered by Rust's umya-spreadsheet via PyO3.
 """
 
 from pyumya.cell import Cell
-from pyumya.styles import Alignment, Border, Font, PatternFill, Side
+from pyumya.formula import ArrayFormula
+from pyumya.rich_text import CellRichText, InlineFont, TextBlock
+from pyumya.styles import (
+    Alignment,
+    Border,
+    Font,
+    GradientFill,
+    NamedStyle,
+    PatternFill,
+    Protection,
+    Side,
+    Stop,
+)
 from pyumya.workbook import Workbook, load_workbook
 from pyumya.worksheet import Worksheet
 
 __all__ = [
     "Alignment",
+    "ArrayFormula",
     "Border",
     "Cell",
+    "CellRichText",
     "Font",
+    "GradientFill",
+    "InlineFont",
+    "NamedStyle",
     "PatternFill",
+    "Protection",
     "Side",
+    "Stop",
+    "TextBlock",
     "Workbook",
     "Worksheet",
     "load_workbook",
@@ -6,18 +6,88 @@ This is a small, openpyxl-inspired API layer over the Rust backend.
 from __future__ import annotations
 
 from dataclasses import dataclass
-from datetime import date, datetime
+from datetime import date, datetime, time, timedelta
 from typing import TYPE_CHECKING, Any
 
-from pyumya.styles import Alignment, Border, Font, PatternFill, Side, normalize_rgb
+from pyumya.formula import ArrayFormula
+from pyumya.rich_text import CellRichText
+from pyumya.styles import (
+    Alignment,
+    Border,
+    Font,
+    GradientFill,
+    NamedStyle,
+    PatternFill,
+    Protection,
+    Side,
+    Stop,
+    color_reference,
+    normalize_rgb,
+)
 
 
 if TYPE_CHECKING:  # pragma: no cover
     from pyumya.worksheet import Worksheet
 
 
+ERROR_CODES = frozenset(
+    {
+        "#NULL!",
+        "#DIV/0!",
+        "#VALUE!",
+        "#REF!",
+        "#NAME?",
+        "#NUM!",
+        "#N/A",
+        "#GETTING_DATA",
+        "#SPILL!",
+    }
+)
+
+
 def _is_error_token(s: str) -> bool:
-    return s == "#N/A" or (s.startswith("#") and s.endswith("!"))
+    return s in ERROR_CODES
+
+
+def payload_to_value(payload: dict[str, Any]) -> Any:
+    """Convert a typed payload from the Rust layer into a Python value."""
+    t = payload.get("type", "blank")
+
+    if t == "blank":
+        return None
+
+    if t == "string":
+        return str(payload.get("value", ""))
+
+    if t == "number":
+        v = payload.get("value")
+        if isinstance(v, float) and v.is_integer():
+            return int(v)
+        return v
+
+    if t == "boolean":
+        return bool(payload.get("value"))
+
+    if t == "error":
+        return str(payload.get("value", ""))
+
+    if t == "rich_text":
+        return CellRichText._from_payload(payload)
+
+    if t == "formula":
+        f = payload.get("formula") or payload.get("value") or ""
+        f = str(f)
+        f = f if f.startswith("=") else f"={f}"
+        if payload.get("formula_type") == "array":
+            return ArrayFormula(str(payload["ref"]), f, bool(payload.get("dynamic", False)))
+        return f
+
+    if t in ("date", "datetime", "time", "duration"):
+        # The Rust layer hands back native date/datetime/time/timedelta objects.
+        return payload.get("value")
+
+    # Fallback: return raw.
+    return payload.get("value")
 
 
 @dataclass
@@ -43,53 +113,27 @@ class Cell:
         t = payload.get("type", "blank")
         return {
             "string": "s",
+            "rich_text": "s",
             "number": "n",
             "boolean": "b",
             "formula": "f",
             "error": "e",
             "date": "d",
             "datetime": "d",
+            "time": "d",
+            "duration": "d",
             "blank": "n",
         }.get(t, "s")
 
     @property
-    def value(self) -> Any:
-        payload = self._ws._rust_read_cell_payload(self._coordinate)
-        t = payload.get("type", "blank")
-
-        if t == "blank":
-            return None
-
-        if t == "string":
-            return str(payload.get("value", ""))
-
-        if t == "number":
-            v = payload.get("value")
-            if isinstance(v, float) and v.is_integer():
-                return int(v)
-            return v
-
-        if t == "boolean":
-            return bool(payload.get("value"))
-
-        if t == "error":
-            return str(payload.get("value", ""))
-
-        if t == "formula":
-            f = payload.get("formula") or payload.get("value") or ""
-            f = str(f)
-            return f if f.startswith("=") else f"={f}"
-
-        if t == "date":
-            s = str(payload.get("value", ""))
-            return date.fromisoformat(s)
-
-        if t == "datetime":
-            s = str(payload.get("value", ""))
-            return datetime.fromisoformat(s)
+    def is_date(self) -> bool:
+        """True if the number format displays a date, time or duration."""
+        kind = self._ws._rust_read_cell_format(self._coordinate).get("number_format_kind")
+        return kind in ("date", "time", "datetime", "duration")
 
-        # Fallback: return raw.
-        return payload.get("value")
+    @property
+    def value(self) -> Any:
+        return payload_to_value(self._ws._rust_read_cell_payload(self._coordinate))
 
     @value.setter
     def value(self, val: Any) -> None:
@@ -104,9 +148,25 @@ class Cell:
         elif isinstance(val, datetime):
             if val.tzinfo is not None:
                 raise ValueError("Timezone-aware datetimes are not supported")
-            payload = {"type": "datetime", "value": val.isoformat()}
+            payload = {"type": "datetime", "value": val}
         elif isinstance(val, date):
-            payload = {"type": "date", "value": val.isoformat()}
+            payload = {"type": "date", "value": val}
+        elif isinstance(val, time):
+            if val.tzinfo is not None:
+                raise ValueError("Timezone-aware times are not supported")
+            payload = {"type": "time", "value": val}
+        elif isinstance(val, timedelta):
+            payload = {"type": "duration", "value": val}
+        elif isinstance(val, CellRichText):
+            payload = val._to_payload()
+        elif isinstance(val, ArrayFormula):
+            payload = {
+                "type": "formula",
+                "formula": val.text,
+                "formula_type": "array",
+                "ref": val.ref,
+                "dynamic": val.dynamic,
+            }
         elif isinstance(val, str):
             if val.startswith("="):
                 payload = {"type": "formula", "formula": val}
@@ -116,11 +176,23 @@ class Cell:
                 payload = {"type": "string", "value": val}
         else:
             raise TypeError(
-                "Cell.value must be one of: str, int, float, bool, None, datetime, date"
+                "Cell.value must be one of: str, int, float, bool, None, datetime, date, "
+                "time, timedelta, ArrayFormula, CellRichText"
             )
 
         self._ws._rust_write_cell_payload(self._coordinate, payload)
 
+    @property
+    def cached_value(self) -> Any:
+        """Last calculated result of a formula cell, or the plain value otherwise.
+
+        ``None`` if the file was saved without calculating the formula.
+        """
+        payload = self._ws._rust_read_cell_payload(self._coordinate)
+        if payload.get("type") == "formula":
+            return payload_to_value(payload.get("cached_value") or {"type": "blank"})
+        return payload_to_value(payload)
+
     # ------------------------------------------------------------------
     # Formatting
     # ------------------------------------------------------------------
@@ -128,77 +200,42 @@ class Cell:
     @property
     def font(self) -> Font:
         d = self._ws._rust_read_cell_format(self._coordinate)
-        return Font(
-            name=str(d.get("font_name", "Calibri")),
-            size=float(d.get("font_size", 11.0)),
-            bold=bool(d.get("bold", False)),
-            italic=bool(d.get("italic", False)),
-            underline=str(d.get("underline", "none")),
-            strikethrough=bool(d.get("strikethrough", False)),
-            color=normalize_rgb(str(d.get("font_color", "000000"))),
-        )
+        return font_from_format(d)
 
     @font.setter
     def font(self, font: Font) -> None:
-        self._ws._rust_write_cell_format(
-            self._coordinate,
-            {
-                "bold": bool(font.bold),
-                "italic": bool(font.italic),
-                "underline": str(font.underline),
-                "strikethrough": bool(font.strikethrough),
-                "font_name": str(font.name),
-                "font_size": float(font.size),
-                "font_color": normalize_rgb(font.color),
-            },
-        )
+        self._ws._rust_write_cell_format(self._coordinate, font_format(font))
 
     @property
-    def fill(self) -> PatternFill:
+    def fill(self) -> PatternFill | GradientFill:
         d = self._ws._rust_read_cell_format(self._coordinate)
-        bg = d.get("bg_color")
-        fill_type = d.get("fill_type")
-        if fill_type is None:
-            fill_type = "solid" if bg is not None else "none"
-        return PatternFill(fill_type=str(fill_type), fgColor=normalize_rgb(str(bg or "000000")))
+        return fill_from_format(d)
 
     @fill.setter
-    def fill(self, fill: PatternFill) -> None:
-        if fill.fill_type == "none":
-            self._ws._rust_write_cell_format(self._coordinate, {"fill_type": "none"})
-            return
-        self._ws._rust_write_cell_format(
-            self._coordinate,
-            {
-                "fill_type": str(fill.fill_type),
-                "bg_color": normalize_rgb(fill.fgColor),
-            },
-        )
+    def fill(self, fill: PatternFill | GradientFill) -> None:
+        self._ws._rust_write_cell_format(self._coordinate, fill_format(fill))
 
     @property
     def alignment(self) -> Alignment:
         d = self._ws._rust_read_cell_format(self._coordinate)
-        return Alignment(
-            horizontal=str(d.get("h_align", "general")),
-            vertical=str(d.get("v_align", "bottom")),
-            wrap_text=bool(d.get("wrap", False)),
-            text_rotation=int(d.get("rotation", 0)),
-            indent=int(d.get("indent", 0)),
-        )
+        return alignment_from_format(d)
 
     @alignment.setter
     def alignment(self, alignment: Alignment) -> None:
-        self._ws._rust_write_cell_format(
-            self._coordinate,
-            {
-                "h_align": str(alignment.horizontal),
-                "v_align": str(alignment.vertical),
-                "wrap": bool(alignment.wrap_text),
-                "rotation": int(alignment.text_rotation),
-                "indent": int(alignment.indent),
-            },
+        self._ws._rust_write_cell_format(self._coordinate, alignment_format(alignment))
+
+    @property
+    def protection(self) -> Protection:
+        d = self._ws._rust_read_cell_format(self._coordinate)
+        return Protection(
+            locked=bool(d.get("locked", True)),
+            hidden=bool(d.get("hidden", False)),
         )
 
+    @protection.setter
+    def protection(self, protection: Protection) -> None:
+        self._ws._rust_write_cell_format(self._coordinate, protection_format(protection))
+
     @property
     def number_format(self) -> str:
         d = self._ws._rust_read_cell_format(self._coordinate)
@@ -219,56 +256,230 @@ class Cell:
             return Side(
                 style=str(raw.get("style", "none")),
                 color=normalize_rgb(str(raw.get("color", "000000"))),
+                theme=raw.get("color_theme"),
+                tint=float(raw.get("color_tint", 0.0)),
+                indexed=raw.get("color_indexed"),
             )
 
-        diag_up = side("diagonal_up")
-        diag_down = side("diagonal_down")
-
-        diagonal_up_enabled = diag_up.style != "none"
-        diagonal_down_enabled = diag_down.style != "none"
-        diag = diag_up if diagonal_up_enabled else diag_down
-
         return Border(
             left=side("left"),
             right=side("right"),
             top=side("top"),
             bottom=side("bottom"),
-            diagonal=diag,
-            diagonalUp=diagonal_up_enabled,
-            diagonalDown=diagonal_down_enabled,
+            diagonal=side("diagonal"),
+            diagonalUp="diagonal_up" in d,
+            diagonalDown="diagonal_down" in d,
+            outline=bool(d.get("outline", True)),
         )
 
     @border.setter
     def border(self, border: Border) -> None:
-        def sd(s: Side) -> dict[str, str]:
-            return {"style": str(s.style), "color": normalize_rgb(str(s.color))}
-
-        diag_side = border.diagonal
-        diag_on = diag_side.style != "none"
-
-        # If a diagonal style is set but neither direction flag is enabled,
-        # default to diagonalUp so the border is not silently dropped.
-        diag_up_flag = bool(border.diagonalUp)
-        diag_down_flag = bool(border.diagonalDown)
-        if diag_on and not (diag_up_flag or diag_down_flag):
-            diag_up_flag = True
-
-        diag_up = diag_up_flag and diag_on
-        diag_down = diag_down_flag and diag_on
-
-        diag_side_dict = sd(diag_side)
-        none_payload = {"style": "none", "color": diag_side_dict["color"]}
-        diag_up_payload = diag_side_dict if diag_up else none_payload
-        diag_down_payload = diag_side_dict if diag_down else none_payload
-
-        self._ws._rust_write_cell_border(
-            self._coordinate,
-            {
-                "left": sd(border.left),
-                "right": sd(border.right),
-                "top": sd(border.top),
-                "bottom": sd(border.bottom),
-                "diagonal_up": diag_up_payload,
-                "diagonal_down": diag_down_payload,
-            },
+        self._ws._rust_write_cell_border(self._coordinate, border_format(border))
+
+    @property
+    def style(self) -> str:
+        """Name of the cell's named style."""
+        d = self._ws._rust_read_cell_format(self._coordinate)
+        return str(d.get("style_name", "Normal"))
+
+    @style.setter
+    def style(self, style: str | NamedStyle) -> None:
+        self._ws.apply_named_style(self._coordinate, style)
+
+
+def font_from_format(d: dict[str, Any]) -> Font:
+    """The `Font` described by a format dict."""
+    return Font(
+        name=str(d.get("font_name", "Calibri")),
+        size=float(d.get("font_size", 11.0)),
+        bold=bool(d.get("bold", False)),
+        italic=bool(d.get("italic", False)),
+        underline=str(d.get("underline", "none")),
+        strikethrough=bool(d.get("strikethrough", False)),
+        color=normalize_rgb(str(d.get("font_color", "000000"))),
+        theme=d.get("font_color_theme"),
+        tint=float(d.get("font_color_tint", 0.0)),
+        indexed=d.get("font_color_indexed"),
+        vertAlign=d.get("vert_align"),
+        family=d.get("font_family"),
+        charset=d.get("font_charset"),
+        scheme=d.get("font_scheme"),
+        outline=bool(d.get("outline", False)),
+        shadow=bool(d.get("shadow", False)),
+        condense=bool(d.get("condense", False)),
+        extend=bool(d.get("extend", False)),
+    )
+
+
+def fill_from_format(d: dict[str, Any]) -> PatternFill | GradientFill:
+    """The fill described by a format dict."""
+    gradient = d.get("gradient")
+    if gradient is not None:
+        return GradientFill(
+            type=str(gradient.get("type", "linear")),
+            degree=float(gradient.get("degree", 0.0)),
+            left=float(gradient.get("left", 0.0)),
+            right=float(gradient.get("right", 0.0)),
+            top=float(gradient.get("top", 0.0)),
+            bottom=float(gradient.get("bottom", 0.0)),
+            stop=[
+                Stop(
+                    color=str(s.get("color", "000000")),
+                    position=float(s["position"]),
+                    theme=s.get("color_theme"),
+                    tint=float(s.get("color_tint", 0.0)),
+                    indexed=s.get("color_indexed"),
+                )
+                for s in gradient.get("stops", [])
+            ],
         )
+    bg = d.get("bg_color")
+    fill_type = d.get("fill_type")
+    if fill_type is None:
+        fill_type = "solid" if bg is not None else "none"
+    return PatternFill(
+        fill_type=str(fill_type),
+        fgColor=normalize_rgb(str(bg or "000000")),
+        theme=d.get("bg_color_theme"),
+        tint=float(d.get("bg_color_tint", 0.0)),
+        indexed=d.get("bg_color_indexed"),
+        bgColor=d.get("pattern_bg_color"),
+    )
+
+
+def alignment_from_format(d: dict[str, Any]) -> Alignment:
+    """The `Alignment` described by a format dict."""
+    return Alignment(
+        horizontal=str(d.get("h_align", "general")),
+        vertical=str(d.get("v_align", "bottom")),
+        wrap_text=bool(d.get("wrap", False)),
+        text_rotation=int(d.get("rotation", 0)),
+        indent=int(d.get("indent", 0)),
+        shrink_to_fit=bool(d.get("shrink_to_fit", False)),
+        justifyLastLine=bool(d.get("justify_last_line", False)),
+        relativeIndent=int(d.get("relative_indent", 0)),
+        readingOrder=int(d.get("reading_order", 0)),
+    )
+
+
+def font_format(font: Font) -> dict[str, Any]:
+    """Format-dict keys for a `Font`."""
+    return {
+        "bold": bool(font.bold),
+        "italic": bool(font.italic),
+        "underline": str(font.underline),
+        "strikethrough": bool(font.strikethrough),
+        "font_name": str(font.name),
+        "font_size": float(font.size),
+        "font_color": normalize_rgb(font.color),
+        **color_reference("font_color", font.theme, font.tint, font.indexed),
+        "vert_align": font.vertAlign,
+        "outline": bool(font.outline),
+        "shadow": bool(font.shadow),
+        "condense": bool(font.condense),
+        "extend": bool(font.extend),
+        **{
+            key: value
+            for key, value in (
+                ("font_family", font.family),
+                ("font_charset", font.charset),
+                ("font_scheme", font.scheme),
+            )
+            if value is not None
+        },
+    }
+
+
+def fill_format(fill: PatternFill | GradientFill) -> dict[str, Any]:
+    """Format-dict keys for a `PatternFill` or `GradientFill`."""
+    if isinstance(fill, GradientFill):
+        gradient: dict[str, Any] = {
+            "type": fill.type,
+            "stops": [
+                {
+                    "position": float(s.position),
+                    "color": normalize_rgb(s.color),
+                    **color_reference("color", s.theme, s.tint, s.indexed),
+                }
+                for s in fill.stop
+            ],
+        }
+        if fill.type == "path":
+            gradient.update(
+                left=float(fill.left),
+                right=float(fill.right),
+                top=float(fill.top),
+                bottom=float(fill.bottom),
+            )
+        else:
+            gradient["degree"] = float(fill.degree)
+        return {"gradient": gradient}
+    if fill.fill_type == "none":
+        return {"fill_type": "none"}
+    return {
+        "fill_type": str(fill.fill_type),
+        "bg_color": normalize_rgb(fill.fgColor),
+        **color_reference("bg_color", fill.theme, fill.tint, fill.indexed),
+        **({} if fill.bgColor is None else {"pattern_bg_color": fill.bgColor}),
+    }
+
+
+def alignment_format(alignment: Alignment) -> dict[str, Any]:
+    """Format-dict keys for an `Alignment`."""
+    return {
+        "h_align": str(alignment.horizontal),
+        "v_align": str(alignment.vertical),
+        "wrap": bool(alignment.wrap_text),
+        "rotation": int(alignment.text_rotation),
+        "indent": int(alignment.indent),
+        "shrink_to_fit": bool(alignment.shrink_to_fit),
+        "justify_last_line": bool(alignment.justifyLastLine),
+        "relative_indent": int(alignment.relativeIndent),
+        "reading_order": int(alignment.readingOrder),
+    }
+
+
+def protection_format(protection: Protection) -> dict[str, Any]:
+    """Format-dict keys for a `Protection`."""
+    return {"locked": bool(protection.locked), "hidden": bool(protection.hidden)}
+
+
+def side_format(side: Side) -> dict[str, Any]:
+    """Border dict entry for one `Side`."""
+    return {
+        "style": str(side.style),
+        "color": normalize_rgb(str(side.color)),
+        **color_reference("color", side.theme, side.tint, side.indexed),
+    }
+
+
+def border_format(border: Border) -> dict[str, Any]:
+    """Border dict for a `Border`."""
+    sd = side_format
+    diag_side = border.diagonal
+    diag_on = diag_side.style != "none"
+
+    # If a diagonal style is set but neither direction flag is enabled,
+    # default to diagonalUp so the border is not silently dropped.
+    diag_up_flag = bool(border.diagonalUp)
+    diag_down_flag = bool(border.diagonalDown)
+    if diag_on and not (diag_up_flag or diag_down_flag):
+        diag_up_flag = True
+
+    diag_up = diag_up_flag and diag_on
+    diag_down = diag_down_flag and diag_on
+
+    diag_side_dict = sd(diag_side)
+    none_payload: dict[str, Any] = {"style": "none", "color": diag_side_dict["color"]}
+    diag_up_payload = diag_side_dict if diag_up else none_payload
+    diag_down_payload = diag_side_dict if diag_down else none_payload
+
+    return {
+        "left": sd(border.left),
+        "right": sd(border.right),
+        "top": sd(border.top),
+        "bottom": sd(border.bottom),
+        "diagonal_up": diag_up_payload,
+        "diagonal_down": diag_down_payload,
+        "outline": bool(border.outline),
+    }
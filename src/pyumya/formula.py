@@ -0,0 +1,23 @@
+"""Formula value objects for pyumya."""
+
+from __future__ import annotations
+
+from dataclasses import dataclass
+
+
+@dataclass
+class ArrayFormula:
+    """An array formula anchored at the top-left cell of `ref`.
+
+    Mirrors openpyxl's `ArrayFormula`. `dynamic` marks a dynamic-array
+    (spilling) formula as written by Excel 365.
+    """
+
+    ref: str
+    text: str
+    dynamic: bool = False
+
+    def __post_init__(self) -> None:
+        self.ref = self.ref.replace("$", "").upper()
+        if not self.text.startswith("="):
+            self.text = f"={self.text}"
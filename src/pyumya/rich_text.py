@@ -0,0 +1,108 @@
+"""Rich text (multi-run) cell values for pyumya.
+
+Mirrors openpyxl's ``CellRichText``, ``TextBlock`` and ``InlineFont``.
+"""
+
+from __future__ import annotations
+
+from dataclasses import dataclass, fields
+from typing import Any
+
+from pyumya.styles import normalize_rgb
+
+
+# InlineFont attribute -> payload key.
+_FONT_KEYS = {
+    "rFont": "name",
+    "sz": "size",
+    "b": "bold",
+    "i": "italic",
+    "u": "underline",
+    "strike": "strike",
+    "color": "color",
+    "vertAlign": "vert_align",
+}
+
+
+@dataclass
+class InlineFont:
+    """Font of a single rich text run. Unset attributes inherit the cell's font."""
+
+    rFont: str | None = None
+    sz: float | None = None
+    b: bool | None = None
+    i: bool | None = None
+    u: str | None = None
+    strike: bool | None = None
+    color: str | None = None
+    vertAlign: str | None = None
+
+    def __post_init__(self) -> None:
+        if self.color is not None:
+            self.color = normalize_rgb(self.color)
+        if self.vertAlign not in (None, "baseline", "superscript", "subscript"):
+            raise ValueError(f"Invalid vertAlign: {self.vertAlign!r}")
+
+    def _to_payload(self) -> dict[str, Any]:
+        return {
+            _FONT_KEYS[f.name]: getattr(self, f.name)
+            for f in fields(self)
+            if getattr(self, f.name) is not None
+        }
+
+    @classmethod
+    def _from_payload(cls, d: dict[str, Any]) -> InlineFont:
+        return cls(**{attr: d[key] for attr, key in _FONT_KEYS.items() if key in d})
+
+
+@dataclass
+class TextBlock:
+    """A run of text with its own font."""
+
+    font: InlineFont
+    text: str
+
+    def __str__(self) -> str:
+        return self.text
+
+
+class CellRichText(list):
+    """A cell value made of plain strings and styled ``TextBlock`` runs.
+
+    ``CellRichText("Total: ", TextBlock(InlineFont(b=True), "42"))``
+    """
+
+    def __init__(self, *args: Any) -> None:
+        if len(args) == 1 and isinstance(args[0], (list, tuple)):
+            args = tuple(args[0])
+        for item in args:
+            if not isinstance(item, (str, TextBlock)):
+                raise TypeError("CellRichText items must be str or TextBlock")
+        super().__init__(args)
+
+    def __str__(self) -> str:
+        return "".join(str(item) for item in self)
+
+    def __repr__(self) -> str:
+        return f"CellRichText({list.__repr__(self)})"
+
+    def _to_payload(self) -> dict[str, Any]:
+        runs: list[dict[str, Any]] = []
+        for item in self:
+            if isinstance(item, TextBlock):
+                runs.append({"text": item.text, "font": item.font._to_payload()})
+            else:
+                runs.append({"text": item})
+        return {"type": "rich_text", "value": str(self), "runs": runs}
+
+    @classmethod
+    def _from_payload(cls, payload: dict[str, Any]) -> CellRichText:
+        items: list[str | TextBlock] = []
+        for run in payload.get("runs", []):
+            text = str(run.get("text", ""))
+            font = run.get("font")
+            if font is None:
+                items.append(text)
+            else:
+                items.append(TextBlock(InlineFont._from_payload(font), text))
+        return cls(items)
@@ -8,6 +8,17 @@ from __future__ import annotations
 from dataclasses import dataclass, field
 
 
+def color_reference(
+    prefix: str, theme: int | None, tint: float, indexed: int | None
+) -> dict[str, int | float]:
+    """Format-dict keys for a theme or indexed color reference."""
+    if indexed is not None:
+        return {f"{prefix}_indexed": int(indexed)}
+    if theme is not None:
+        return {f"{prefix}_theme": int(theme), f"{prefix}_tint": float(tint)}
+    return {}
+
+
 def normalize_rgb(color: str) -> str:
     """Normalize a color string to 6-digit uppercase RGB (RRGGBB).
 
@@ -27,8 +38,25 @@ def normalize_rgb(color: str) -> str:
     return s
 
 
+UNDERLINES = ("none", "single", "double", "singleAccounting", "doubleAccounting")
+
+
 @dataclass
 class Font:
+    """Cell font.
+
+    ``color`` is always the resolved RGB. When the color refers to the workbook
+    theme (``theme``, with an optional ``tint`` between -1 and 1) or to the
+    legacy palette (``indexed``), the reference is kept so that writing the
+    font back preserves it.
+
+    ``underline`` is ``"none"``, ``"single"``, ``"double"`` or an accounting
+    variant (``"singleAccounting"``, ``"doubleAccounting"``). ``vertAlign``
+    raises or lowers the text (``"superscript"``, ``"subscript"``).
+    ``family``, ``charset`` and ``scheme`` (``"major"``/``"minor"`` for the
+    theme's heading and body fonts) are kept as read when ``None``.
+    """
+
     name: str = "Calibri"
     size: float = 11.0
     bold: bool = False
@@ -36,27 +64,110 @@ class Font:
     underline: str = "none"
     strikethrough: bool = False
     color: str = "000000"
+    theme: int | None = None
+    tint: float = 0.0
+    indexed: int | None = None
+    vertAlign: str | None = None
+    family: int | None = None
+    charset: int | None = None
+    scheme: str | None = None
+    outline: bool = False
+    shadow: bool = False
+    condense: bool = False
+    extend: bool = False
 
     def __post_init__(self) -> None:
         self.color = normalize_rgb(self.color)
+        if self.underline not in UNDERLINES:
+            raise ValueError(f"Invalid underline: {self.underline!r}")
+        if self.vertAlign == "baseline":
+            self.vertAlign = None
+        if self.vertAlign not in (None, "superscript", "subscript"):
+            raise ValueError(f"Invalid vertAlign: {self.vertAlign!r}")
+        if self.scheme not in (None, "major", "minor", "none"):
+            raise ValueError(f"Invalid font scheme: {self.scheme!r}")
 
 
 @dataclass
 class PatternFill:
+    """Pattern fill. ``theme``/``tint``/``indexed`` describe ``fgColor`` as in `Font`.
+
+    ``fill_type`` is any Excel pattern (``"solid"``, ``"gray125"``,
+    ``"darkTrellis"``, ...); ``bgColor`` is the color between the pattern's
+    marks.
+    """
+
     fill_type: str = "none"
     fgColor: str = "000000"
+    theme: int | None = None
+    tint: float = 0.0
+    indexed: int | None = None
+    bgColor: str | None = None
 
     def __post_init__(self) -> None:
         self.fgColor = normalize_rgb(self.fgColor)
+        if self.bgColor is not None:
+            self.bgColor = normalize_rgb(self.bgColor)
         # Convenience: if a non-default color is provided, assume solid.
-        if self.fill_type == "none" and self.fgColor != "000000":
+        referenced = self.theme is not None or self.indexed is not None
+        if self.fill_type == "none" and (self.fgColor != "000000" or referenced):
             self.fill_type = "solid"
 
 
+@dataclass
+class Stop:
+    """A gradient color stop at ``position`` (0-1)."""
+
+    color: str
+    position: float
+    theme: int | None = None
+    tint: float = 0.0
+    indexed: int | None = None
+
+    def __post_init__(self) -> None:
+        self.color = normalize_rgb(self.color)
+        if not 0.0 <= self.position <= 1.0:
+            raise ValueError("Stop.position must be between 0 and 1")
+
+
+@dataclass
+class GradientFill:
+    """Gradient fill.
+
+    ``type="linear"`` blends along ``degree``; ``type="path"`` radiates from
+    the rectangle given by ``left``, ``right``, ``top`` and ``bottom`` (0-1).
+    Plain colors in ``stop`` are spread evenly.
+    """
+
+    type: str = "linear"
+    degree: float = 0.0
+    left: float = 0.0
+    right: float = 0.0
+    top: float = 0.0
+    bottom: float = 0.0
+    stop: list[Stop] = field(default_factory=list)
+
+    def __post_init__(self) -> None:
+        if self.type not in ("linear", "path"):
+            raise ValueError(f"Invalid gradient type: {self.type!r}")
+        items = list(self.stop)
+        if any(not isinstance(s, Stop) for s in items):
+            last = max(len(items) - 1, 1)
+            items = [
+                s if isinstance(s, Stop) else Stop(str(s), i / last) for i, s in enumerate(items)
+            ]
+        self.stop = items
+
+
 @dataclass
 class Side:
+    """One border line. ``theme``/``tint``/``indexed`` describe ``color`` as in `Font`."""
+
     style: str = "none"
     color: str = "000000"
+    theme: int | None = None
+    tint: float = 0.0
+    indexed: int | None = None
 
     def __post_init__(self) -> None:
         self.color = normalize_rgb(self.color)
@@ -64,6 +175,14 @@ class Side:
 
 @dataclass
 class Border:
+    """Cell border.
+
+    ``diagonal`` is drawn in the directions enabled by ``diagonalUp`` and
+    ``diagonalDown``. ``outline=False`` stores the border as applying to
+    every cell of a range rather than its outline (the ``<border>``
+    attribute; it does not change how a single cell is drawn).
+    """
+
     left: Side = field(default_factory=Side)
     right: Side = field(default_factory=Side)
     top: Side = field(default_factory=Side)
@@ -73,17 +192,69 @@ class Border:
     # openpyxl-compatible flags for diagonal direction.
     diagonalUp: bool = False
     diagonalDown: bool = False
+    outline: bool = True
 
 
 @dataclass
 class Alignment:
+    """Cell alignment.
+
+    ``text_rotation`` is 0-90 (counterclockwise), 91-180 or -1 to -90
+    (clockwise), or 255 for vertical text (letters stacked top to bottom).
+    ``readingOrder`` is 0 (follow the text), 1 (left-to-right) or 2
+    (right-to-left).
+    """
+
     horizontal: str = "general"
     vertical: str = "bottom"
     wrap_text: bool = False
     text_rotation: int = 0
     indent: int = 0
+    shrink_to_fit: bool = False
+    justifyLastLine: bool = False
+    relativeIndent: int = 0
+    readingOrder: int = 0
 
     def __post_init__(self) -> None:
         # Excel caps indent at 250; Rust layer expects u32.
         if not (0 <= self.indent <= 250):
             raise ValueError("Alignment.indent must be between 0 and 250")
+        if not (-90 <= self.text_rotation <= 180 or self.text_rotation == 255):
+            raise ValueError("Alignment.text_rotation must be between -90 and 180, or 255")
+        if not (-15 <= self.relativeIndent <= 15):
+            raise ValueError("Alignment.relativeIndent must be between -15 and 15")
+        if self.readingOrder not in (0, 1, 2):
+            raise ValueError("Alignment.readingOrder must be 0, 1 or 2")
+
+
+@dataclass
+class Protection:
+    """Cell protection, enforced once the sheet is protected.
+
+    ``locked`` cells cannot be edited; ``hidden`` cells do not show their
+    formula in the formula bar.
+    """
+
+    locked: bool = True
+    hidden: bool = False
+
+
+@dataclass
+class NamedStyle:
+    """A named cell style ("Heading 1", "Currency", custom ones).
+
+    Parts left as ``None`` keep the workbook defaults. Register the style with
+    ``Workbook.add_named_style`` (or just assign it to ``Cell.style``) and
+    apply it by name afterwards.
+    """
+
+    name: str
+    font: Font | None = None
+    fill: PatternFill | GradientFill | None = None
+    border: Border | None = None
+    alignment: Alignment | None = None
+    number_format: str | None = None
+
+    def __post_init__(self) -> None:
+        if not self.name.strip():
+            raise ValueError("NamedStyle.name must not be empty")
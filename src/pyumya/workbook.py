@@ -3,9 +3,11 @@
 from __future__ import annotations
 
 from pathlib import Path
-from typing import Iterator
+from typing import Any, Iterator
 
 from pyumya._rust import RustWorkbook
+from pyumya.cell import alignment_format, border_format, fill_format, font_format
+from pyumya.styles import NamedStyle
 from pyumya.worksheet import Worksheet
 
 
@@ -39,11 +41,118 @@ class Workbook:
         """Return list of sheet names in workbook order."""
         return self._rust.sheet_names()
 
+    @property
+    def active(self) -> Worksheet | None:
+        """The sheet the workbook opens on; set it by sheet, title or index."""
+        names = self.sheetnames
+        index = int(self._rust.read_workbook_view()["active"])
+        return Worksheet(self, names[index]) if index < len(names) else None
+
+    @active.setter
+    def active(self, sheet: Worksheet | str | int) -> None:
+        self._rust.set_active_sheet(self._sheet_index(sheet))
+
+    @property
+    def first_visible_tab(self) -> int:
+        """Index of the first sheet shown in the tab bar."""
+        return int(self._rust.read_workbook_view()["first_sheet"])
+
+    @first_visible_tab.setter
+    def first_visible_tab(self, index: int) -> None:
+        self._rust.set_first_sheet(int(index))
+
+    @property
+    def selected_sheets(self) -> list[str]:
+        """Titles of the selected (grouped) sheets, which include the active one."""
+        return list(self._rust.read_workbook_view()["selected"])
+
+    @selected_sheets.setter
+    def selected_sheets(self, sheets: list[Worksheet | str]) -> None:
+        titles = [s.title if isinstance(s, Worksheet) else str(s) for s in sheets]
+        self._rust.set_selected_sheets(titles)
+
+    def _sheet_index(self, sheet: Worksheet | str | int) -> int:
+        if isinstance(sheet, int):
+            return sheet
+        title = sheet.title if isinstance(sheet, Worksheet) else str(sheet)
+        if title not in self.sheetnames:
+            raise KeyError(f"Worksheet '{title}' does not exist.")
+        return self.sheetnames.index(title)
+
+    @property
+    def date1904(self) -> bool:
+        """Whether date serials use the 1904 date system (older Mac Excel files)."""
+        return bool(self._rust.get_date1904())
+
+    @date1904.setter
+    def date1904(self, value: bool) -> None:
+        self._rust.set_date1904(bool(value))
+
+    @property
+    def named_styles(self) -> list[str]:
+        """Names of the named styles in the workbook, "Normal" first."""
+        return list(self._rust.named_styles())
+
+    def add_named_style(self, style: NamedStyle) -> None:
+        """Register ``style``, or redefine the style of the same name.
+
+        Cells already using a redefined style pick up its new formatting.
+        """
+        fmt: dict[str, Any] = {}
+        if style.font is not None:
+            fmt.update(font_format(style.font))
+        if style.fill is not None:
+            fmt.update(fill_format(style.fill))
+        if style.alignment is not None:
+            fmt.update(alignment_format(style.alignment))
+        if style.number_format is not None:
+            fmt["number_format"] = str(style.number_format)
+        border = None if style.border is None else border_format(style.border)
+        self._rust.define_named_style(style.name, fmt, border)
+
     def create_sheet(self, title: str) -> Worksheet:
         """Create a new worksheet and return it."""
         self._rust.add_sheet(title)
         return Worksheet(self, title)
 
+    def remove(self, worksheet: Worksheet | str) -> None:
+        """Remove a worksheet and everything kept for its cells."""
+        title = worksheet.title if isinstance(worksheet, Worksheet) else str(worksheet)
+        self._rust.remove_sheet(title)
+
+    def move_sheet(self, sheet: Worksheet | str, offset: int = 0) -> None:
+        """Move a sheet ``offset`` places along the tab order (negative moves left)."""
+        title = sheet.title if isinstance(sheet, Worksheet) else str(sheet)
+        index = self.sheetnames.index(title) + int(offset)
+        self._rust.move_sheet(title, index)
+
+    def copy_worksheet(self, source: Worksheet, title: str | None = None) -> Worksheet:
+        """Copy a worksheet, from this or another open workbook, to the end of
+        this workbook and return the copy.
+
+        The copy is titled ``title``, by default the source title with " Copy"
+        appended. Its formulas are kept as written, so when copying between
+        workbooks references to other sheets resolve against this workbook.
+        """
+        new_title = f"{source.title} Copy" if title is None else str(title)
+        if source._workbook._rust is self._rust:
+            self._rust.copy_sheet(source.title, new_title)
+        else:
+            source._workbook._rust.copy_sheet_to(source.title, self._rust, new_title)
+        return Worksheet(self, new_title)
+
+    def calculate(self) -> list[str]:
+        """Evaluate every formula and store the results as cached values.
+
+        Values are then visible through ``Cell.cached_value`` and to readers
+        that do not recalculate (e.g. ``load_workbook(..., data_only=True)``).
+
+        Returns:
+            Cells skipped because they are part of, or depend on, a circular
+            reference, e.g. ``["Sheet1!A1"]``.
+        """
+        return list(self._rust.calculate())
+
     def save(self, filename: str | Path) -> None:
         """Save the workbook to disk."""
         self._rust.save(str(filename))
@@ -67,14 +176,20 @@ class Workbook:
         pass
 
 
-def load_workbook(filename: str | Path) -> Workbook:
+def load_workbook(
+    filename: str | Path, data_only: bool = False, rich_text: bool = False
+) -> Workbook:
     """Open an existing Excel workbook (.xlsx).
 
     Args:
         filename: Path to the .xlsx file.
+        data_only: If True, formula cells read as the result last cached by
+            Excel instead of the formula text.
+        rich_text: If True, cells with mixed formatting read as
+            ``CellRichText``; otherwise as plain strings.
 
     Returns:
         A Workbook object.
     """
-    rust_book = RustWorkbook.open(str(filename))
+    rust_book = RustWorkbook.open(str(filename), data_only=data_only, rich_text=rich_text)
     return Workbook(_rust_book=rust_book)
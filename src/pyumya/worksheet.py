@@ -5,7 +5,31 @@ from __future__ import annotations
 from collections.abc import Iterator
 from typing import TYPE_CHECKING, Any
 
-from pyumya.cell import Cell
+from pyumya.cell import (
+    Cell,
+    alignment_format,
+    alignment_from_format,
+    border_format,
+    fill_format,
+    fill_from_format,
+    font_format,
+    font_from_format,
+    payload_to_value,
+    protection_format,
+    side_format,
+)
+from pyumya.formula import ArrayFormula
+from pyumya.rich_text import CellRichText
+from pyumya.styles import (
+    Alignment,
+    Border,
+    Font,
+    GradientFill,
+    NamedStyle,
+    PatternFill,
+    Protection,
+    Side,
+)
 
 
 if TYPE_CHECKING:  # pragma: no cover
@@ -42,6 +66,23 @@ class Worksheet:
     def title(self) -> str:
         return self._title
 
+    @title.setter
+    def title(self, title: str) -> None:
+        """Rename the sheet. Formulas, defined names, data validations and
+        internal hyperlinks that refer to it follow the new name."""
+        self._workbook._rust.rename_sheet(self._title, str(title))
+        self._title = str(title)
+
+    @property
+    def sheet_state(self) -> str:
+        """``"visible"``, ``"hidden"`` or ``"veryHidden"`` (hidden from Excel's
+        Unhide dialog too)."""
+        return str(self._workbook._rust.read_sheet_properties(self._title)["state"])
+
+    @sheet_state.setter
+    def sheet_state(self, state: str) -> None:
+        self._workbook._rust.set_sheet_properties(self._title, {"state": str(state)})
+
     def __getitem__(self, key: str) -> Cell:
         if not isinstance(key, str):
             raise TypeError("Worksheet keys must be A1 strings")
@@ -58,8 +99,50 @@ class Worksheet:
     def append(self, iterable: Any) -> None:
         values = list(iterable)
         row = self.max_row + 1 if self.max_row > 0 else 1
-        for idx, v in enumerate(values, start=1):
-            self.cell(row=row, column=idx, value=v)
+        self.write_rows([values], min_row=row)
+        if all(v is None for v in values):
+            # Still take up the row, so the next append goes below it.
+            self._rust_write_cell_payload(self._a1_from_row_col(row, 1), {"type": "blank"})
+
+    def write_rows(self, rows: Any, min_row: int = 1, min_col: int = 1) -> None:
+        """Write a block of Python values with its top-left at (min_row, min_col).
+
+        ``rows`` is an iterable of row iterables. Plain values are written in a
+        single call into the Rust layer; rich text and array formulas are then
+        set cell by cell, as ``Cell.value`` would.
+        """
+        block = [list(r) for r in rows]
+        cell_values = [
+            (min_row + r, min_col + c, v)
+            for r, row in enumerate(block)
+            for c, v in enumerate(row)
+            if isinstance(v, (CellRichText, ArrayFormula))
+        ]
+        for row_idx, col_idx, _ in cell_values:
+            block[row_idx - min_row][col_idx - min_col] = None
+        top_left = self._a1_from_row_col(min_row, min_col)
+        self._workbook._rust.write_range_values(self._title, top_left, block)
+        for row_idx, col_idx, value in cell_values:
+            self.cell(row_idx, col_idx).value = value
+
+    def write_shared_formula(self, ref: str, formula: str) -> None:
+        """Write ``formula`` as a shared formula filling ``ref``.
+
+        The formula is stored once on the top-left cell; every other cell in the
+        range reuses it with relative references shifted, as Excel does when a
+        formula is filled down or across.
+        """
+        top_left = ref.replace("$", "").split(":")[0]
+        text = formula if formula.startswith("=") else f"={formula}"
+        self._rust_write_cell_payload(
+            top_left,
+            {"type": "formula", "formula": text, "formula_type": "shared", "ref": ref},
+        )
+
+    @property
+    def array_formulae(self) -> dict[str, str]:
+        """Array formula anchors mapped to the range they fill (like openpyxl)."""
+        return dict(self._workbook._rust.array_formula_ranges(self._title))
 
     def iter_rows(
         self,
@@ -67,7 +150,8 @@ class Worksheet:
         max_row: int | None = None,
         min_col: int = 1,
         max_col: int | None = None,
-    ) -> Iterator[tuple[Cell, ...]]:
+        values_only: bool = False,
+    ) -> Iterator[tuple[Any, ...]]:
         if max_row is None:
             max_row = self.max_row
         if max_col is None:
@@ -75,6 +159,25 @@ class Worksheet:
         if max_row <= 0 or max_col <= 0:
             return iter(())
 
+        if values_only:
+            # One FFI call for the whole block instead of one per cell.
+            top_left = self._a1_from_row_col(min_row, min_col)
+            bottom_right = self._a1_from_row_col(max_row, max_col)
+            grid = self._workbook._rust.read_range_values(
+                self._title, f"{top_left}:{bottom_right}"
+            )
+            # The grid stops at the last used row and column; pad the rest.
+            width = max_col - min_col + 1
+            blank_row = (None,) * width
+
+            def values() -> Iterator[tuple[Any, ...]]:
+                for row in grid:
+                    yield tuple(payload_to_value(p) for p in row) + blank_row[len(row) :]
+                for _ in range(len(grid), max_row - min_row + 1):
+                    yield blank_row
+
+            return values()
+
         def gen() -> Iterator[tuple[Cell, ...]]:
             for r in range(min_row, max_row + 1):
                 row_cells = tuple(self.cell(r, c) for c in range(min_col, max_col + 1))
@@ -103,6 +206,11 @@ class Worksheet:
 
         return gen()
 
+    @property
+    def values(self) -> Iterator[tuple[Any, ...]]:
+        """Iterate over all rows, yielding plain values (like openpyxl)."""
+        return self.iter_rows(values_only=True)
+
     @property
     def max_row(self) -> int:
         return int(self._workbook._rust.sheet_max_row(self._title))
@@ -111,10 +219,132 @@ class Worksheet:
     def max_column(self) -> int:
         return int(self._workbook._rust.sheet_max_column(self._title))
 
+    def format_range(
+        self,
+        ref: str,
+        *,
+        font: Font | None = None,
+        fill: PatternFill | GradientFill | None = None,
+        alignment: Alignment | None = None,
+        number_format: str | None = None,
+        protection: Protection | None = None,
+    ) -> None:
+        """Format every cell of a range in one call.
+
+        ``ref`` is a range (``"B2:F200"``) or several separated by spaces
+        (``"A1:A5 C1:C5"``). Only the parts given are changed.
+        """
+        fmt: dict[str, Any] = {}
+        if font is not None:
+            fmt.update(font_format(font))
+        if fill is not None:
+            fmt.update(fill_format(fill))
+        if alignment is not None:
+            fmt.update(alignment_format(alignment))
+        if number_format is not None:
+            fmt["number_format"] = str(number_format)
+        if protection is not None:
+            fmt.update(protection_format(protection))
+        self._workbook._rust.write_range_format(self._title, ref.strip().upper(), fmt)
+
+    def border_range(
+        self,
+        ref: str,
+        border: Border,
+        *,
+        outline: bool = False,
+        inner_horizontal: Side | None = None,
+        inner_vertical: Side | None = None,
+    ) -> None:
+        """Set the border of every cell of a range (see `format_range`).
+
+        With ``outline=True`` the edges of ``border`` draw only the outer box
+        of each area, and the lines between cells come from
+        ``inner_horizontal`` and ``inner_vertical`` (left as they are when
+        ``None``).
+        """
+        payload = border_format(border)
+        if outline:
+            for key, side in (
+                ("inner_horizontal", inner_horizontal),
+                ("inner_vertical", inner_vertical),
+            ):
+                if side is not None:
+                    payload[key] = side_format(side)
+        self._workbook._rust.write_range_border(
+            self._title, ref.strip().upper(), payload, outline
+        )
+
+    def apply_named_style(self, ref: str, style: str | NamedStyle) -> None:
+        """Apply a named style to a cell or range (see `format_range`).
+
+        The cells take on the style's formatting. A `NamedStyle` that is not
+        yet registered is added to the workbook first.
+        """
+        if isinstance(style, NamedStyle):
+            if style.name not in self._workbook.named_styles:
+                self._workbook.add_named_style(style)
+            style = style.name
+        self._workbook._rust.apply_named_style(self._title, ref.strip().upper(), style)
+
     # ---------------------------------------------------------------------
     # Structural features
     # ---------------------------------------------------------------------
 
+    def insert_rows(self, idx: int, amount: int = 1) -> None:
+        """Insert ``amount`` rows before row ``idx``.
+
+        As in Excel, everything below moves down: cells, formatting, merged
+        ranges, hyperlinks, comments, validations, conditional formats and
+        images. Formulas anywhere in the workbook, including defined names,
+        keep pointing at the cells they referred to.
+        """
+        self._workbook._rust.insert_rows(self._title, int(idx), int(amount))
+
+    def delete_rows(self, idx: int, amount: int = 1) -> None:
+        """Delete ``amount`` rows starting at row ``idx``; references to deleted
+        cells become ``#REF!``."""
+        self._workbook._rust.delete_rows(self._title, int(idx), int(amount))
+
+    def insert_cols(self, idx: int, amount: int = 1) -> None:
+        """Insert ``amount`` columns before column ``idx`` (1-based)."""
+        self._workbook._rust.insert_cols(self._title, int(idx), int(amount))
+
+    def delete_cols(self, idx: int, amount: int = 1) -> None:
+        """Delete ``amount`` columns starting at column ``idx`` (1-based)."""
+        self._workbook._rust.delete_cols(self._title, int(idx), int(amount))
+
+    def move_range(self, cell_range: str, rows: int = 0, cols: int = 0) -> None:
+        """Move a range ``rows`` down and ``cols`` right (negative: up, left).
+
+        This is Excel's cut and paste: values, formatting, merged ranges,
+        comments and hyperlinks move, cells landed on are replaced, and
+        formulas anywhere in the workbook that refer to the moved cells follow
+        them.
+        """
+        row, col = self._row_col_from_a1(cell_range.split(":")[0])
+        dest = self._a1_from_row_col(row + rows, col + cols)
+        self._workbook._rust.move_range(self._title, cell_range.strip().upper(), dest)
+
+    def copy_range(
+        self,
+        cell_range: str,
+        dest: str,
+        what: str = "all",
+        target: Worksheet | None = None,
+    ) -> None:
+        """Copy a range to ``dest`` (its top-left cell) on this sheet or ``target``.
+
+        ``what`` is ``"values"`` (values and formulas), ``"formats"`` (styles
+        and merged ranges) or ``"all"`` (both, plus comments and hyperlinks).
+        Relative references in copied formulas move with them, as when pasting
+        in Excel.
+        """
+        sheet = (target or self)._title
+        self._workbook._rust.copy_range(
+            self._title, cell_range.strip().upper(), sheet, dest.strip().upper(), what
+        )
+
     def merge_cells(self, range_string: str) -> None:
         self._workbook._rust.merge_cells(self._title, str(range_string))
 
@@ -156,6 +386,44 @@ class Worksheet:
     def column_dimensions(self) -> ColumnDimensions:
         return ColumnDimensions(self)
 
+    @property
+    def sheet_properties(self) -> SheetProperties:
+        return SheetProperties(self)
+
+    @property
+    def sheet_view(self) -> SheetView:
+        return SheetView(self)
+
+    def autofit_columns(
+        self,
+        columns: list[str] | None = None,
+        min_width: float = 0.0,
+        max_width: float = 255.0,
+    ) -> None:
+        """Fit column widths to the text their cells display.
+
+        Each cell's value is rendered through its number format and measured in
+        its font. ``columns`` (letters) defaults to every column with content;
+        widths are in characters, clamped to ``min_width``..``max_width``.
+        """
+        letters = None if columns is None else [str(c).strip().upper() for c in columns]
+        self._workbook._rust.autofit_columns(
+            self._title, letters, float(min_width), float(max_width)
+        )
+
+    def autofit_rows(
+        self,
+        rows: list[int] | None = None,
+        min_height: float = 0.0,
+        max_height: float = 409.0,
+    ) -> None:
+        """Fit row heights to their cells' lines of text, wrapping text in
+        wrapping cells to the column width. Heights are in points."""
+        indexes = None if rows is None else [int(r) for r in rows]
+        self._workbook._rust.autofit_rows(
+            self._title, indexes, float(min_height), float(max_height)
+        )
+
     # ---------------------------------------------------------------------
     # Tier 2 features
     # ---------------------------------------------------------------------
@@ -320,15 +588,131 @@ class Worksheet:
     def _rust_set_column_width(self, col_letter: str, width: float) -> None:
         self._workbook._rust.set_column_width(self._title, str(col_letter), float(width))
 
+    def _rust_read_row_hidden(self, row: int) -> bool:
+        return bool(self._workbook._rust.read_row_hidden(self._title, int(row)))
+
+    def _rust_set_row_hidden(self, row: int, hidden: bool) -> None:
+        self._workbook._rust.set_row_hidden(self._title, int(row), bool(hidden))
+
+    def _rust_read_column_hidden(self, col_letter: str) -> bool:
+        return bool(self._workbook._rust.read_column_hidden(self._title, str(col_letter)))
+
+    def _rust_set_column_hidden(self, col_letter: str, hidden: bool) -> None:
+        self._workbook._rust.set_column_hidden(self._title, str(col_letter), bool(hidden))
+
+    def _rust_read_row_outline(self, row: int) -> tuple[int, bool]:
+        level, collapsed = self._workbook._rust.read_row_outline(self._title, int(row))
+        return int(level), bool(collapsed)
+
+    def _rust_read_column_outline(self, col_letter: str) -> tuple[int, bool]:
+        level, collapsed = self._workbook._rust.read_column_outline(self._title, str(col_letter))
+        return int(level), bool(collapsed)
+
+    def _rust_read_row_format(self, row: int) -> dict[str, Any]:
+        return dict(self._workbook._rust.read_row_format(self._title, int(row)))
+
+    def _rust_write_row_format(self, row: int, payload: dict[str, Any]) -> None:
+        self._workbook._rust.write_row_format(self._title, int(row), payload)
+
+    def _rust_read_column_format(self, col_letter: str) -> dict[str, Any]:
+        return dict(self._workbook._rust.read_column_format(self._title, str(col_letter)))
+
+    def _rust_write_column_format(self, col_letter: str, payload: dict[str, Any]) -> None:
+        self._workbook._rust.write_column_format(self._title, str(col_letter), payload)
+
     def _rust_get_merged_ranges(self) -> list[str]:
         return list(self._workbook._rust.get_merged_ranges(self._title))
 
 
-class RowDimension:
+class _DimensionFormat:
+    """Default formatting of a whole row or column.
+
+    Empty cells in the row or column show it, and cells created there start
+    from it. Setting a part also restyles the cells already there.
+    """
+
+    def _read_format(self) -> dict[str, Any]:
+        raise NotImplementedError
+
+    def _write_format(self, payload: dict[str, Any]) -> None:
+        raise NotImplementedError
+
+    @property
+    def customFormat(self) -> bool:
+        return bool(self._read_format())
+
+    @property
+    def font(self) -> Font:
+        return font_from_format(self._read_format())
+
+    @font.setter
+    def font(self, font: Font) -> None:
+        self._write_format(font_format(font))
+
+    @property
+    def fill(self) -> PatternFill | GradientFill:
+        return fill_from_format(self._read_format())
+
+    @fill.setter
+    def fill(self, fill: PatternFill | GradientFill) -> None:
+        self._write_format(fill_format(fill))
+
+    @property
+    def alignment(self) -> Alignment:
+        return alignment_from_format(self._read_format())
+
+    @alignment.setter
+    def alignment(self, alignment: Alignment) -> None:
+        self._write_format(alignment_format(alignment))
+
+    @property
+    def number_format(self) -> str:
+        return str(self._read_format().get("number_format", "General"))
+
+    @number_format.setter
+    def number_format(self, fmt: str) -> None:
+        self._write_format({"number_format": str(fmt)})
+
+
+class RowDimension(_DimensionFormat):
     def __init__(self, ws: Worksheet, idx: int) -> None:
         self._ws = ws
         self._idx = idx
 
+    def _read_format(self) -> dict[str, Any]:
+        return self._ws._rust_read_row_format(self._idx)
+
+    def _write_format(self, payload: dict[str, Any]) -> None:
+        self._ws._rust_write_row_format(self._idx, payload)
+
+    @property
+    def hidden(self) -> bool:
+        return self._ws._rust_read_row_hidden(self._idx)
+
+    @hidden.setter
+    def hidden(self, hidden: bool) -> None:
+        self._ws._rust_set_row_hidden(self._idx, hidden)
+
+    @property
+    def outline_level(self) -> int:
+        return self._ws._rust_read_row_outline(self._idx)[0]
+
+    @outline_level.setter
+    def outline_level(self, level: int) -> None:
+        self._ws._workbook._rust.set_row_outline_level(
+            self._ws.title, self._idx, self._idx, int(level)
+        )
+
+    outlineLevel = outline_level
+
+    @property
+    def collapsed(self) -> bool:
+        return self._ws._rust_read_row_outline(self._idx)[1]
+
+    @collapsed.setter
+    def collapsed(self, collapsed: bool) -> None:
+        self._ws._workbook._rust.set_row_collapsed(self._ws.title, self._idx, bool(collapsed))
+
     @property
     def height(self) -> float | None:
         return self._ws._rust_read_row_height(self._idx)
@@ -349,12 +733,60 @@ class RowDimensions:
     def __getitem__(self, idx: int) -> RowDimension:
         return RowDimension(self._ws, int(idx))
 
+    def group(
+        self, start: int, end: int | None = None, outline_level: int = 1, hidden: bool = False
+    ) -> None:
+        """Group rows ``start..end`` (inclusive) at ``outline_level``.
+
+        With ``hidden`` the group is collapsed, as Excel's outline buttons do.
+        """
+        last = start if end is None else end
+        self._ws._workbook._rust.group_rows(
+            self._ws.title, int(start), int(last), int(outline_level), bool(hidden)
+        )
 
-class ColumnDimension:
+
+class ColumnDimension(_DimensionFormat):
     def __init__(self, ws: Worksheet, letter: str) -> None:
         self._ws = ws
         self._letter = letter
 
+    def _read_format(self) -> dict[str, Any]:
+        return self._ws._rust_read_column_format(self._letter)
+
+    def _write_format(self, payload: dict[str, Any]) -> None:
+        self._ws._rust_write_column_format(self._letter, payload)
+
+    @property
+    def hidden(self) -> bool:
+        return self._ws._rust_read_column_hidden(self._letter)
+
+    @hidden.setter
+    def hidden(self, hidden: bool) -> None:
+        self._ws._rust_set_column_hidden(self._letter, hidden)
+
+    @property
+    def outline_level(self) -> int:
+        return self._ws._rust_read_column_outline(self._letter)[0]
+
+    @outline_level.setter
+    def outline_level(self, level: int) -> None:
+        self._ws._workbook._rust.set_column_outline_level(
+            self._ws.title, self._letter, self._letter, int(level)
+        )
+
+    outlineLevel = outline_level
+
+    @property
+    def collapsed(self) -> bool:
+        return self._ws._rust_read_column_outline(self._letter)[1]
+
+    @collapsed.setter
+    def collapsed(self, collapsed: bool) -> None:
+        self._ws._workbook._rust.set_column_collapsed(
+            self._ws.title, self._letter, bool(collapsed)
+        )
+
     @property
     def width(self) -> float | None:
         return self._ws._rust_read_column_width(self._letter)
@@ -378,6 +810,197 @@ class ColumnDimensions:
             raise KeyError("Column key cannot be empty")
         return ColumnDimension(self._ws, letter)
 
+    def group(
+        self, start: str = "A", end: str | None = None, outline_level: int = 1, hidden: bool = False
+    ) -> None:
+        """Group columns ``start..end`` (letters, inclusive) at ``outline_level``.
+
+        With ``hidden`` the group is collapsed, as Excel's outline buttons do.
+        """
+        first = str(start).strip().upper()
+        last = first if end is None else str(end).strip().upper()
+        self._ws._workbook._rust.group_columns(
+            self._ws.title, first, last, int(outline_level), bool(hidden)
+        )
+
+
+class OutlineProperties:
+    """Where summary rows and columns sit relative to their groups."""
+
+    def __init__(self, ws: Worksheet) -> None:
+        self._ws = ws
+
+    def _read(self) -> dict[str, Any]:
+        return dict(self._ws._workbook._rust.read_outline_properties(self._ws.title))
+
+    @property
+    def summaryBelow(self) -> bool:
+        return bool(self._read()["summary_below"])
+
+    @summaryBelow.setter
+    def summaryBelow(self, value: bool) -> None:
+        self._ws._workbook._rust.set_outline_properties(
+            self._ws.title, {"summary_below": bool(value)}
+        )
+
+    @property
+    def summaryRight(self) -> bool:
+        return bool(self._read()["summary_right"])
+
+    @summaryRight.setter
+    def summaryRight(self, value: bool) -> None:
+        self._ws._workbook._rust.set_outline_properties(
+            self._ws.title, {"summary_right": bool(value)}
+        )
+
+
+class SheetProperties:
+    def __init__(self, ws: Worksheet) -> None:
+        self._ws = ws
+
+    @property
+    def tabColor(self) -> str | None:
+        """Tab color as ``RRGGBB``, or None for the default."""
+        props = self._ws._workbook._rust.read_sheet_properties(self._ws.title)
+        color = props.get("tab_color")
+        return None if color is None else str(color)
+
+    @tabColor.setter
+    def tabColor(self, color: str | None) -> None:
+        value = None if color is None else str(color).lstrip("#").upper()
+        self._ws._workbook._rust.set_sheet_properties(self._ws.title, {"tab_color": value})
+
+    @property
+    def outlinePr(self) -> OutlineProperties:
+        return OutlineProperties(self._ws)
+
+
+class SheetView:
+    """Zoom, view mode, what is shown and the selection of a worksheet.
+
+    Freezing or splitting panes keeps these settings.
+    """
+
+    def __init__(self, ws: Worksheet) -> None:
+        self._ws = ws
+
+    def _read(self) -> dict[str, Any]:
+        return dict(self._ws._workbook._rust.read_sheet_view(self._ws.title))
+
+    def _set(self, key: str, value: Any) -> None:
+        self._ws._workbook._rust.set_sheet_view(self._ws.title, {key: value})
+
+    @property
+    def view(self) -> str:
+        """``"normal"``, ``"pageLayout"`` or ``"pageBreakPreview"``."""
+        return str(self._read()["view"])
+
+    @view.setter
+    def view(self, value: str) -> None:
+        self._set("view", str(value))
+
+    @property
+    def zoomScale(self) -> int:
+        """Zoom of the current view, in percent (10-400)."""
+        return int(self._read()["zoom_scale"])
+
+    @zoomScale.setter
+    def zoomScale(self, value: int) -> None:
+        self._set("zoom_scale", int(value))
+
+    @property
+    def zoomScaleNormal(self) -> int | None:
+        return self._read()["zoom_scale_normal"]
+
+    @zoomScaleNormal.setter
+    def zoomScaleNormal(self, value: int | None) -> None:
+        self._set("zoom_scale_normal", None if value is None else int(value))
+
+    @property
+    def zoomScalePageLayoutView(self) -> int | None:
+        return self._read()["zoom_scale_page_layout_view"]
+
+    @zoomScalePageLayoutView.setter
+    def zoomScalePageLayoutView(self, value: int | None) -> None:
+        self._set("zoom_scale_page_layout_view", None if value is None else int(value))
+
+    @property
+    def zoomScaleSheetLayoutView(self) -> int | None:
+        """Zoom of the page break preview."""
+        return self._read()["zoom_scale_sheet_layout_view"]
+
+    @zoomScaleSheetLayoutView.setter
+    def zoomScaleSheetLayoutView(self, value: int | None) -> None:
+        self._set("zoom_scale_sheet_layout_view", None if value is None else int(value))
+
+    @property
+    def showGridLines(self) -> bool:
+        return bool(self._read()["show_grid_lines"])
+
+    @showGridLines.setter
+    def showGridLines(self, value: bool) -> None:
+        self._set("show_grid_lines", bool(value))
+
+    @property
+    def showRowColHeaders(self) -> bool:
+        return bool(self._read()["show_row_col_headers"])
+
+    @showRowColHeaders.setter
+    def showRowColHeaders(self, value: bool) -> None:
+        self._set("show_row_col_headers", bool(value))
+
+    @property
+    def showZeros(self) -> bool:
+        return bool(self._read()["show_zeros"])
+
+    @showZeros.setter
+    def showZeros(self, value: bool) -> None:
+        self._set("show_zeros", bool(value))
+
+    @property
+    def showFormulas(self) -> bool:
+        return bool(self._read()["show_formulas"])
+
+    @showFormulas.setter
+    def showFormulas(self, value: bool) -> None:
+        self._set("show_formulas", bool(value))
+
+    @property
+    def rightToLeft(self) -> bool:
+        return bool(self._read()["right_to_left"])
+
+    @rightToLeft.setter
+    def rightToLeft(self, value: bool) -> None:
+        self._set("right_to_left", bool(value))
+
+    @property
+    def topLeftCell(self) -> str:
+        """Top-left visible cell of the view."""
+        return str(self._read()["top_left_cell"])
+
+    @topLeftCell.setter
+    def topLeftCell(self, a1: str) -> None:
+        self._set("top_left_cell", str(a1))
+
+    @property
+    def activeCell(self) -> str:
+        """Active cell; setting it selects just that cell."""
+        return str(self._read()["active_cell"])
+
+    @activeCell.setter
+    def activeCell(self, a1: str) -> None:
+        self._set("active_cell", str(a1))
+
+    @property
+    def selection(self) -> list[str]:
+        """Selected ranges; setting them makes the first one's top-left cell active."""
+        return list(self._read()["selection"])
+
+    @selection.setter
+    def selection(self, ranges: str | list[str]) -> None:
+        value = ranges if isinstance(ranges, str) else [str(r) for r in ranges]
+        self._set("selection", value)
+
 
 class MergedCells:
     def __init__(self, ws: Worksheet) -> None:
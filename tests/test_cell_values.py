@@ -2,12 +2,16 @@
 
 from __future__ import annotations
 
-from datetime import date, datetime
+import re
+import zipfile
+from collections.abc import Callable
+from datetime import date, datetime, time, timedelta
 from pathlib import Path
 
 import pytest
 
 import pyumya
+from pyumya import CellRichText, InlineFont, TextBlock
 
 
 def test_cell_value_roundtrip_all_types(tmp_path: Path) -> None:
@@ -42,3 +46,264 @@ def test_cell_value_roundtrip_all_types(tmp_path: Path) -> None:
     assert ws2["A8"].value == "#DIV/0!"
     assert ws2["A9"].value == "#N/A"
     assert ws2["A10"].value == "#VALUE!"
+
+
+def test_time_and_duration_roundtrip(tmp_path: Path) -> None:
+    out = tmp_path / "times.xlsx"
+
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+
+    ws["A1"].value = time(13, 45, 30)
+    ws["A2"].value = timedelta(hours=30, minutes=15)
+    ws["A3"].value = 0.5
+    ws["A3"].number_format = "h:mm"
+    ws["A4"].value = 1.25
+    ws["A4"].number_format = "[h]:mm:ss"
+
+    wb.save(out)
+
+    ws2 = pyumya.load_workbook(out)["Sheet1"]
+    assert ws2["A1"].value == time(13, 45, 30)
+    assert ws2["A1"].data_type == "d"
+    assert ws2["A2"].value == timedelta(hours=30, minutes=15)
+    assert ws2["A3"].value == time(12, 0)
+    assert ws2["A4"].value == timedelta(days=1, hours=6)
+
+
+def test_rust_payloads_use_native_datetime_objects() -> None:
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+
+    wb._rust.write_cell_value("Sheet1", "A1", {"type": "date", "value": date(2026, 3, 4)})
+    wb._rust.write_cell_value("Sheet1", "A2", {"type": "datetime", "value": "2026-03-04T05:06:07"})
+
+    assert wb._rust.read_cell_value("Sheet1", "A1") == {"type": "date", "value": date(2026, 3, 4)}
+    assert ws["A2"].value == datetime(2026, 3, 4, 5, 6, 7)
+
+    # A datetime is a date too, but a date payload would drop its time.
+    with pytest.raises(ValueError, match="datetime"):
+        wb._rust.write_cell_value(
+            "Sheet1", "A3", {"type": "date", "value": datetime(2026, 3, 4, 5, 6)}
+        )
+
+
+def test_date1904_roundtrip(tmp_path: Path) -> None:
+    out = tmp_path / "date1904.xlsx"
+
+    wb = pyumya.Workbook()
+    assert wb.date1904 is False
+    wb.date1904 = True
+    ws = wb["Sheet1"]
+    ws["A1"].value = date(2026, 2, 1)
+    ws["A2"].value = datetime(2026, 2, 1, 12, 0)
+    wb.save(out)
+
+    wb2 = pyumya.load_workbook(out)
+    assert wb2.date1904 is True
+    ws2 = wb2["Sheet1"]
+    assert ws2["A1"].value == date(2026, 2, 1)
+    assert ws2["A2"].value == datetime(2026, 2, 1, 12, 0)
+
+    # The stored serial is 1462 days smaller than in the 1900 system.
+    ws2["A1"].number_format = "General"
+    assert ws2["A1"].value == 46054 - 1462
+
+
+@pytest.mark.parametrize(
+    ("fmt", "expected"),
+    [
+        ("h:mm", time(6, 0)),
+        ("mm:ss.0", time(6, 0)),
+        ('"day"0', 0.25),
+        ("0.00%", 0.25),
+        ("[Red][<0]0.00;0.00", 0.25),
+    ],
+)
+def test_number_format_classification(fmt: str, expected: object) -> None:
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    ws["A1"].value = 0.25
+    ws["A1"].number_format = fmt
+    assert ws["A1"].value == expected
+
+
+def test_date_formats_by_classifier(tmp_path: Path) -> None:
+    out = tmp_path / "formats.xlsx"
+
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    ws["A1"].value = 46054
+    ws["A1"].number_format = "[$-409]d-mmm-yy"
+    ws["A2"].value = 46054.5
+    ws["A2"].number_format = "dd/mm/yyyy hh:mm"
+    ws["A3"].value = 46054
+    ws["A3"].number_format = '"day "0'
+    wb.save(out)
+
+    ws2 = pyumya.load_workbook(out)["Sheet1"]
+    assert ws2["A1"].value == date(2026, 2, 1)
+    assert ws2["A1"].is_date
+    assert ws2["A2"].value == datetime(2026, 2, 1, 12, 0)
+    assert ws2["A3"].value == 46054
+    assert not ws2["A3"].is_date
+
+
+def _rewrite_part(path: Path, part: str, edit: Callable[[str], str]) -> None:
+    """Rewrite one XML part of a saved package in place."""
+    with zipfile.ZipFile(path) as zin:
+        entries = {name: zin.read(name) for name in zin.namelist()}
+    entries[part] = edit(entries[part].decode("utf-8")).encode("utf-8")
+    with zipfile.ZipFile(path, "w", zipfile.ZIP_DEFLATED) as zout:
+        for name, data in entries.items():
+            zout.writestr(name, data)
+
+
+@pytest.mark.parametrize(
+    ("fmt_id", "serial", "expected"),
+    [
+        (27, 46054, date(2026, 2, 1)),
+        (31, 46054, date(2026, 2, 1)),
+        (32, 0.25, time(6, 0)),
+        (33, 0.5, time(12, 0)),
+        (35, 0.75, time(18, 0)),
+        (35, 46054.5, datetime(2026, 2, 1, 12, 0)),
+        (55, 0.25, time(6, 0)),
+        (57, 46054, date(2026, 2, 1)),
+    ],
+)
+def test_cjk_builtin_format_classification(
+    tmp_path: Path, fmt_id: int, serial: float, expected: object
+) -> None:
+    out = tmp_path / "cjk.xlsx"
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    ws["A1"].value = serial
+    ws["A1"].number_format = "h:mm"
+    wb.save(out)
+
+    # Point the cell at the locale-specific built-in ID, which has no code.
+    def edit(xml: str) -> str:
+        xml = re.sub(r'<numFmt numFmtId="20"[^>]*/>', "", xml)
+        return xml.replace('numFmtId="20"', f'numFmtId="{fmt_id}"')
+
+    _rewrite_part(out, "xl/styles.xml", edit)
+
+    assert pyumya.load_workbook(out)["Sheet1"]["A1"].value == expected
+
+
+def test_error_values_are_genuine_error_cells(tmp_path: Path) -> None:
+    openpyxl = pytest.importorskip("openpyxl")
+    out = tmp_path / "errors.xlsx"
+    tokens = ["#NULL!", "#DIV/0!", "#VALUE!", "#REF!", "#NAME?", "#NUM!", "#N/A"]
+
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    for row, token in enumerate(tokens, start=1):
+        ws.cell(row=row, column=1, value=token)
+    wb.save(out)
+
+    ws2 = pyumya.load_workbook(out)["Sheet1"]
+    for row, token in enumerate(tokens, start=1):
+        c = ws2.cell(row=row, column=1)
+        assert c.value == token
+        assert c.data_type == "e"
+
+    # No sentinel formulas: the file holds real error cells.
+    ws3 = openpyxl.load_workbook(out)["Sheet1"]
+    for row, token in enumerate(tokens, start=1):
+        c = ws3.cell(row=row, column=1)
+        assert c.data_type == "e"
+        assert c.value == token
+
+
+def _with_cached_values(path: Path, cached: dict[str, str]) -> None:
+    """Inject `<v>` results into formula cells, as Excel does when saving."""
+
+    def edit(xml: str) -> str:
+        for ref, v in cached.items():
+            xml = re.sub(
+                rf'(<c r="{ref}"[^>]*>\s*<f>[^<]*</f>)\s*(<v>[^<]*</v>|<v\s*/>)?',
+                rf"\1<v>{v}</v>",
+                xml,
+            )
+        return xml
+
+    _rewrite_part(path, "xl/worksheets/sheet1.xml", edit)
+
+
+def test_formula_cached_values_and_data_only(tmp_path: Path) -> None:
+    openpyxl = pytest.importorskip("openpyxl")
+    out = tmp_path / "cached.xlsx"
+
+    wb = openpyxl.Workbook()
+    ws = wb.active
+    ws.title = "Sheet1"
+    ws["A1"] = 2
+    ws["A2"] = 3
+    ws["B1"] = "=A1+A2"
+    ws["B2"] = "=A1*A2"
+    ws["B3"] = "=A3"
+    wb.save(out)
+    _with_cached_values(out, {"B1": "5", "B2": "6"})
+
+    ws2 = pyumya.load_workbook(out)["Sheet1"]
+    assert ws2["B1"].value == "=A1+A2"
+    assert ws2["B1"].cached_value == 5
+    assert ws2["B2"].cached_value == 6
+    # Never calculated: no cached result.
+    assert ws2["B3"].cached_value is None
+    assert ws2["A1"].cached_value == 2
+
+    payload = ws2._rust_read_cell_payload("B1")
+    assert payload["cached_value"] == {"type": "number", "value": 5.0}
+
+    ws3 = pyumya.load_workbook(out, data_only=True)["Sheet1"]
+    assert ws3["B1"].value == 5
+    assert ws3["B2"].value == 6
+    assert ws3["B1"].data_type == "n"
+    assert ws3["B3"].value is None
+    assert list(ws3.iter_rows(min_row=1, max_row=2, min_col=2, max_col=2, values_only=True)) == [
+        (5,),
+        (6,),
+    ]
+
+
+def test_rich_text_roundtrip(tmp_path: Path) -> None:
+    out = tmp_path / "rich.xlsx"
+
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    rich = CellRichText(
+        "Total: ",
+        TextBlock(InlineFont(b=True, color="FF0000"), "42"),
+        TextBlock(InlineFont(i=True, sz=9, vertAlign="superscript"), "kg"),
+    )
+    ws["A1"].value = rich
+    ws["A2"].value = "plain"
+
+    assert ws["A1"].value == rich
+    assert ws["A1"].data_type == "s"
+    wb.save(out)
+
+    # Plain strings unless rich text is requested, as in openpyxl.
+    assert pyumya.load_workbook(out)["Sheet1"]["A1"].value == "Total: 42kg"
+
+    ws2 = pyumya.load_workbook(out, rich_text=True)["Sheet1"]
+    value = ws2["A1"].value
+    assert isinstance(value, CellRichText)
+    assert str(value) == "Total: 42kg"
+    assert value[0] == "Total: "
+    assert value[1].text == "42"
+    assert value[1].font.b is True
+    assert value[1].font.color == "FF0000"
+    assert value[2].font.i is True
+    assert value[2].font.sz == 9
+    assert value[2].font.vertAlign == "superscript"
+    assert ws2["A2"].value == "plain"
+
+    openpyxl = pytest.importorskip("openpyxl")
+    ws3 = openpyxl.load_workbook(out, rich_text=True)["Sheet1"]
+    blocks = [b for b in ws3["A1"].value if not isinstance(b, str)]
+    assert blocks[0].font.b
+    assert blocks[1].font.vertAlign == "superscript"
@@ -102,7 +102,317 @@ def test_diagonal_implicit_direction(tmp_path: Path) -> None:
     assert ws2["A1"].border.diagonalUp is True
 
 
+def test_border_colors_and_outline_roundtrip(tmp_path: Path) -> None:
+    out = tmp_path / "border_colors.xlsx"
+
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    ws["A1"].border = pyumya.Border(
+        top=pyumya.Side(style="MediumDashed", color="FF0000"),
+        bottom=pyumya.Side(style="thin", theme=4, tint=-0.25),
+        left=pyumya.Side(style="double", indexed=10),
+        diagonal=pyumya.Side(style="hair", color="00FF00"),
+        diagonalUp=True,
+        diagonalDown=True,
+        outline=False,
+    )
+    wb.save(out)
+
+    b = pyumya.load_workbook(out)["Sheet1"]["A1"].border
+    assert b.top.style == "mediumDashed"
+    assert b.top.color == "FF0000"
+    assert b.bottom.theme == 4
+    assert b.bottom.tint == pytest.approx(-0.25)
+    assert b.left.indexed == 10
+    assert b.left.color == "FF0000"
+    assert b.diagonal == pyumya.Side(style="hair", color="00FF00")
+    assert b.diagonalUp and b.diagonalDown
+    assert b.outline is False
+    assert b.right.style == "none"
+
+    openpyxl = pytest.importorskip("openpyxl")
+    ob = openpyxl.load_workbook(out)["Sheet1"]["A1"].border
+    assert ob.top.style == "mediumDashed"
+    assert ob.bottom.color.theme == 4
+    assert ob.outline is False
+
+
+def test_invalid_border_style_raises() -> None:
+    wb = pyumya.Workbook()
+    with pytest.raises(ValueError):
+        wb["Sheet1"]["A1"].border = pyumya.Border(top=pyumya.Side(style="squiggly"))
+
+
+def test_font_attributes_roundtrip(tmp_path: Path) -> None:
+    out = tmp_path / "fonts.xlsx"
+
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+
+    ws["A1"].value = "x2"
+    ws["A1"].font = pyumya.Font(
+        name="Cambria",
+        underline="doubleAccounting",
+        vertAlign="superscript",
+        family=1,
+        charset=0,
+        scheme="major",
+        outline=True,
+        shadow=True,
+    )
+    ws["A2"].value = "plain"
+    ws["A2"].font = pyumya.Font(name="Cambria", underline="doubleAccounting")
+    ws["A3"].value = "sub"
+    ws["A3"].font = pyumya.Font(vertAlign="subscript", condense=True, extend=True)
+    wb.save(out)
+
+    ws2 = pyumya.load_workbook(out)["Sheet1"]
+    f1 = ws2["A1"].font
+    assert f1.underline == "doubleAccounting"
+    assert f1.vertAlign == "superscript"
+    assert f1.family == 1
+    assert f1.scheme == "major"
+    assert f1.outline and f1.shadow
+    assert not f1.condense
+    f2 = ws2["A2"].font
+    assert f2.underline == "doubleAccounting"
+    assert f2.vertAlign is None
+    assert not f2.outline
+    f3 = ws2["A3"].font
+    assert f3.vertAlign == "subscript"
+    assert f3.condense and f3.extend
+
+    openpyxl = pytest.importorskip("openpyxl")
+    ows = openpyxl.load_workbook(out)["Sheet1"]
+    assert ows["A1"].font.vertAlign == "superscript"
+    assert ows["A1"].font.u == "doubleAccounting"
+    assert ows["A1"].font.outline
+    assert ows["A2"].font.vertAlign is None
+
+
+def test_invalid_font_attributes_raise() -> None:
+    with pytest.raises(ValueError):
+        pyumya.Font(underline="wavy")
+    with pytest.raises(ValueError):
+        pyumya.Font(vertAlign="raised")
+
+
+def test_alignment_and_protection_roundtrip(tmp_path: Path) -> None:
+    out = tmp_path / "alignment.xlsx"
+
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+
+    ws["A1"].value = "shrink"
+    ws["A1"].alignment = pyumya.Alignment(
+        horizontal="distributed",
+        shrink_to_fit=True,
+        justifyLastLine=True,
+        relativeIndent=2,
+        readingOrder=2,
+    )
+    ws["A2"].value = "stacked"
+    ws["A2"].alignment = pyumya.Alignment(text_rotation=255)
+    ws["A3"].value = "input"
+    ws["A3"].protection = pyumya.Protection(locked=False)
+    ws["A4"].value = "=1+1"
+    ws["A4"].protection = pyumya.Protection(hidden=True)
+    wb.save(out)
+
+    ws2 = pyumya.load_workbook(out)["Sheet1"]
+    a1 = ws2["A1"].alignment
+    assert a1.horizontal == "distributed"
+    assert a1.shrink_to_fit and a1.justifyLastLine
+    assert a1.relativeIndent == 2
+    assert a1.readingOrder == 2
+    assert ws2["A2"].alignment.text_rotation == 255
+    assert not ws2["A2"].alignment.shrink_to_fit
+    assert ws2["A3"].protection == pyumya.Protection(locked=False, hidden=False)
+    assert ws2["A4"].protection == pyumya.Protection(locked=True, hidden=True)
+    assert ws2["B1"].protection.locked
+
+    openpyxl = pytest.importorskip("openpyxl")
+    ows = openpyxl.load_workbook(out)["Sheet1"]
+    assert ows["A1"].alignment.shrink_to_fit
+    assert ows["A1"].alignment.readingOrder == 2
+    assert ows["A2"].alignment.textRotation == 255
+    assert ows["A3"].protection.locked is False
+    assert ows["A4"].protection.hidden is True
+
+
 def test_negative_indent_raises() -> None:
     """Alignment.indent must be >= 0."""
     with pytest.raises(ValueError, match="indent"):
         pyumya.Alignment(indent=-1)
+
+
+def test_theme_and_indexed_colors_resolved(tmp_path: Path) -> None:
+    openpyxl = pytest.importorskip("openpyxl")
+    from openpyxl.styles import Color, Font, PatternFill
+
+    path = tmp_path / "theme_colors.xlsx"
+    src = openpyxl.Workbook()
+    ws = src.active
+    ws.title = "Sheet1"
+    ws["A1"] = "accent"
+    ws["A1"].font = Font(color=Color(theme=4, tint=0.3999755851924192))
+    ws["A1"].fill = PatternFill("solid", fgColor=Color(theme=0, tint=-0.1499984740745262))
+    ws["A2"] = "indexed"
+    ws["A2"].font = Font(color=Color(indexed=10))
+    src.save(path)
+
+    wb = pyumya.load_workbook(path)
+    ws2 = wb["Sheet1"]
+
+    font = ws2["A1"].font
+    assert font.color == "95B3D7"
+    assert font.theme == 4
+    assert font.tint == pytest.approx(0.4, abs=1e-3)
+    fill = ws2["A1"].fill
+    assert fill.fgColor == "D9D9D9"
+    assert fill.theme == 0
+    assert ws2["A2"].font.color == "FF0000"
+    assert ws2["A2"].font.indexed == 10
+
+    # Writing the font back keeps the theme reference.
+    ws2["B1"].font = font
+    ws2["B1"].fill = fill
+    wb.save(tmp_path / "resaved.xlsx")
+    ws3 = openpyxl.load_workbook(tmp_path / "resaved.xlsx")["Sheet1"]
+    assert ws3["B1"].font.color.theme == 4
+    assert ws3["B1"].fill.fgColor.theme == 0
+    assert ws3["B1"].fill.fgColor.tint == pytest.approx(-0.15, abs=1e-3)
+
+
+def test_pattern_and_gradient_fills_roundtrip(tmp_path: Path) -> None:
+    out = tmp_path / "fills.xlsx"
+
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    ws["A1"].fill = pyumya.PatternFill("darkGrid", fgColor="FF0000", bgColor="FFFF00")
+    ws["A2"].fill = pyumya.GradientFill(degree=90, stop=["FFFFFF", "4472C4"])
+    ws["A3"].fill = pyumya.GradientFill(
+        type="path",
+        left=0.5,
+        right=0.5,
+        top=0.5,
+        bottom=0.5,
+        stop=[pyumya.Stop("FFFFFF", 0), pyumya.Stop("000000", 1, theme=1)],
+    )
+    wb.save(out)
+
+    ws2 = pyumya.load_workbook(out)["Sheet1"]
+    pattern = ws2["A1"].fill
+    assert isinstance(pattern, pyumya.PatternFill)
+    assert (pattern.fill_type, pattern.fgColor, pattern.bgColor) == ("darkGrid", "FF0000", "FFFF00")
+
+    linear = ws2["A2"].fill
+    assert isinstance(linear, pyumya.GradientFill)
+    assert linear.type == "linear"
+    assert linear.degree == 90
+    assert [(s.position, s.color) for s in linear.stop] == [(0, "FFFFFF"), (1, "4472C4")]
+
+    path = ws2["A3"].fill
+    assert isinstance(path, pyumya.GradientFill)
+    assert path.type == "path"
+    assert (path.left, path.right, path.top, path.bottom) == (0.5, 0.5, 0.5, 0.5)
+    assert path.stop[1].theme == 1
+
+    openpyxl = pytest.importorskip("openpyxl")
+    ws3 = openpyxl.load_workbook(out)["Sheet1"]
+    assert ws3["A1"].fill.fill_type == "darkGrid"
+    assert ws3["A3"].fill.type == "path"
+    assert ws3["A3"].fill.top == 0.5
+
+
+def test_gradient_stop_validation() -> None:
+    with pytest.raises(ValueError):
+        pyumya.Stop("FFFFFF", 1.5)
+    with pytest.raises(ValueError):
+        pyumya.GradientFill(type="radial")
+
+
+def test_named_styles_roundtrip(tmp_path: Path) -> None:
+    out = tmp_path / "named_styles.xlsx"
+
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    highlight = pyumya.NamedStyle(
+        "Highlight",
+        font=pyumya.Font(bold=True, color="9C0006"),
+        fill=pyumya.PatternFill("solid", fgColor="FFC7CE"),
+        number_format="0.00%",
+    )
+    wb.add_named_style(highlight)
+    assert wb.named_styles == ["Normal", "Highlight"]
+
+    ws.apply_named_style("B2:C3", "Highlight")
+    ws["A1"].style = pyumya.NamedStyle("Heading 1", font=pyumya.Font(size=15, bold=True))
+    assert ws["C3"].style == "Highlight"
+    assert ws["C3"].font.bold is True
+    assert ws["C3"].fill.fgColor == "FFC7CE"
+    assert ws["D4"].style == "Normal"
+    wb.save(out)
+
+    wb2 = pyumya.load_workbook(out)
+    ws2 = wb2["Sheet1"]
+    assert wb2.named_styles == ["Normal", "Highlight", "Heading 1"]
+    assert ws2["B2"].style == "Highlight"
+    assert ws2["B2"].number_format == "0.00%"
+    assert ws2["A1"].style == "Heading 1"
+
+    # Styles read from a file can be applied to new cells.
+    ws2.apply_named_style("E5", "Highlight")
+    assert ws2["E5"].font.color == "9C0006"
+
+    with pytest.raises(ValueError):
+        ws2.apply_named_style("A1", "Missing")
+
+    openpyxl = pytest.importorskip("openpyxl")
+    src = openpyxl.load_workbook(out)
+    assert "Highlight" in src.named_styles
+    assert src["Sheet1"]["B2"].style == "Highlight"
+    assert src["Sheet1"]["A1"].style == "Heading 1"
+
+
+def test_range_format_and_outline_border(tmp_path: Path) -> None:
+    out = tmp_path / "ranges.xlsx"
+
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    ws.format_range(
+        "A1:A3 C1:C3",
+        font=pyumya.Font(bold=True),
+        fill=pyumya.PatternFill("solid", fgColor="DDEBF7"),
+    )
+    ws.border_range(
+        "B5:D7",
+        pyumya.Border(
+            left=pyumya.Side("thick"),
+            right=pyumya.Side("thick"),
+            top=pyumya.Side("thick"),
+            bottom=pyumya.Side("thick"),
+        ),
+        outline=True,
+        inner_horizontal=pyumya.Side("hair", color="808080"),
+    )
+    wb.save(out)
+
+    ws2 = pyumya.load_workbook(out)["Sheet1"]
+    assert ws2["A3"].font.bold is True
+    assert ws2["C1"].fill.fgColor == "DDEBF7"
+    assert ws2["B1"].font.bold is False
+
+    corner = ws2["B5"].border
+    assert (corner.top.style, corner.left.style) == ("thick", "thick")
+    assert corner.bottom.style == "hair"
+    assert corner.right.style == "none"
+    middle = ws2["C6"].border
+    assert (middle.top.style, middle.bottom.style) == ("hair", "hair")
+    assert middle.bottom.color == "808080"
+    assert (middle.left.style, middle.right.style) == ("none", "none")
+    assert ws2["D7"].border.right.style == "thick"
+    assert ws2["D7"].border.bottom.style == "thick"
+
+    with pytest.raises(ValueError):
+        ws2.format_range("A1:nope", number_format="0.00")
@@ -2,9 +2,14 @@
 
 from __future__ import annotations
 
+import re
+import zipfile
 from pathlib import Path
 
+import pytest
+
 import pyumya
+from pyumya import ArrayFormula
 
 
 def test_formula_roundtrip(tmp_path: Path) -> None:
@@ -27,3 +32,240 @@ def test_formula_roundtrip(tmp_path: Path) -> None:
     assert ws2["A3"].data_type == "f"
     assert ws2["A4"].value == "=A1+A2"
     assert ws2["A4"].data_type == "f"
+
+
+def test_calculate_writes_cached_values(tmp_path: Path) -> None:
+    out = tmp_path / "calc.xlsx"
+
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    data = wb.create_sheet("Data")
+
+    ws.write_rows([[1, 2], [3, 4], [5, 6]])
+    data.write_rows([["apple", 1.5], ["banana", 0.25], ["cherry", 3]])
+
+    formulas = {
+        "C1": "=SUM(A1:B3)",
+        "C2": "=AVERAGE(A1:A3)*2",
+        "C3": '=IF(C1>20,"big","small")',
+        "C4": "=VLOOKUP(\"banana\",Data!A1:B3,2,FALSE)",
+        "C5": "=INDEX(Data!A1:A3,MATCH(3,Data!B1:B3,0))",
+        "C6": '=COUNTIF(A1:B3,">2")',
+        "C7": '=SUMIFS(B1:B3,A1:A3,">1",A1:A3,"<5")',
+        "C8": "=ROUND(2.345,2)",
+        "C9": '=TEXT(DATE(2024,1,5),"yyyy-mm-dd")',
+        "C10": "=IFERROR(1/0,\"div\")",
+        "C11": '="n="&C1',
+        "C12": "=C2+C1",
+        "C13": "=1/0",
+    }
+    for ref, formula in formulas.items():
+        ws[ref].value = formula
+
+    assert wb.calculate() == []
+
+    expected = {
+        "C1": 21,
+        "C2": 6,
+        "C3": "big",
+        "C4": 0.25,
+        "C5": "cherry",
+        "C6": 4,
+        "C7": 4,
+        "C8": 2.35,
+        "C9": "2024-01-05",
+        "C10": "div",
+        "C11": "n=21",
+        "C12": 27,
+        "C13": "#DIV/0!",
+    }
+    for ref, value in expected.items():
+        assert ws[ref].cached_value == value, ref
+        # The formula itself is kept.
+        assert ws[ref].value == formulas[ref]
+
+    wb.save(out)
+    ws2 = pyumya.load_workbook(out, data_only=True)["Sheet1"]
+    for ref, value in expected.items():
+        assert ws2[ref].value == value, ref
+
+
+def test_calculate_reports_circular_references() -> None:
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    ws["A1"].value = "=B1+1"
+    ws["B1"].value = "=A1+1"
+    ws["C1"].value = "=A1*2"
+    ws["D1"].value = "=2*3"
+
+    assert sorted(wb.calculate()) == ["Sheet1!A1", "Sheet1!B1", "Sheet1!C1"]
+    assert ws["D1"].cached_value == 6
+
+
+def test_calculate_date_out_of_range_is_num_error() -> None:
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    ws["A1"].value = "=DATE(2024,1,1E15)"
+    ws["A2"].value = "=DATE(2024,1E300,1)"
+    ws["A3"].value = "=DATE(2024,-1E300,1)"
+    ws["A4"].value = "=DATE(2024,14,0)"
+
+    assert wb.calculate() == []
+    for ref in ("A1", "A2", "A3"):
+        assert ws[ref].cached_value == "#NUM!", ref
+    # Within range, months and days still roll over.
+    assert ws["A4"].cached_value == 45688
+
+
+def test_calculate_orders_whole_column_and_spill_references() -> None:
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    other = wb.create_sheet("Other")
+    # Dependents come before their precedents in cell order.
+    ws["A1"].value = "=SUM(B:B)"
+    ws["A2"].value = "=SUM(3:3)"
+    ws["A3"].value = "=Other!A1*2"
+    ws["B1"].value = "=C2+1"
+    ws["B5000"].value = "=C1"
+    ws["C1"].value = ArrayFormula("C1:C2", "=D1:D2*10")
+    ws["D1"].value = 1
+    ws["D2"].value = 2
+    ws["E3"].value = "=D2"
+    other["A1"].value = "=Sheet1!B1"
+
+    assert wb.calculate() == []
+    assert ws["B1"].cached_value == 21
+    assert ws["B5000"].cached_value == 10
+    assert ws["A1"].cached_value == 31
+    assert ws["A2"].cached_value == 44
+    assert ws["A3"].cached_value == 42
+
+
+def test_calculate_match_exact_on_unsorted_data() -> None:
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    ws.write_rows([["e", 5], ["b", 2], ["i", 9], ["d", 4], ["g", 7]])
+    ws["C1"].value = "=MATCH(4,B1:B5,0)"
+    ws["C2"].value = "=INDEX(A1:A5,MATCH(2,B1:B5,0))"
+    ws["C3"].value = "=MATCH(3,B1:B5,0)"
+    ws["C4"].value = "=MATCH(4,B1:B5,0.5)"
+
+    assert wb.calculate() == []
+    assert ws["C1"].cached_value == 4
+    assert ws["C2"].cached_value == "b"
+    assert ws["C3"].cached_value == "#N/A"
+    # A positive mode is an ascending approximate match, whatever its size.
+    assert ws["C4"].cached_value == "#N/A"
+
+
+def test_calculate_number_formats_in_text() -> None:
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    cases = {
+        "A1": ('=TEXT(1234.567,"#,##0.00")', "1,234.57"),
+        "A2": ('=TEXT(0.256,"0.0%")', "25.6%"),
+        "A3": ('=TEXT(-5,"0;(0)")', "(5)"),
+        "A4": ('=TEXT(0.75,"h:mm AM/PM")', "6:00 PM"),
+        "A5": ('=TEXT(DATE(2023,3,9),"dddd, mmmm d")', "Thursday, March 9"),
+        "A6": ('=TEXT(12345,"0.00E+00")', "1.23E+04"),
+        "A7": ('=TEXT(1.5,"[h]:mm")', "36:00"),
+    }
+    for ref, (formula, _) in cases.items():
+        ws[ref].value = formula
+    wb.calculate()
+    for ref, (_, expected) in cases.items():
+        assert ws[ref].cached_value == expected, ref
+
+
+def test_shared_formula_roundtrip(tmp_path: Path) -> None:
+    out = tmp_path / "shared.xlsx"
+
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    ws.write_rows([[1, 10], [2, 20], [3, 30]])
+    ws.write_shared_formula("C1:C3", "=A1*B1+$A$1")
+
+    assert ws["C1"].value == "=A1*B1+$A$1"
+    assert ws["C3"].value == "=A3*B3+$A$1"
+    wb.save(out)
+
+    with zipfile.ZipFile(out) as z:
+        xml = z.read("xl/worksheets/sheet1.xml").decode("utf-8")
+    assert 't="shared"' in xml
+    assert 'ref="C1:C3"' in xml
+
+    ws2 = pyumya.load_workbook(out)["Sheet1"]
+    assert ws2["C2"].value == "=A2*B2+$A$1"
+    master = ws2._rust_read_cell_payload("C1")
+    dependent = ws2._rust_read_cell_payload("C2")
+    assert master["formula_type"] == "shared"
+    assert master["ref"] == "C1:C3"
+    assert dependent["shared_index"] == master["shared_index"]
+    assert dependent["shared_master"] == "C1"
+
+    # Overwriting a dependent with a plain formula detaches it from the group.
+    ws2["C2"].value = "=1+1"
+    assert ws2["C2"].value == "=1+1"
+    assert ws2["C3"].value == "=A3*B3+$A$1"
+
+
+def test_array_formula_roundtrip(tmp_path: Path) -> None:
+    openpyxl = pytest.importorskip("openpyxl")
+    out = tmp_path / "array.xlsx"
+
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    ws.write_rows([[1, 4], [2, 5], [3, 6]])
+    ws["C1"].value = ArrayFormula("C1:C3", "=A1:A3*B1:B3")
+    ws["D1"].value = ArrayFormula("D1", "SUM(A1:A3*B1:B3)")
+
+    assert ws["C1"].value == ArrayFormula("C1:C3", "=A1:A3*B1:B3")
+    assert ws.array_formulae == {"C1": "C1:C3", "D1": "D1"}
+    assert wb.calculate() == []
+    assert [ws[f"C{r}"].cached_value for r in range(1, 4)] == [4, 10, 18]
+    assert ws["D1"].cached_value == 32
+    wb.save(out)
+
+    wb2 = openpyxl.load_workbook(out)
+    f = wb2["Sheet1"]["C1"].value
+    assert isinstance(f, openpyxl.worksheet.formula.ArrayFormula)
+    assert f.ref == "C1:C3"
+    assert f.text == "=A1:A3*B1:B3"
+
+    ws3 = pyumya.load_workbook(out)["Sheet1"]
+    assert ws3["C1"].value == ArrayFormula("C1:C3", "=A1:A3*B1:B3")
+    assert ws3["D1"].value == ArrayFormula("D1", "=SUM(A1:A3*B1:B3)")
+
+
+def test_dynamic_array_formula_roundtrip(tmp_path: Path) -> None:
+    out = tmp_path / "dynamic.xlsx"
+    resaved = tmp_path / "resaved.xlsx"
+
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    ws.write_rows([[3], [1], [2]])
+    ws["B1"].value = ArrayFormula("B1:B3", "=A1:A3*2", dynamic=True)
+    ws["C1"].value = ArrayFormula("C1", "=SUM(A1:A3)")
+    wb.save(out)
+
+    with zipfile.ZipFile(out) as z:
+        assert "xl/metadata.xml" in z.namelist()
+        xml = z.read("xl/worksheets/sheet1.xml").decode("utf-8")
+        types = z.read("[Content_Types].xml").decode("utf-8")
+    flagged = re.search(r'<c [^>]*cm="1"[^>]*>', xml)
+    assert flagged and 'r="B1"' in flagged.group(0)
+    assert xml.count('cm="1"') == 1
+    assert "/xl/metadata.xml" in types
+
+    wb2 = pyumya.load_workbook(out)
+    ws2 = wb2["Sheet1"]
+    assert ws2["B1"].value.dynamic is True
+    assert ws2["C1"].value.dynamic is False
+
+    # The flag survives a second save, and goes away with the formula.
+    wb2.save(resaved)
+    assert pyumya.load_workbook(resaved)["Sheet1"]["B1"].value.dynamic is True
+    ws2["B1"].value = 5
+    wb2.save(resaved)
+    with zipfile.ZipFile(resaved) as z:
+        assert 'cm="1"' not in z.read("xl/worksheets/sheet1.xml").decode("utf-8")
@@ -0,0 +1,53 @@
+"""Roundtrip tests for sheet view settings."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+import pyumya
+
+
+def test_sheet_view_roundtrip_survives_freeze(tmp_path: Path) -> None:
+    out = tmp_path / "view.xlsx"
+
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    view = ws.sheet_view
+    assert view.zoomScale == 100
+    assert view.showGridLines is True
+    assert view.selection == ["A1"]
+
+    view.view = "pageLayout"
+    view.zoomScale = 85
+    view.zoomScalePageLayoutView = 85
+    view.showGridLines = False
+    view.showRowColHeaders = False
+    view.showZeros = False
+    view.showFormulas = True
+    view.rightToLeft = True
+    view.selection = ["C3:D4", "F6"]
+    assert view.activeCell == "C3"
+
+    with pytest.raises(ValueError):
+        view.zoomScale = 5
+
+    ws.freeze_panes = "B2"
+    ws.freeze_panes = None
+    wb.save(out)
+
+    ws2 = pyumya.load_workbook(out)["Sheet1"]
+    view2 = ws2.sheet_view
+    assert ws2.freeze_panes is None
+    assert view2.view == "pageLayout"
+    assert view2.zoomScale == 85
+    assert view2.zoomScalePageLayoutView == 85
+    assert view2.zoomScaleNormal is None
+    assert view2.showGridLines is False
+    assert view2.showRowColHeaders is False
+    assert view2.showZeros is False
+    assert view2.showFormulas is True
+    assert view2.rightToLeft is True
+    assert view2.activeCell == "C3"
+    assert view2.selection == ["C3:D4", "F6"]
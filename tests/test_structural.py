@@ -4,6 +4,8 @@ from __future__ import annotations
 
 from pathlib import Path
 
+import pytest
+
 import pyumya
 
 
@@ -48,3 +50,258 @@ def test_row_height_and_column_width_roundtrip(tmp_path: Path) -> None:
     ws2 = wb2["Sheet1"]
     assert ws2.row_dimensions[1].height == 20
     assert ws2.column_dimensions["A"].width == 15
+
+
+def test_row_and_column_default_styles(tmp_path: Path) -> None:
+    out = tmp_path / "dim_styles.xlsx"
+
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+
+    ws["B3"].value = "existing"
+    ws.row_dimensions[3].font = pyumya.Font(bold=True)
+    ws.column_dimensions["D"].fill = pyumya.PatternFill(fgColor="FFFF00")
+    ws.row_dimensions[5].hidden = True
+    ws.column_dimensions["E"].hidden = True
+
+    assert ws.row_dimensions[3].customFormat
+    assert not ws.row_dimensions[4].customFormat
+    assert ws["B3"].font.bold
+    assert ws["H3"].font.bold
+    assert ws["D10"].fill.fgColor == "FFFF00"
+
+    ws["F3"].value = 1
+    wb.save(out)
+
+    wb2 = pyumya.load_workbook(out)
+    ws2 = wb2["Sheet1"]
+    assert ws2.row_dimensions[3].font.bold
+    assert ws2["F3"].font.bold
+    assert ws2["Z3"].font.bold
+    assert ws2.column_dimensions["D"].fill.fill_type == "solid"
+    assert ws2["D1"].fill.fgColor == "FFFF00"
+    assert ws2.row_dimensions[5].hidden
+    assert not ws2.row_dimensions[6].hidden
+    assert ws2.column_dimensions["E"].hidden
+
+
+def test_insert_and_delete_rows_and_cols(tmp_path: Path) -> None:
+    out = tmp_path / "shift.xlsx"
+
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    other = wb.create_sheet("Data")
+
+    ws["A1"].value = 1
+    ws["A2"].value = 2
+    ws["A3"].value = 3
+    ws["B1"].value = "=SUM(A1:A3)"
+    ws["B2"].value = "=$A$3*2"
+    ws["A3"].font = pyumya.Font(bold=True)
+    ws.merge_cells("C2:D3")
+    other["A1"].value = "=Sheet1!A2+Sheet1!A3"
+
+    ws.insert_rows(2, amount=2)
+    assert ws["A4"].value == 2
+    assert ws["B1"].value == "=SUM(A1:A5)"
+    assert ws["B4"].value == "=$A$5*2"
+    assert ws["A5"].font.bold
+    assert ws.merged_cells.ranges == ["C4:D5"]
+    assert other["A1"].value == "=Sheet1!A4+Sheet1!A5"
+
+    ws.delete_rows(5)
+    assert ws["B1"].value == "=SUM(A1:A4)"
+    assert ws["B4"].value == "=#REF!*2"
+    assert other["A1"].value == "=Sheet1!A4+Sheet1!#REF!"
+
+    ws.insert_cols(1)
+    assert ws["B4"].value == 2
+    assert ws["C1"].value == "=SUM(B1:B4)"
+    ws.delete_cols(1)
+    wb.save(out)
+
+    wb2 = pyumya.load_workbook(out)
+    ws2 = wb2["Sheet1"]
+    assert ws2["A4"].value == 2
+    assert ws2["B1"].value == "=SUM(A1:A4)"
+    assert wb2["Data"]["A1"].value == "=Sheet1!A4+Sheet1!#REF!"
+
+
+def test_formulas_with_array_constants_are_still_adjusted() -> None:
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    data = wb.create_sheet("Data")
+    ws["A1"].value = 1
+    ws["A2"].value = 2
+    ws["B1"].value = "=SUMPRODUCT(A1:A2*{1;2})"
+    ws["B2"].value = "=SUM(Data!A1:A2,{1,2})+Sales[Total]"
+    data["A1"].value = "=INDEX({10,20},1)+Sheet1!A2"
+
+    ws.insert_rows(1)
+    assert ws["B2"].value == "=SUMPRODUCT(A2:A3*{1;2})"
+    assert data["A1"].value == "=INDEX({10,20},1)+Sheet1!A3"
+
+    ws.move_range("A2:A3", cols=3)
+    assert ws["B2"].value == "=SUMPRODUCT(D2:D3*{1;2})"
+
+    data.title = "Inputs"
+    assert ws["B3"].value == "=SUM(Inputs!A1:A2,{1,2})+Sales[Total]"
+
+
+def test_shifting_lines_keeps_formulas_in_step() -> None:
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    ws["A3"].value = 1
+    ws["B3"].value = "=A3*2"
+    ws.add_data_validation({"range": "C2", "validation_type": "list", "formula1": '"a,b"'})
+    ws.add_data_validation({"range": "C5", "validation_type": "custom", "formula1": "=A5>0"})
+
+    # A validation whose cells are all deleted goes; the others are adjusted.
+    ws.delete_rows(2)
+    assert ws["B2"].value == "=A2*2"
+    [validation] = ws.data_validations
+    assert validation["range"] == "C4"
+    assert "A4>0" in validation["formula1"]
+
+    # A formula that cannot be read stops the shift before anything moves.
+    ws["D1"].value = '="unterminated'
+    with pytest.raises(ValueError, match="unterminated"):
+        ws.insert_rows(1)
+    assert ws["A2"].value == 1
+    assert ws["B2"].value == "=A2*2"
+
+
+def test_moving_and_copying_ranges_reports_unreadable_formulas() -> None:
+    wb = pyumya.Workbook()
+    ws = wb.create_sheet("Ärger")
+    other = wb["Sheet1"]
+    ws["A1"].value = 1
+    ws["B1"].value = '="unterminated'
+    other["A1"].value = "=ÄRGER!A1"
+
+    with pytest.raises(ValueError, match="unterminated"):
+        ws.move_range("A1:B1", rows=1)
+    assert ws["A1"].value == 1
+    assert other["A1"].value == "=ÄRGER!A1"
+    with pytest.raises(ValueError, match="unterminated"):
+        ws.copy_range("A1:B1", "A5")
+    assert ws["A5"].value is None
+
+    # Sheet names match regardless of case, not only for ASCII letters.
+    ws["B1"].value = None
+    ws.move_range("A1", rows=1)
+    assert other["A1"].value == "=ÄRGER!A2"
+    ws.title = "Ärgernis"
+    assert other["A1"].value == "=Ärgernis!A2"
+
+
+def test_move_and_copy_range(tmp_path: Path) -> None:
+    out = tmp_path / "ranges.xlsx"
+
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    other = wb.create_sheet("Report")
+
+    ws["A1"].value = 10
+    ws["A2"].value = 20
+    ws["B1"].value = "=A1*2"
+    ws["B2"].value = "=$A$2+A1"
+    ws["B1"].font = pyumya.Font(bold=True)
+    ws["D1"].value = "=SUM(A1:A2)"
+    ws.merge_cells("A3:B3")
+
+    ws.copy_range("A1:B3", "A5")
+    assert ws["A5"].value == 10
+    assert ws["B5"].value == "=A5*2"
+    assert ws["B6"].value == "=$A$2+A5"
+    assert ws["B5"].font.bold
+    assert "A7:B7" in ws.merged_cells.ranges
+
+    ws.copy_range("B1", "C10", what="formats")
+    assert ws["C10"].value is None
+    assert ws["C10"].font.bold
+    ws.copy_range("A1:B1", "A1", what="values", target=other)
+    assert other["B1"].value == "=A1*2"
+    assert not other["B1"].font.bold
+
+    ws.move_range("A1:B2", rows=10, cols=2)
+    assert ws["A1"].value is None
+    assert ws["C11"].value == 10
+    assert ws["D11"].value == "=C11*2"
+    assert ws["D12"].value == "=$C$12+C11"
+    assert ws["D11"].font.bold
+    assert ws["D1"].value == "=SUM(C11:C12)"
+    wb.save(out)
+
+    wb2 = pyumya.load_workbook(out)
+    ws2 = wb2["Sheet1"]
+    assert ws2["C12"].value == 20
+    assert ws2["D1"].value == "=SUM(C11:C12)"
+
+
+def test_outline_grouping_roundtrip(tmp_path: Path) -> None:
+    out = tmp_path / "outline.xlsx"
+
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+
+    ws.row_dimensions.group(2, 4, hidden=True)
+    ws.row_dimensions[3].outline_level = 2
+    ws.column_dimensions.group("B", "C", outline_level=1)
+    ws.sheet_properties.outlinePr.summaryRight = False
+    assert ws.row_dimensions[2].hidden
+    assert ws.row_dimensions[5].collapsed
+    wb.save(out)
+
+    wb2 = pyumya.load_workbook(out)
+    ws2 = wb2["Sheet1"]
+    assert ws2.row_dimensions[2].outline_level == 1
+    assert ws2.row_dimensions[3].outlineLevel == 2
+    assert ws2.row_dimensions[4].hidden
+    assert ws2.row_dimensions[5].collapsed
+    assert ws2.row_dimensions[5].outline_level == 0
+    assert ws2.column_dimensions["C"].outline_level == 1
+    assert not ws2.column_dimensions["C"].hidden
+    assert ws2.sheet_properties.outlinePr.summaryBelow
+    assert not ws2.sheet_properties.outlinePr.summaryRight
+
+    ws2.insert_rows(1)
+    assert ws2.row_dimensions[4].outline_level == 2
+    assert ws2.row_dimensions[6].collapsed
+
+
+def test_autofit_columns_and_rows(tmp_path: Path) -> None:
+    out = tmp_path / "autofit.xlsx"
+
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+
+    ws["A1"].value = "a fairly long heading"
+    ws["A2"].value = "x"
+    ws["B1"].value = "short"
+    ws["B2"].value = 1234.5
+    ws["B2"].number_format = "#,##0.00"
+    ws["C1"].value = "merged across two columns"
+    ws.merge_cells("C1:D1")
+    ws["E1"].value = "bold text"
+    ws["E1"].font = pyumya.Font(bold=True)
+    ws["F1"].value = "bold text"
+
+    ws.autofit_columns()
+    assert ws.column_dimensions["A"].width > ws.column_dimensions["B"].width
+    assert ws.column_dimensions["E"].width > ws.column_dimensions["F"].width
+    assert ws.column_dimensions["C"].width is None
+    ws.autofit_columns(["A"], max_width=10)
+    assert ws.column_dimensions["A"].width == 10
+
+    ws["A3"].value = "one two three four five six seven eight nine ten"
+    ws["A3"].alignment = pyumya.Alignment(wrap_text=True)
+    ws["A4"].value = "first\nsecond"
+    ws.autofit_rows()
+    assert ws.row_dimensions[2].height == 15
+    assert ws.row_dimensions[3].height > 2 * ws.row_dimensions[4].height - 1
+    assert ws.row_dimensions[4].height == 30
+    wb.save(out)
+
+    wb2 = pyumya.load_workbook(out)
+    assert wb2["Sheet1"].column_dimensions["A"].width == 10
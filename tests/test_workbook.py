@@ -3,6 +3,8 @@
 import tempfile
 from pathlib import Path
 
+import pytest
+
 import pyumya
 
 
@@ -49,3 +51,119 @@ def test_getitem_missing_raises():
         assert False, "Should have raised KeyError"
     except KeyError:
         pass
+
+
+def test_rename_move_and_copy_sheets(tmp_path: Path):
+    wb = pyumya.Workbook()
+    data = wb.create_sheet("Data")
+    data["A1"].value = 5
+    ws = wb["Sheet1"]
+    ws["A1"].value = "=Data!A1*2"
+    ws.add_hyperlink("B1", "Data!A1", display="Go", internal=True)
+    ws.add_data_validation({"range": "C1", "validation_type": "list", "formula1": "Data!$A$1:$A$3"})
+
+    data.title = "Source Data"
+    assert wb.sheetnames == ["Sheet1", "Source Data"]
+    assert ws["A1"].value == "='Source Data'!A1*2"
+    assert ws.hyperlinks[0]["target"] == "'Source Data'!A1"
+    assert ws.data_validations[0]["formula1"] == "'Source Data'!$A$1:$A$3"
+
+    wb.move_sheet("Source Data", -1)
+    assert wb.sheetnames == ["Source Data", "Sheet1"]
+    with pytest.raises(ValueError, match="between 0 and 1"):
+        wb.move_sheet("Source Data", -1)
+
+    # A formula that cannot be read stops the rename before anything changes.
+    ws["D1"].value = '="unterminated'
+    with pytest.raises(ValueError, match="unterminated"):
+        data.title = "Inputs"
+    assert wb.sheetnames == ["Source Data", "Sheet1"]
+    assert ws["A1"].value == "='Source Data'!A1*2"
+    ws["D1"].value = None
+
+    copy = wb.copy_worksheet(ws)
+    assert wb.sheetnames == ["Source Data", "Sheet1", "Sheet1 Copy"]
+    assert copy["A1"].value == "='Source Data'!A1*2"
+
+    other = pyumya.Workbook()
+    moved = other.copy_worksheet(wb["Source Data"], title="Imported")
+    assert moved["A1"].value == 5
+
+    out = tmp_path / "sheets.xlsx"
+    wb.save(out)
+    wb2 = pyumya.load_workbook(out)
+    assert wb2.sheetnames == ["Source Data", "Sheet1", "Sheet1 Copy"]
+    assert wb2["Sheet1 Copy"]["A1"].value == "='Source Data'!A1*2"
+
+
+def test_sheet_visibility_tab_color_and_selection(tmp_path: Path):
+    wb = pyumya.Workbook()
+    for title in ("Data", "Lookup", "Notes"):
+        wb.create_sheet(title)
+    wb["Lookup"].sheet_state = "veryHidden"
+    wb["Data"].sheet_properties.tabColor = "ff0000"
+    wb.active = "Notes"
+    wb.first_visible_tab = 1
+    wb.selected_sheets = [wb["Data"], "Notes"]
+    assert wb.active.title == "Notes"
+
+    with pytest.raises(ValueError):
+        wb.selected_sheets = ["Lookup"]
+
+    out = tmp_path / "view.xlsx"
+    wb.save(out)
+    wb2 = pyumya.load_workbook(out)
+    assert wb2["Lookup"].sheet_state == "veryHidden"
+    assert wb2["Sheet1"].sheet_state == "visible"
+    assert wb2["Data"].sheet_properties.tabColor == "FF0000"
+    assert wb2["Sheet1"].sheet_properties.tabColor is None
+    assert wb2.active.title == "Notes"
+    assert wb2.first_visible_tab == 1
+    assert wb2.selected_sheets == ["Data", "Notes"]
+
+
+def test_remove_sheet_drops_its_cell_state(tmp_path: Path):
+    wb = pyumya.Workbook()
+    wb.add_named_style(pyumya.NamedStyle("Highlight", font=pyumya.Font(bold=True)))
+    old = wb.create_sheet("Data")
+    old.apply_named_style("A1", "Highlight")
+    old["B1"].value = "x2"
+    old["B1"].font = pyumya.Font(vertAlign="superscript", shadow=True)
+
+    wb.remove(old)
+    assert wb.sheetnames == ["Sheet1"]
+    new = wb.create_sheet("Data")
+    new["A1"].value = 1
+    new["B1"].value = "plain"
+    assert new["A1"].style == "Normal"
+    assert new["B1"].font.vertAlign is None
+
+    out = tmp_path / "removed.xlsx"
+    wb.save(out)
+    ws2 = pyumya.load_workbook(out)["Data"]
+    assert ws2["A1"].style == "Normal"
+    assert ws2["B1"].font.vertAlign is None
+    assert not ws2["B1"].font.shadow
+
+
+def test_save_patches_in_one_pass_and_replaces_file(tmp_path: Path):
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    wb.date1904 = True
+    ws["A1"].value = "x2"
+    ws["A1"].font = pyumya.Font(vertAlign="superscript")
+    ws.row_dimensions.group(2, 3)
+    ws.sheet_view.showZeros = False
+
+    out = tmp_path / "patched.xlsx"
+    out.write_bytes(b"not a workbook")
+    wb.save(out)
+    wb.save(out)
+    assert sorted(p.name for p in tmp_path.iterdir()) == ["patched.xlsx"]
+
+    wb2 = pyumya.load_workbook(out)
+    ws2 = wb2["Sheet1"]
+    assert wb2.date1904 is True
+    assert ws2["A1"].font.vertAlign == "superscript"
+    assert ws2.row_dimensions[2].outline_level == 1
+    assert ws2.sheet_view.showZeros is False
@@ -2,7 +2,14 @@
 
 from __future__ import annotations
 
+import zipfile
+from datetime import date, datetime
+from pathlib import Path
+
+import pytest
+
 import pyumya
+from pyumya import ArrayFormula, CellRichText, InlineFont, TextBlock
 
 
 def test_worksheet_cell_access() -> None:
@@ -44,3 +51,117 @@ def test_iter_rows_cols_and_append() -> None:
     ws.cell(row=2, column=3, value="Y")
     assert ws.max_row == 2
     assert ws.max_column == 3
+
+
+def test_append_accepts_what_cell_value_accepts() -> None:
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    rich = CellRichText("x", TextBlock(InlineFont(vertAlign="superscript"), "2"))
+
+    ws.append([1, 2])
+    ws.append([3, rich, ArrayFormula("C2", "=SUM(A1:A2)")])
+
+    assert ws["A2"].value == 3
+    assert isinstance(ws["B2"].value, CellRichText)
+    assert str(ws["B2"].value) == "x2"
+    assert ws["C2"].value == ArrayFormula("C2", "=SUM(A1:A2)")
+    assert ws.max_row == 2
+
+    # An empty row still takes up a row, as appending cell by cell did.
+    ws.append([None, None])
+    ws.append([4])
+    assert ws.max_row == 4
+    assert ws["A4"].value == 4
+
+
+def test_write_rows_replaces_rich_text_and_dynamic_arrays(tmp_path: Path) -> None:
+    out = tmp_path / "overwrite.xlsx"
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    ws["A1"].value = CellRichText("x", TextBlock(InlineFont(vertAlign="superscript"), "2"))
+    ws["B1"].value = ArrayFormula("B1:B2", "=C1:C2*2", dynamic=True)
+
+    ws.write_rows([["plain", 5]])
+    wb.save(out)
+
+    with zipfile.ZipFile(out) as z:
+        xml = z.read("xl/worksheets/sheet1.xml").decode("utf-8")
+    assert 'cm="1"' not in xml
+    ws2 = pyumya.load_workbook(out)["Sheet1"]
+    assert ws2["A1"].value == "plain"
+    assert ws2["B1"].value == 5
+
+
+def test_iter_rows_values_only() -> None:
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+
+    ws.append(["a", 1, True])
+    ws.cell(row=3, column=2, value=2.5)
+
+    assert list(ws.iter_rows(values_only=True)) == [
+        ("a", 1, True),
+        (None, None, None),
+        (None, 2.5, None),
+    ]
+    assert list(ws.iter_rows(min_row=3, min_col=2, values_only=True)) == [(2.5, None)]
+    assert list(ws.values) == list(ws.iter_rows(values_only=True))
+
+
+def test_read_range_values_payloads() -> None:
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+
+    ws["B2"].value = "x"
+    ws["C3"].value = "=B2"
+
+    grid = wb._rust.read_range_values("Sheet1", "C3:B2")
+    assert len(grid) == 2
+    assert all(len(row) == 2 for row in grid)
+    assert grid[0][0] == {"type": "string", "value": "x"}
+    assert grid[0][1] == {"type": "blank"}
+    assert grid[1][1]["type"] == "formula"
+    assert grid[1][1]["formula"] == "B2"
+
+    # Huge ranges stop at the used area; iter_rows pads past it with blanks.
+    assert len(wb._rust.read_range_values("Sheet1", "A1:XFD1048576")) == 3
+    assert wb._rust.read_range_values("Sheet1", "A10:B20") == []
+    assert list(ws.iter_rows(min_row=2, max_row=4, min_col=2, max_col=4, values_only=True)) == [
+        ("x", None, None),
+        (None, "=B2", None),
+        (None, None, None),
+    ]
+
+
+def test_write_rows_native_values(tmp_path: Path) -> None:
+    out = tmp_path / "write_rows.xlsx"
+
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    ws.write_rows(
+        [
+            ["name", 1, 2.5, True, None],
+            [date(2026, 1, 2), datetime(2026, 1, 2, 3, 4, 5), "=B1+C1", "#N/A", "x"],
+        ],
+        min_row=2,
+        min_col=2,
+    )
+    wb.save(out)
+
+    ws2 = pyumya.load_workbook(out)["Sheet1"]
+    assert list(ws2.iter_rows(min_row=2, max_row=2, min_col=2, max_col=5, values_only=True)) == [
+        ("name", 1, 2.5, True)
+    ]
+    assert ws2["B3"].value == date(2026, 1, 2)
+    assert ws2["C3"].value == datetime(2026, 1, 2, 3, 4, 5)
+    assert ws2["D3"].value == "=B1+C1"
+    assert ws2["E3"].value == "#N/A"
+    assert ws2["F3"].value == "x"
+    assert ws2["F2"].value is None
+
+
+def test_write_rows_rejects_unsupported_types() -> None:
+    wb = pyumya.Workbook()
+    ws = wb["Sheet1"]
+    with pytest.raises(TypeError):
+        ws.write_rows([[object()]])